@@ -1,5 +1,7 @@
+use std::path::Path;
+
 use agama_lib::proxies::Questions1Proxy;
-use agama_lib::questions::http_client::HTTPClient;
+use agama_lib::questions::{client::QuestionsClient, http_client::HTTPClient};
 use agama_lib::{connection, error::ServiceError};
 use clap::{Args, Subcommand, ValueEnum};
 
@@ -47,11 +49,8 @@ async fn set_mode(proxy: Questions1Proxy<'_>, value: Modes) -> Result<(), Servic
         .map_err(|e| e.into())
 }
 
-async fn set_answers(proxy: Questions1Proxy<'_>, path: String) -> Result<(), ServiceError> {
-    proxy
-        .add_answer_file(path.as_str())
-        .await
-        .map_err(|e| e.into())
+async fn set_answers(client: QuestionsClient<'_>, path: String) -> Result<(), ServiceError> {
+    client.add_answer_file(Path::new(&path)).await
 }
 
 async fn list_questions() -> Result<(), ServiceError> {
@@ -86,11 +85,11 @@ async fn ask_question() -> Result<(), ServiceError> {
 
 pub async fn run(subcommand: QuestionsCommands) -> Result<(), ServiceError> {
     let connection = connection().await?;
-    let proxy = Questions1Proxy::new(&connection).await?;
+    let client = QuestionsClient::new(&connection).await?;
 
     match subcommand {
-        QuestionsCommands::Mode(value) => set_mode(proxy, value.value).await,
-        QuestionsCommands::Answers { path } => set_answers(proxy, path).await,
+        QuestionsCommands::Mode(value) => set_mode(client.proxy().clone(), value.value).await,
+        QuestionsCommands::Answers { path } => set_answers(client, path).await,
         QuestionsCommands::List => list_questions().await,
         QuestionsCommands::Ask => ask_question().await,
     }
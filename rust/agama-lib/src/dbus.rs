@@ -44,6 +44,23 @@ where
     }
 }
 
+/// Helper to get an unsigned integer property which might be sent as a signed one.
+///
+/// Some services (e.g., the ones written in Ruby) send integers in `a{sv}` hashes as `i32` values.
+pub fn get_u32_property(
+    properties: &HashMap<String, OwnedValue>,
+    name: &str,
+) -> Result<u32, zbus::zvariant::Error> {
+    if let Ok(value) = get_property::<u32>(properties, name) {
+        return Ok(value);
+    }
+
+    let value: i32 = get_property(properties, name)?;
+    value.try_into().map_err(|_| {
+        zbus::zvariant::Error::Message(format!("Invalid value for property '{}'", name))
+    })
+}
+
 #[macro_export]
 macro_rules! property_from_dbus {
     ($self:ident, $field:ident, $key:expr, $dbus:ident, $type:ty) => {
@@ -85,7 +102,7 @@ mod tests {
 
     use zbus::zvariant::{self, OwnedValue, Str};
 
-    use crate::dbus::{get_optional_property, get_property};
+    use crate::dbus::{get_optional_property, get_property, get_u32_property};
 
     #[test]
     fn test_get_property() {
@@ -116,4 +133,17 @@ mod tests {
         let device: Option<String> = get_optional_property(&data, "Device").unwrap();
         assert_eq!(device, None);
     }
+
+    #[test]
+    fn test_get_u32_property() {
+        let data: HashMap<String, OwnedValue> = HashMap::from([
+            ("Unsigned".to_string(), 1_u32.into()),
+            ("Signed".to_string(), 2_i32.into()),
+            ("Negative".to_string(), (-1_i32).into()),
+        ]);
+        assert_eq!(get_u32_property(&data, "Unsigned"), Ok(1));
+        assert_eq!(get_u32_property(&data, "Signed"), Ok(2));
+        assert!(get_u32_property(&data, "Negative").is_err());
+        assert!(get_u32_property(&data, "Missing").is_err());
+    }
 }
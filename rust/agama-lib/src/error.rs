@@ -8,6 +8,12 @@ use zbus::{self, zvariant};
 pub enum ServiceError {
     #[error("Cannot generate Agama logs: {0}")]
     CannotGenerateLogs(String),
+    #[error("The logs archive '{0}' does not exist")]
+    MissingLogs(String),
+    #[error("The {0} was cancelled")]
+    OperationCancelled(&'static str),
+    #[error("Could not collect the logs after {0} attempts: {1}")]
+    LogsAttemptsExhausted(u32, Box<ServiceError>),
     #[error("D-Bus service error: {0}")]
     DBus(#[from] zbus::Error),
     #[error("Could not connect to Agama bus at '{0}': {1}")]
@@ -37,8 +43,31 @@ pub enum ServiceError {
     UnsuccessfulAction(String),
     #[error("Unknown installation phase: {0}")]
     UnknownInstallationPhase(u32),
+    #[error("The current installation phase ({0}) is out of range (there are {1} phases)")]
+    InstallationPhaseOutOfRange(u32, usize),
+    #[error("Unknown service status: {0}")]
+    UnknownServiceStatus(u32),
+    #[error(
+        "The installation is not possible: {}",
+        .0.iter().map(|i| i.description.as_str()).collect::<Vec<_>>().join(", ")
+    )]
+    NotInstallable(Vec<crate::issues::Issue>),
     #[error("Question with id {0} does not exist")]
     QuestionNotExist(u32),
+    #[error("Question with id {0} was not answered in time")]
+    QuestionTimeout(u32),
+    #[error("Question with id {0} was cancelled")]
+    QuestionCancelled(u32),
+    #[error("Question with id {0} has no option at index {1}")]
+    QuestionOptionOutOfRange(u32, usize),
+    #[error("Invalid question: {0}")]
+    InvalidQuestion(#[from] crate::questions::client::QuestionBuilderError),
+    #[error("Invalid question data: {0}")]
+    InvalidQuestionData(#[from] crate::questions::data::QuestionDataError),
+    #[error("Could not read the answers file '{0}': {1}")]
+    UnreadableAnswersFile(String, #[source] io::Error),
+    #[error("I/O error: {0}")]
+    IOError(#[from] io::Error),
     #[error("Backend call failed with status {0} and text '{1}'")]
     BackendError(u16, String),
     #[error("You are not logged in. Please use: agama auth login")]
@@ -0,0 +1,277 @@
+//! This module offers a typed representation of the issues reported by Agama services.
+
+use crate::{error::ServiceError, proxies::IssuesProxy};
+use serde::{Serialize, Serializer};
+use zbus::Connection;
+
+/// Origin of an issue.
+///
+/// It maps the third element of the tuples in the `All` property of the
+/// `org.opensuse.Agama1.Issues` interface. It is serialized as that number.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Source {
+    /// Unknown source.
+    Unknown,
+    /// The issue comes from the system (e.g., missing hardware).
+    System,
+    /// The issue comes from the configuration.
+    Config,
+    /// A source not known by this version (keeping the raw value).
+    Other(u32),
+}
+
+impl From<u32> for Source {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => Self::Unknown,
+            1 => Self::System,
+            2 => Self::Config,
+            _ => Self::Other(value),
+        }
+    }
+}
+
+impl From<Source> for u32 {
+    fn from(value: Source) -> Self {
+        match value {
+            Source::Unknown => 0,
+            Source::System => 1,
+            Source::Config => 2,
+            Source::Other(value) => value,
+        }
+    }
+}
+
+impl Serialize for Source {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32((*self).into())
+    }
+}
+
+/// Severity of an issue.
+///
+/// It maps the fourth element of the tuples in the `All` property of the
+/// `org.opensuse.Agama1.Issues` interface. It is serialized as that number.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    /// The issue does not block the installation.
+    Warn,
+    /// The issue blocks the installation.
+    Error,
+    /// A severity not known by this version (keeping the raw value). To be on the safe side, it
+    /// is considered to block the installation.
+    Other(u32),
+}
+
+impl Severity {
+    /// Determines whether the issue blocks the installation.
+    pub fn is_error(&self) -> bool {
+        !matches!(self, Self::Warn)
+    }
+}
+
+impl From<u32> for Severity {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => Self::Warn,
+            1 => Self::Error,
+            _ => Self::Other(value),
+        }
+    }
+}
+
+impl From<Severity> for u32 {
+    fn from(value: Severity) -> Self {
+        match value {
+            Severity::Warn => 0,
+            Severity::Error => 1,
+            Severity::Other(value) => value,
+        }
+    }
+}
+
+impl Serialize for Severity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32((*self).into())
+    }
+}
+
+/// Issue reported by an Agama service.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Issue {
+    /// Issue description.
+    pub description: String,
+    /// Additional details, if any.
+    pub details: Option<String>,
+    /// Where the issue comes from.
+    pub source: Source,
+    /// Issue severity.
+    pub severity: Severity,
+}
+
+impl From<(String, String, u32, u32)> for Issue {
+    /// Builds an issue from the D-Bus representation.
+    ///
+    /// The tuple contains the description, the details (an empty string means no details), the
+    /// source and the severity.
+    fn from((description, details, source, severity): (String, String, u32, u32)) -> Self {
+        let details = if details.is_empty() {
+            None
+        } else {
+            Some(details)
+        };
+
+        Self {
+            description,
+            details,
+            source: source.into(),
+            severity: severity.into(),
+        }
+    }
+}
+
+/// Number of issues of each severity.
+///
+/// The issues with an unknown severity are counted as errors (see [Severity::is_error]).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct IssuesCount {
+    /// Number of warnings.
+    pub warnings: usize,
+    /// Number of errors.
+    pub errors: usize,
+}
+
+impl IssuesCount {
+    /// Counts the issues of each severity.
+    ///
+    /// * `issues`: issues to count.
+    pub fn from_issues(issues: &[Issue]) -> Self {
+        let mut count = Self::default();
+        for issue in issues {
+            if issue.severity.is_error() {
+                count.errors += 1;
+            } else {
+                count.warnings += 1;
+            }
+        }
+        count
+    }
+
+    /// Determines whether there is any error (which blocks the installation).
+    pub fn has_errors(&self) -> bool {
+        self.errors > 0
+    }
+}
+
+/// D-Bus client for the `org.opensuse.Agama1.Issues` interface.
+#[derive(Clone)]
+pub struct IssuesClient<'a> {
+    proxy: IssuesProxy<'a>,
+}
+
+impl<'a> IssuesClient<'a> {
+    /// Builds a client for the issues of the given D-Bus object.
+    ///
+    /// * `connection`: D-Bus connection.
+    /// * `destination`: D-Bus service name (e.g., "org.opensuse.Agama.Software1").
+    /// * `path`: D-Bus object path (e.g., "/org/opensuse/Agama/Software1").
+    pub async fn new(
+        connection: &Connection,
+        destination: &str,
+        path: &str,
+    ) -> Result<IssuesClient<'a>, ServiceError> {
+        let proxy = IssuesProxy::builder(connection)
+            .destination(destination.to_string())?
+            .path(path.to_string())?
+            .build()
+            .await?;
+        Ok(Self { proxy })
+    }
+
+    /// Returns the current issues.
+    ///
+    /// Use the `all` method of [IssuesProxy] to get the raw values.
+    pub async fn issues(&self) -> Result<Vec<Issue>, ServiceError> {
+        let issues = self.proxy.all().await?;
+        Ok(issues.into_iter().map(Issue::from).collect())
+    }
+
+    /// Returns the issues that block the installation (see [Severity::is_error]).
+    pub async fn errors(&self) -> Result<Vec<Issue>, ServiceError> {
+        self.filter(|i| i.severity.is_error()).await
+    }
+
+    /// Returns the issues with the warning severity.
+    pub async fn warnings(&self) -> Result<Vec<Issue>, ServiceError> {
+        self.filter(|i| !i.severity.is_error()).await
+    }
+
+    /// Returns the number of issues of each severity.
+    pub async fn count_by_severity(&self) -> Result<IssuesCount, ServiceError> {
+        Ok(IssuesCount::from_issues(&self.issues().await?))
+    }
+
+    async fn filter(&self, predicate: impl Fn(&Issue) -> bool) -> Result<Vec<Issue>, ServiceError> {
+        let issues = self.issues().await?;
+        Ok(issues.into_iter().filter(predicate).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Issue, IssuesCount, Severity, Source};
+
+    #[test]
+    fn test_issue_from_tuple() {
+        let issue = Issue::from(("No products".to_string(), "".to_string(), 1, 1));
+        assert_eq!(issue.description, "No products");
+        assert_eq!(issue.details, None);
+        assert_eq!(issue.source, Source::System);
+        assert_eq!(issue.severity, Severity::Error);
+
+        let issue = Issue::from(("No root".to_string(), "Add a root device".to_string(), 2, 0));
+        assert_eq!(issue.details.as_deref(), Some("Add a root device"));
+        assert_eq!(issue.source, Source::Config);
+        assert_eq!(issue.severity, Severity::Warn);
+    }
+
+    #[test]
+    fn test_issue_from_unknown_values() {
+        let issue = Issue::from(("Error".to_string(), "".to_string(), 5, 3));
+        assert_eq!(issue.source, Source::Other(5));
+        assert_eq!(issue.severity, Severity::Other(3));
+        assert!(issue.severity.is_error());
+        assert_eq!(u32::from(issue.source), 5);
+        assert_eq!(u32::from(issue.severity), 3);
+    }
+
+    #[test]
+    fn test_issue_serialization() {
+        let issue = Issue::from(("Error".to_string(), "".to_string(), 0, 1));
+        assert_eq!(
+            serde_json::to_string(&issue).unwrap(),
+            r#"{"description":"Error","details":null,"source":0,"severity":1}"#
+        );
+
+        let issue = Issue::from(("Error".to_string(), "".to_string(), 5, 3));
+        assert_eq!(
+            serde_json::to_string(&issue).unwrap(),
+            r#"{"description":"Error","details":null,"source":5,"severity":3}"#
+        );
+    }
+
+    #[test]
+    fn test_issues_count() {
+        let issues: Vec<Issue> = [(0, 1), (1, 0), (2, 0), (1, 7)]
+            .into_iter()
+            .map(|(source, severity)| {
+                Issue::from(("Issue".to_string(), "".to_string(), source, severity))
+            })
+            .collect();
+        let count = IssuesCount::from_issues(&issues);
+        assert_eq!(count.warnings, 2);
+        assert_eq!(count.errors, 2);
+        assert!(count.has_errors());
+        assert!(!IssuesCount::from_issues(&issues[1..3]).has_errors());
+    }
+}
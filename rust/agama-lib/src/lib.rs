@@ -27,11 +27,13 @@ pub mod auth;
 pub mod base_http_client;
 pub mod error;
 pub mod install_settings;
+pub mod issues;
 pub mod localization;
 pub mod manager;
 pub mod network;
 pub mod product;
 pub mod profile;
+pub mod service_status;
 pub mod software;
 pub mod storage;
 pub mod users;
@@ -39,6 +41,7 @@ pub mod users;
 pub mod dbus;
 pub mod progress;
 pub mod proxies;
+pub mod reconnect;
 mod store;
 pub use store::Store;
 pub mod questions;
@@ -1,9 +1,11 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize, utoipa::ToSchema)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LocaleConfig {
-    /// Locales to install in the target system
+    /// Locales to install in the target system. They can be written as "en_US.UTF-8", "en_US" or
+    /// "en-US", but they are always reported in the canonical form ("en_US.UTF-8"). The first one
+    /// is the primary locale of the system. The list cannot be empty or contain duplicates.
     pub locales: Option<Vec<String>>,
     /// Keymap for the target system
     pub keymap: Option<String>,
@@ -11,6 +13,29 @@ pub struct LocaleConfig {
     pub timezone: Option<String>,
     /// User-interface locale. It is actually not related to the `locales` property.
     pub ui_locale: Option<String>,
-    /// User-interface locale. It is relevant only on local installations.
+    /// User-interface keymap. It is relevant only on local installations.
     pub ui_keymap: Option<String>,
+    /// XKB options for the user-interface keyboard (e.g., "numpad:mac" to make the numeric keypad
+    /// always produce digits, as if Num Lock were always on). Only some options are supported.
+    pub ui_keyboard_options: Option<Vec<String>>,
+    /// Whether the hardware clock (RTC) keeps the time in UTC (`true`) or in local time
+    /// (`false`). Local time is usually needed when dual-booting with Windows.
+    pub rtc_utc: Option<bool>,
+    /// NTP servers to synchronize the time with. Each one must be a hostname (e.g.,
+    /// "pool.ntp.org") or an IP address.
+    pub ntp_servers: Option<Vec<String>>,
+}
+
+impl LocaleConfig {
+    /// Names of the attributes, as they are written in JSON.
+    pub const FIELDS: [&'static str; 8] = [
+        "locales",
+        "keymap",
+        "timezone",
+        "uiLocale",
+        "uiKeymap",
+        "uiKeyboardOptions",
+        "rtcUtc",
+        "ntpServers",
+    ];
 }
@@ -1,10 +1,11 @@
 //! Representation of the localization settings
 
+use super::model::LocaleConfig;
 use serde::{Deserialize, Serialize};
 
 /// Localization settings for the system being installed (not the UI)
 /// FIXME: this one is close to CLI. A possible duplicate close to HTTP is LocaleConfig
-#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LocalizationSettings {
     /// like "en_US.UTF-8"
@@ -14,3 +15,18 @@ pub struct LocalizationSettings {
     /// like "Europe/Berlin"
     pub timezone: Option<String>,
 }
+
+impl From<LocalizationSettings> for LocaleConfig {
+    /// Maps the profile settings to the localization configuration.
+    ///
+    /// The `language` becomes the only element of `locales` and the `keyboard` becomes the
+    /// `keymap`. The user interface settings are not included.
+    fn from(settings: LocalizationSettings) -> Self {
+        LocaleConfig {
+            locales: settings.language.map(|l| vec![l]),
+            keymap: settings.keyboard,
+            timezone: settings.timezone,
+            ..Default::default()
+        }
+    }
+}
@@ -50,18 +50,7 @@ impl LocalizationStore {
     }
 
     pub async fn store(&self, settings: &LocalizationSettings) -> Result<(), ServiceError> {
-        // clones are necessary as we have different structs owning their data
-        let opt_language = settings.language.clone();
-        let opt_keymap = settings.keyboard.clone();
-        let opt_timezone = settings.timezone.clone();
-
-        let config = LocaleConfig {
-            locales: opt_language.map(|s| vec![s]),
-            keymap: opt_keymap,
-            timezone: opt_timezone,
-            ui_locale: None,
-            ui_keymap: None,
-        };
+        let config = LocaleConfig::from(settings.clone());
         self.localization_client.set_config(&config).await
     }
 }
@@ -125,7 +114,7 @@ mod test {
                 .path("/api/l10n/config")
                 .header("content-type", "application/json")
                 .body(
-                    r#"{"locales":["fr_FR.UTF-8"],"keymap":"fr(dvorak)","timezone":"Europe/Paris","uiLocale":null,"uiKeymap":null}"#
+                    r#"{"locales":["fr_FR.UTF-8"],"keymap":"fr(dvorak)","timezone":"Europe/Paris","uiLocale":null,"uiKeymap":null,"uiKeyboardOptions":null,"rtcUtc":null,"ntpServers":null}"#
                 );
             then.status(204);
         });
@@ -1,14 +1,50 @@
 //! This module implements the web API for the manager module.
 
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    pin::Pin,
+    time::Duration,
+};
+
+use crate::dbus::{get_property, get_u32_property};
 use crate::error::ServiceError;
 use crate::proxies::ServiceStatusProxy;
 use crate::{
+    issues::{Issue, IssuesClient},
     progress::Progress,
-    proxies::{Manager1Proxy, ProgressProxy},
+    proxies::{IssuesProxy, Manager1Proxy, ProgressProxy},
+    reconnect::{is_connection_error, Backoff},
 };
+use futures_util::future::try_join_all;
+use serde::Serialize;
 use serde_repr::Serialize_repr;
-use tokio_stream::StreamExt;
-use zbus::Connection;
+use std::future::Future;
+use tokio_stream::{wrappers::IntervalStream, Stream, StreamExt, StreamMap};
+use tokio_util::sync::CancellationToken;
+use zbus::{zvariant::OwnedValue, Connection};
+
+/// D-Bus objects reporting issues (service name and object path).
+///
+/// They are checked by [ManagerClient::wait_until_installable].
+pub const ISSUES_OBJECTS: [(&str, &str); 4] = [
+    (
+        "org.opensuse.Agama.Storage1",
+        "/org/opensuse/Agama/Storage1",
+    ),
+    (
+        "org.opensuse.Agama.Software1",
+        "/org/opensuse/Agama/Software1",
+    ),
+    (
+        "org.opensuse.Agama.Software1",
+        "/org/opensuse/Agama/Software1/Product",
+    ),
+    ("org.opensuse.Agama.Manager1", "/org/opensuse/Agama/Users1"),
+];
+
+/// Interval to check whether the installation is possible.
+const INSTALLABLE_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
 /// D-Bus client for the manager service
 #[derive(Clone)]
@@ -19,16 +55,35 @@ pub struct ManagerClient<'a> {
 }
 
 /// Represents the installation phase.
-/// NOTE: does this conversion have any value?
+///
+/// The numeric values match the `id` of each entry in the `InstallationPhases` D-Bus property,
+/// and [InstallationPhase::label] matches its `label`:
+///
+/// | id | label     | variant                       |
+/// |----|-----------|-------------------------------|
+/// | 0  | "startup" | [InstallationPhase::Startup]  |
+/// | 1  | "config"  | [InstallationPhase::Config]   |
+/// | 2  | "install" | [InstallationPhase::Install]  |
 #[derive(Clone, Copy, Debug, PartialEq, Serialize_repr, utoipa::ToSchema)]
 #[repr(u32)]
 pub enum InstallationPhase {
     /// Start up phase.
-    Startup,
+    Startup = 0,
     /// Configuration phase.
-    Config,
+    Config = 1,
     /// Installation phase.
-    Install,
+    Install = 2,
+}
+
+impl InstallationPhase {
+    /// Returns the label of the phase as reported by the `InstallationPhases` D-Bus property.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Startup => "startup",
+            Self::Config => "config",
+            Self::Install => "install",
+        }
+    }
 }
 
 impl TryFrom<u32> for InstallationPhase {
@@ -44,6 +99,247 @@ impl TryFrom<u32> for InstallationPhase {
     }
 }
 
+/// Describes an installation phase as reported by the `InstallationPhases` D-Bus property.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PhaseDescriptor {
+    /// Phase ID (see [InstallationPhase]).
+    pub id: u32,
+    /// Phase label (e.g., "startup").
+    pub label: String,
+}
+
+impl PhaseDescriptor {
+    /// Returns the installation phase described by this entry.
+    pub fn phase(&self) -> Result<InstallationPhase, ServiceError> {
+        self.id.try_into()
+    }
+}
+
+impl TryFrom<&HashMap<String, OwnedValue>> for PhaseDescriptor {
+    type Error = ServiceError;
+
+    fn try_from(value: &HashMap<String, OwnedValue>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: get_u32_property(value, "id")?,
+            label: get_property(value, "label")?,
+        })
+    }
+}
+
+/// Installation phase along with its position in the installation process.
+///
+/// See [ManagerClient::ordered_installation_phases].
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrderedPhase {
+    /// Position of the phase, starting at 0.
+    pub index: usize,
+    /// Phase descriptor.
+    pub descriptor: PhaseDescriptor,
+}
+
+// Sorts the phases in the order they happen (by ID), numbering them.
+fn order_phases(mut phases: Vec<PhaseDescriptor>) -> Vec<OrderedPhase> {
+    phases.sort_by_key(|p| p.id);
+    phases
+        .into_iter()
+        .enumerate()
+        .map(|(index, descriptor)| OrderedPhase { index, descriptor })
+        .collect()
+}
+
+// Returns the phase with the given ID from the ordered list.
+fn find_phase(phases: Vec<OrderedPhase>, id: u32) -> Result<OrderedPhase, ServiceError> {
+    let count = phases.len();
+    phases
+        .into_iter()
+        .find(|p| p.descriptor.id == id)
+        .ok_or(ServiceError::InstallationPhaseOutOfRange(id, count))
+}
+
+/// Point-in-time snapshot of the manager status.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagerStatus {
+    /// Services which are busy.
+    pub busy_services: Vec<String>,
+    /// Current installation phase.
+    pub phase: InstallationPhase,
+    /// Whether it is possible to start the installation.
+    pub can_install: bool,
+}
+
+/// Backend the installer runs on, as reported by the `IguanaBackend` D-Bus property.
+///
+/// It determines what happens when the installation finishes (see [ManagerClient::finish]):
+///
+/// * [Backend::Standard]: the machine is rebooted (`shutdown -r now`).
+/// * [Backend::Iguana]: Agama is stopped (`agamactl -k`) and Iguana boots the installed system
+///   using the mount list written by the storage service. The installer environment (including
+///   the logs the service stores in `/tmp/var/logs` when finishing) is discarded, so the callers
+///   which want to keep the logs must collect them (see [ManagerClient::collect_logs_to]) before
+///   finishing.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Backend {
+    /// Iguana (initrd-based) environment.
+    Iguana,
+    /// Regular live medium.
+    Standard,
+}
+
+impl From<bool> for Backend {
+    /// Builds the backend from the `IguanaBackend` D-Bus property.
+    fn from(iguana: bool) -> Self {
+        if iguana {
+            Self::Iguana
+        } else {
+            Self::Standard
+        }
+    }
+}
+
+impl Backend {
+    /// Whether it is the Iguana backend.
+    pub fn is_iguana(&self) -> bool {
+        matches!(self, Self::Iguana)
+    }
+
+    /// Command the manager service runs to finish the installation.
+    pub fn finish_command(&self) -> &'static str {
+        match self {
+            Self::Iguana => "/usr/bin/agamactl -k",
+            Self::Standard => "/usr/sbin/shutdown -r now",
+        }
+    }
+}
+
+/// Outcome of [ManagerClient::finish_with_confirmation].
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FinishOutcome {
+    /// The request was accepted and the connection was closed because the system is going down.
+    ShuttingDown,
+    /// The request was accepted, but the connection was not closed within the grace period.
+    Accepted,
+}
+
+/// Reason why the installation cannot start (see [InstallReadiness]).
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "reason", rename_all = "camelCase")]
+pub enum NotReadyReason {
+    /// The manager service reports that it is not possible to install.
+    CannotInstall,
+    /// The installer is not in the configuration phase.
+    WrongPhase { phase: InstallationPhase },
+    /// Some services are busy.
+    BusyServices { services: Vec<String> },
+    /// There are issues blocking the installation.
+    BlockingIssues { count: usize },
+}
+
+/// Summary of whether the installation can start.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallReadiness {
+    /// Whether the installation can start (there are no reasons preventing it).
+    pub ready: bool,
+    /// Whether the manager service reports that it is possible to install.
+    pub can_install: bool,
+    /// Current installation phase.
+    pub phase: InstallationPhase,
+    /// Services which are busy.
+    pub busy_services: Vec<String>,
+    /// Issues which block the installation.
+    pub issues: Vec<Issue>,
+    /// Reasons why the installation cannot start.
+    pub reasons: Vec<NotReadyReason>,
+}
+
+impl InstallReadiness {
+    /// Builds the report from the manager status and the blocking issues.
+    ///
+    /// * `status`: manager status.
+    /// * `issues`: issues which block the installation.
+    pub fn new(status: ManagerStatus, issues: Vec<Issue>) -> Self {
+        let mut reasons = vec![];
+        if status.phase != InstallationPhase::Config {
+            reasons.push(NotReadyReason::WrongPhase {
+                phase: status.phase,
+            });
+        }
+        if !status.busy_services.is_empty() {
+            reasons.push(NotReadyReason::BusyServices {
+                services: status.busy_services.clone(),
+            });
+        }
+        if !issues.is_empty() {
+            reasons.push(NotReadyReason::BlockingIssues {
+                count: issues.len(),
+            });
+        }
+        if !status.can_install {
+            reasons.push(NotReadyReason::CannotInstall);
+        }
+
+        Self {
+            ready: reasons.is_empty(),
+            can_install: status.can_install,
+            phase: status.phase,
+            busy_services: status.busy_services,
+            issues,
+            reasons,
+        }
+    }
+}
+
+/// Number of attempts to collect the logs when the backoff does not limit them.
+pub const DEFAULT_LOGS_ATTEMPTS: u32 = 3;
+
+// Runs the given operation until it succeeds, waiting between the attempts as set by `backoff`.
+//
+// It gives up after `attempts` attempts, returning the last error.
+async fn retry_logs<T, F, Fut>(
+    backoff: &Backoff,
+    attempts: u32,
+    mut op: F,
+) -> Result<T, ServiceError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ServiceError>>,
+{
+    let attempts = attempts.max(1);
+    let mut attempt = 0;
+    loop {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(error) if attempt + 1 >= attempts => {
+                return Err(ServiceError::LogsAttemptsExhausted(
+                    attempts,
+                    Box::new(error),
+                ))
+            }
+            Err(error) => {
+                log::warn!(
+                    "Could not collect the logs (attempt {}): {}",
+                    attempt + 1,
+                    error
+                );
+                tokio::time::sleep(backoff.delay(attempt)).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Logs archive copied to a local path.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogsArchive {
+    /// Local path of the archive.
+    pub path: PathBuf,
+    /// Size of the archive in bytes.
+    pub size: u64,
+}
+
 impl<'a> ManagerClient<'a> {
     pub async fn new(connection: Connection) -> zbus::Result<ManagerClient<'a>> {
         Ok(Self {
@@ -59,37 +355,376 @@ impl<'a> ManagerClient<'a> {
     }
 
     /// Returns the current installation phase.
+    ///
+    /// Use the `current_installation_phase` method of [Manager1Proxy] to get the raw value.
     pub async fn current_installation_phase(&self) -> Result<InstallationPhase, ServiceError> {
         let phase = self.manager_proxy.current_installation_phase().await?;
         phase.try_into()
     }
 
+    /// Returns the list of installation phases.
+    ///
+    /// Use the `installation_phases` method of [Manager1Proxy] to get the raw values.
+    pub async fn installation_phases(&self) -> Result<Vec<PhaseDescriptor>, ServiceError> {
+        let phases = self.manager_proxy.installation_phases().await?;
+        phases.iter().map(PhaseDescriptor::try_from).collect()
+    }
+
+    /// Returns the list of installation phases in the order they happen.
+    ///
+    /// Each phase carries its position, so it can be used to draw a progress indicator.
+    pub async fn ordered_installation_phases(&self) -> Result<Vec<OrderedPhase>, ServiceError> {
+        Ok(order_phases(self.installation_phases().await?))
+    }
+
+    /// Returns the descriptor of the current installation phase, along with its position.
+    ///
+    /// It returns a [ServiceError::InstallationPhaseOutOfRange] error if the current phase is not
+    /// included in the list of phases.
+    pub async fn current_phase_descriptor(&self) -> Result<OrderedPhase, ServiceError> {
+        let current = async {
+            let current = self.manager_proxy.current_installation_phase().await;
+            current.map_err(ServiceError::from)
+        };
+        let (current, phases) = tokio::try_join!(current, self.ordered_installation_phases())?;
+        find_phase(phases, current)
+    }
+
+    /// Returns a snapshot of the manager status.
+    ///
+    /// The busy services, the installation phase and whether it is possible to install are
+    /// fetched concurrently, so the snapshot reflects a single point in time.
+    pub async fn status_snapshot(&self) -> Result<ManagerStatus, ServiceError> {
+        let (busy_services, phase, can_install) = tokio::try_join!(
+            self.busy_services(),
+            self.current_installation_phase(),
+            self.can_install()
+        )?;
+        Ok(ManagerStatus {
+            busy_services,
+            phase,
+            can_install,
+        })
+    }
+
+    /// Collects the logs and copies the resulting archive to the given path.
+    ///
+    /// If the destination is a directory, the archive is copied into it keeping its name. If the
+    /// archive cannot be copied, it returns a [ServiceError::IOError] error.
+    ///
+    /// * `destination`: local path to copy the archive to.
+    pub async fn collect_logs_to(&self, destination: &Path) -> Result<LogsArchive, ServiceError> {
+        let source = PathBuf::from(self.manager_proxy.collect_logs().await?);
+        if !tokio::fs::try_exists(&source).await.unwrap_or(false) {
+            return Err(ServiceError::MissingLogs(source.display().to_string()));
+        }
+
+        let path = match source.file_name() {
+            Some(name) if destination.is_dir() => destination.join(name),
+            _ => destination.to_path_buf(),
+        };
+        let size = tokio::fs::copy(&source, &path).await?;
+        Ok(LogsArchive { path, size })
+    }
+
+    /// Collects the logs like [Self::collect_logs_to], retrying when it fails.
+    ///
+    /// Collecting the logs does not change the system, so it is safe to repeat. The number of
+    /// attempts is limited by `backoff.max_attempts` or, if it is not set, by
+    /// [DEFAULT_LOGS_ATTEMPTS]. If all of them fail, it returns
+    /// [ServiceError::LogsAttemptsExhausted] with the last error.
+    ///
+    /// * `destination`: local path to copy the archive to.
+    /// * `backoff`: delays between the attempts.
+    pub async fn collect_logs_with_retry(
+        &self,
+        destination: &Path,
+        backoff: &Backoff,
+    ) -> Result<LogsArchive, ServiceError> {
+        let attempts = backoff.max_attempts.unwrap_or(DEFAULT_LOGS_ATTEMPTS);
+        retry_logs(backoff, attempts, || self.collect_logs_to(destination)).await
+    }
+
     /// Starts the probing process.
     pub async fn probe(&self) -> Result<(), ServiceError> {
         self.wait().await?;
         Ok(self.manager_proxy.probe().await?)
     }
 
+    /// Starts the probing process and reports the progress until it finishes.
+    ///
+    /// As the `Probe` D-Bus call returns before the probing finishes, it keeps reporting the
+    /// progress until the manager is idle again or the progress is finished. Like
+    /// [ManagerClient::commit_with_progress], it ignores the (already finished) progress of a
+    /// previous operation and reports the final progress once the probing finishes. An error
+    /// from the `Probe` call is returned as soon as it happens.
+    ///
+    /// * `on_progress`: function to call with each progress update.
+    pub async fn probe_with_progress<F>(&self, on_progress: F) -> Result<(), ServiceError>
+    where
+        F: FnMut(&Progress),
+    {
+        self.probe_with_cancel(on_progress, CancellationToken::new())
+            .await
+    }
+
+    /// Starts the probing process and reports the progress until it finishes or it is cancelled.
+    ///
+    /// It behaves like [ManagerClient::probe_with_progress], but it stops waiting when the token
+    /// is cancelled, returning a [ServiceError::OperationCancelled] error. If the token is
+    /// cancelled while the service is still busy with a previous operation, the probing is not
+    /// started at all. Otherwise, as the Manager D-Bus interface does not offer a way to abort the
+    /// probing, the cancellation only affects the client: the service keeps probing and it
+    /// remains busy until it finishes.
+    ///
+    /// * `on_progress`: function to call with each progress update.
+    /// * `cancel`: token to cancel the operation.
+    pub async fn probe_with_cancel<F>(
+        &self,
+        mut on_progress: F,
+        cancel: CancellationToken,
+    ) -> Result<(), ServiceError>
+    where
+        F: FnMut(&Progress),
+    {
+        tokio::select! {
+            biased;
+            _ = cancel.cancelled() => return Err(ServiceError::OperationCancelled("probing")),
+            result = self.wait() => result?,
+        }
+        let mut updates = Progress::changes_from_proxy(self.progress_proxy.clone())
+            .await
+            .fuse();
+        let mut status = self.status_proxy.receive_current_changed().await.fuse();
+        let probe = self.manager_proxy.probe();
+        tokio::pin!(probe);
+
+        let mut probed = false;
+        let mut last: Option<Progress> = None;
+        loop {
+            tokio::select! {
+                result = &mut probe, if !probed => {
+                    result?;
+                    probed = true;
+                    if !self.is_busy().await {
+                        break;
+                    }
+                }
+                Some(progress) = updates.next() => {
+                    if last.is_none() && progress.finished {
+                        continue;
+                    }
+                    let finished = progress.finished;
+                    on_progress(&progress);
+                    last = Some(progress);
+                    if probed && finished {
+                        break;
+                    }
+                }
+                Some(change) = status.next() => {
+                    if probed && change.get().await? == 0 {
+                        break;
+                    }
+                }
+                _ = cancel.cancelled() => return Err(ServiceError::OperationCancelled("probing")),
+                else => break,
+            }
+        }
+
+        let progress = self.progress().await?;
+        if last.as_ref() != Some(&progress) {
+            on_progress(&progress);
+        }
+        Ok(())
+    }
+
     /// Starts the installation.
     pub async fn install(&self) -> Result<(), ServiceError> {
         Ok(self.manager_proxy.commit().await?)
     }
 
+    /// Starts the installation and reports the progress until it finishes.
+    ///
+    /// Progress changes are reported while the installation runs, ignoring the (already finished)
+    /// progress of a previous operation. Once the installation finishes, the final progress is
+    /// reported too. Errors reading intermediate progress values are ignored, but an error from
+    /// the installation itself is returned as soon as it happens.
+    ///
+    /// * `on_progress`: function to call with each progress update.
+    pub async fn commit_with_progress<F>(&self, on_progress: F) -> Result<(), ServiceError>
+    where
+        F: FnMut(&Progress),
+    {
+        self.commit_with_cancel(on_progress, CancellationToken::new())
+            .await
+    }
+
+    /// Starts the installation and reports the progress until it finishes or it is cancelled.
+    ///
+    /// It behaves like [ManagerClient::commit_with_progress], but it stops waiting when the token
+    /// is cancelled, returning a [ServiceError::OperationCancelled] error. The Manager D-Bus
+    /// interface does not offer a way to abort the installation, so the cancellation only
+    /// affects the client: the service keeps installing the system.
+    ///
+    /// * `on_progress`: function to call with each progress update.
+    /// * `cancel`: token to cancel the operation.
+    pub async fn commit_with_cancel<F>(
+        &self,
+        mut on_progress: F,
+        cancel: CancellationToken,
+    ) -> Result<(), ServiceError>
+    where
+        F: FnMut(&Progress),
+    {
+        let mut updates = Progress::changes_from_proxy(self.progress_proxy.clone())
+            .await
+            .fuse();
+        let commit = self.manager_proxy.commit();
+        tokio::pin!(commit);
+
+        let mut last: Option<Progress> = None;
+        let result = loop {
+            tokio::select! {
+                result = &mut commit => break result,
+                _ = cancel.cancelled() => {
+                    return Err(ServiceError::OperationCancelled("installation"))
+                }
+                Some(progress) = updates.next() => {
+                    if last.is_none() && progress.finished {
+                        continue;
+                    }
+                    on_progress(&progress);
+                    last = Some(progress);
+                }
+            }
+        };
+        result?;
+
+        let progress = self.progress().await?;
+        if last.as_ref() != Some(&progress) {
+            on_progress(&progress);
+        }
+        Ok(())
+    }
+
     /// Executes the after installation tasks.
     pub async fn finish(&self) -> Result<(), ServiceError> {
         Ok(self.manager_proxy.finish().await?)
     }
 
+    /// Executes the after installation tasks, telling whether the system is going down.
+    ///
+    /// The `Finish` D-Bus call reboots the system (or exits Iguana), so the connection is
+    /// expected to be closed. Hence, losing the connection (even during the call) is considered a
+    /// success ([FinishOutcome::ShuttingDown]). If the call succeeds but the connection is not
+    /// closed within the grace period, it returns [FinishOutcome::Accepted]. Note there is no
+    /// installation phase after [InstallationPhase::Install] to watch for.
+    ///
+    /// * `grace`: maximum time to wait for the connection to be closed.
+    pub async fn finish_with_confirmation(
+        &self,
+        grace: Duration,
+    ) -> Result<FinishOutcome, ServiceError> {
+        // the stream finishes when the connection is closed
+        let mut changes = self
+            .manager_proxy
+            .receive_current_installation_phase_changed()
+            .await;
+
+        match self.manager_proxy.finish().await {
+            Ok(()) => {}
+            Err(error) if is_connection_error(&error) => {
+                log::info!(
+                    "Connection lost while finishing the installation: {}",
+                    error
+                );
+                return Ok(FinishOutcome::ShuttingDown);
+            }
+            Err(error) => return Err(error.into()),
+        }
+
+        let closed = async { while changes.next().await.is_some() {} };
+        match tokio::time::timeout(grace, closed).await {
+            Ok(()) => Ok(FinishOutcome::ShuttingDown),
+            Err(_) => Ok(FinishOutcome::Accepted),
+        }
+    }
+
     /// Determines whether it is possible to start the installation.
     pub async fn can_install(&self) -> Result<bool, ServiceError> {
         Ok(self.manager_proxy.can_install().await?)
     }
 
+    /// Waits until it is possible to start the installation.
+    ///
+    /// It checks `CanInstall` periodically and whenever the issues of any service (see
+    /// [ISSUES_OBJECTS]) change. If it is not possible to install after the given timeout, it
+    /// returns a [ServiceError::NotInstallable] error including the issues which block the
+    /// installation.
+    ///
+    /// * `timeout`: maximum time to wait.
+    pub async fn wait_until_installable(&self, timeout: Duration) -> Result<(), ServiceError> {
+        let connection = self.manager_proxy.connection();
+        let mut changes: StreamMap<usize, Pin<Box<dyn Stream<Item = ()> + Send>>> =
+            StreamMap::new();
+        for (index, (destination, path)) in ISSUES_OBJECTS.iter().enumerate() {
+            let proxy = IssuesProxy::builder(connection)
+                .destination(*destination)?
+                .path(*path)?
+                .build()
+                .await?;
+            let stream = proxy.receive_all_changed().await.map(|_| ());
+            changes.insert(index, Box::pin(stream));
+        }
+        let ticks = IntervalStream::new(tokio::time::interval(INSTALLABLE_POLL_INTERVAL));
+        changes.insert(ISSUES_OBJECTS.len(), Box::pin(ticks.map(|_| ())));
+
+        let wait = async {
+            while changes.next().await.is_some() {
+                if self.can_install().await? {
+                    return Ok(());
+                }
+            }
+            Ok::<(), ServiceError>(())
+        };
+        if let Ok(result) = tokio::time::timeout(timeout, wait).await {
+            return result;
+        }
+
+        Err(ServiceError::NotInstallable(self.blocking_issues().await?))
+    }
+
+    /// Returns the issues which block the installation (errors).
+    ///
+    /// It collects the issues from all the services (see [ISSUES_OBJECTS]) concurrently.
+    pub async fn blocking_issues(&self) -> Result<Vec<Issue>, ServiceError> {
+        let connection = self.manager_proxy.connection();
+        let errors = ISSUES_OBJECTS.iter().map(|(destination, path)| async move {
+            let client = IssuesClient::new(connection, destination, path).await?;
+            client.errors().await
+        });
+        let issues = try_join_all(errors).await?;
+        Ok(issues.into_iter().flatten().collect())
+    }
+
+    /// Returns a report telling whether the installation can start and, if not, why.
+    ///
+    /// The manager status and the issues are fetched concurrently.
+    pub async fn install_readiness(&self) -> Result<InstallReadiness, ServiceError> {
+        let (status, issues) = tokio::try_join!(self.status_snapshot(), self.blocking_issues())?;
+        Ok(InstallReadiness::new(status, issues))
+    }
+
     /// Determines whether the installer is running on Iguana.
     pub async fn use_iguana(&self) -> Result<bool, ServiceError> {
         Ok(self.manager_proxy.iguana_backend().await?)
     }
 
+    /// Returns the backend the installer runs on.
+    pub async fn backend(&self) -> Result<Backend, ServiceError> {
+        Ok(self.use_iguana().await?.into())
+    }
+
     /// Returns the current progress.
     pub async fn progress(&self) -> zbus::Result<Progress> {
         Progress::from_proxy(&self.progress_proxy).await
@@ -120,3 +755,154 @@ impl<'a> ManagerClient<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        find_phase, order_phases, retry_logs, Backend, InstallReadiness, InstallationPhase,
+        ManagerStatus, NotReadyReason, PhaseDescriptor,
+    };
+    use crate::dbus::to_owned_hash;
+    use crate::error::ServiceError;
+    use crate::issues::Issue;
+    use crate::reconnect::Backoff;
+    use std::{collections::HashMap, time::Duration};
+    use zbus::zvariant::Value;
+
+    #[tokio::test]
+    async fn test_retry_logs() {
+        let backoff = Backoff {
+            initial: Duration::ZERO,
+            ..Default::default()
+        };
+
+        let mut calls = 0;
+        let result = retry_logs(&backoff, 3, || {
+            calls += 1;
+            let result = if calls < 3 {
+                Err(ServiceError::MissingLogs("/tmp/logs".to_string()))
+            } else {
+                Ok(calls)
+            };
+            async move { result }
+        })
+        .await;
+        assert_eq!(result.unwrap(), 3);
+
+        let mut calls = 0;
+        let result: Result<(), _> = retry_logs(&backoff, 2, || {
+            calls += 1;
+            async { Err(ServiceError::MissingLogs("/tmp/logs".to_string())) }
+        })
+        .await;
+        assert_eq!(calls, 2);
+        assert!(matches!(
+            result,
+            Err(ServiceError::LogsAttemptsExhausted(2, error))
+                if matches!(*error, ServiceError::MissingLogs(_))
+        ));
+    }
+
+    #[test]
+    fn test_installation_phase_try_from() {
+        for phase in [
+            InstallationPhase::Startup,
+            InstallationPhase::Config,
+            InstallationPhase::Install,
+        ] {
+            assert_eq!(InstallationPhase::try_from(phase as u32).unwrap(), phase);
+        }
+        assert!(InstallationPhase::try_from(3).is_err());
+    }
+
+    #[test]
+    fn test_ordered_phases() {
+        let descriptor = |id: u32, label: &str| PhaseDescriptor {
+            id,
+            label: label.to_string(),
+        };
+        let phases = order_phases(vec![
+            descriptor(2, "install"),
+            descriptor(0, "startup"),
+            descriptor(1, "config"),
+        ]);
+        let labels: Vec<_> = phases
+            .iter()
+            .map(|p| (p.index, p.descriptor.label.as_str()))
+            .collect();
+        assert_eq!(labels, vec![(0, "startup"), (1, "config"), (2, "install")]);
+
+        let current = find_phase(phases.clone(), 1).unwrap();
+        assert_eq!(current.index, 1);
+        assert_eq!(current.descriptor.label, "config");
+        assert!(matches!(
+            find_phase(phases, 3),
+            Err(ServiceError::InstallationPhaseOutOfRange(3, 3))
+        ));
+    }
+
+    #[test]
+    fn test_install_readiness() {
+        let status = ManagerStatus {
+            busy_services: vec![],
+            phase: InstallationPhase::Config,
+            can_install: true,
+        };
+        let readiness = InstallReadiness::new(status.clone(), vec![]);
+        assert!(readiness.ready);
+        assert!(readiness.reasons.is_empty());
+
+        let busy = ManagerStatus {
+            busy_services: vec!["org.opensuse.Agama.Storage1".to_string()],
+            can_install: false,
+            ..status
+        };
+        let issue = Issue::from(("No root".to_string(), "".to_string(), 2, 1));
+        let readiness = InstallReadiness::new(busy, vec![issue]);
+        assert!(!readiness.ready);
+        assert_eq!(
+            readiness.reasons,
+            vec![
+                NotReadyReason::BusyServices {
+                    services: vec!["org.opensuse.Agama.Storage1".to_string()]
+                },
+                NotReadyReason::BlockingIssues { count: 1 },
+                NotReadyReason::CannotInstall
+            ]
+        );
+    }
+
+    #[test]
+    fn test_backend_from_bool() {
+        assert_eq!(Backend::from(true), Backend::Iguana);
+        assert!(Backend::from(true).is_iguana());
+        assert_eq!(Backend::from(false), Backend::Standard);
+        assert!(!Backend::Standard.is_iguana());
+    }
+
+    #[test]
+    fn test_installation_phase_label() {
+        assert_eq!(InstallationPhase::Startup.label(), "startup");
+        assert_eq!(InstallationPhase::Config.label(), "config");
+        assert_eq!(InstallationPhase::Install.label(), "install");
+    }
+
+    #[test]
+    fn test_phase_descriptor_try_from() {
+        let phase = HashMap::from([("id", Value::new(1_u32)), ("label", Value::new("config"))]);
+        let descriptor = PhaseDescriptor::try_from(&to_owned_hash(&phase)).unwrap();
+        assert_eq!(descriptor.id, 1);
+        assert_eq!(descriptor.label, "config");
+        assert_eq!(descriptor.phase().unwrap(), InstallationPhase::Config);
+
+        let signed = HashMap::from([("id", Value::new(2_i32)), ("label", Value::new("install"))]);
+        let descriptor = PhaseDescriptor::try_from(&to_owned_hash(&signed)).unwrap();
+        assert_eq!(descriptor.phase().unwrap(), InstallationPhase::Install);
+
+        let missing = HashMap::from([("id", Value::new(1_u32))]);
+        assert!(PhaseDescriptor::try_from(&to_owned_hash(&missing)).is_err());
+
+        let wrong_type = HashMap::from([("id", Value::new("1")), ("label", Value::new("config"))]);
+        assert!(PhaseDescriptor::try_from(&to_owned_hash(&wrong_type)).is_err());
+    }
+}
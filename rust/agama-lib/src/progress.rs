@@ -48,12 +48,13 @@
 
 use crate::{error::ServiceError, proxies::ProgressProxy};
 use async_trait::async_trait;
+use futures_util::stream;
 use serde::Serialize;
-use tokio_stream::{StreamExt, StreamMap};
+use tokio_stream::{Stream, StreamExt, StreamMap};
 use zbus::Connection;
 
 /// Represents the progress for an Agama service.
-#[derive(Clone, Default, Debug, Serialize)]
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Progress {
     /// Current step
@@ -80,6 +81,66 @@ impl Progress {
         })
     }
 
+    /// Returns a stream of progress updates.
+    ///
+    /// A new item is emitted whenever the current step, the total steps or the finished flag
+    /// change. The stream emits a last item when the progress is finished and then it closes.
+    ///
+    /// ```no_run
+    /// # use agama_lib::{progress::Progress, proxies::ProgressProxy};
+    /// # use tokio_stream::StreamExt;
+    /// # async fn report(proxy: ProgressProxy<'_>) {
+    /// let mut updates = Progress::stream_from_proxy(proxy).await;
+    /// while let Some(progress) = updates.next().await {
+    ///     println!("{}/{} {}", progress.current_step, progress.max_steps, progress.current_title);
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// * `proxy`: proxy to get the progress from.
+    pub async fn stream_from_proxy<'a>(
+        proxy: ProgressProxy<'a>,
+    ) -> impl Stream<Item = Progress> + Unpin + 'a {
+        let changes = Self::changes_from_proxy(proxy).await;
+        let updates = stream::unfold((changes, false), |(mut changes, done)| async move {
+            if done {
+                return None;
+            }
+
+            let progress = changes.next().await?;
+            let done = progress.finished;
+            Some((progress, (changes, done)))
+        });
+        Box::pin(updates)
+    }
+
+    // Returns a stream which emits the progress whenever it changes. Unlike
+    // [Progress::stream_from_proxy], it does not close when the progress is finished.
+    pub(crate) async fn changes_from_proxy<'a>(
+        proxy: ProgressProxy<'a>,
+    ) -> impl Stream<Item = Progress> + Unpin + 'a {
+        let current_step = proxy.receive_current_step_changed().await.map(|_| ());
+        let total_steps = proxy.receive_total_steps_changed().await.map(|_| ());
+        let finished = proxy.receive_finished_changed().await.map(|_| ());
+        let changes = Box::pin(current_step.merge(total_steps).merge(finished));
+
+        let updates = stream::unfold(
+            (changes, proxy, None::<Progress>),
+            |(mut changes, proxy, last)| async move {
+                while changes.next().await.is_some() {
+                    let Ok(progress) = Progress::from_proxy(&proxy).await else {
+                        continue;
+                    };
+                    if last.as_ref() != Some(&progress) {
+                        return Some((progress.clone(), (changes, proxy, Some(progress))));
+                    }
+                }
+                None
+            },
+        );
+        Box::pin(updates)
+    }
+
     pub fn from_cached_proxy(proxy: &crate::proxies::ProgressProxy<'_>) -> Option<Progress> {
         let (current_step, current_title) = proxy.cached_current_step().ok()??;
         let max_steps = proxy.cached_total_steps().ok()??;
@@ -1,6 +1,8 @@
 //! Data model for Agama questions
 
 use std::collections::HashMap;
+pub mod client;
+pub mod data;
 pub mod http_client;
 pub mod model;
 
@@ -69,6 +71,13 @@ impl GenericQuestion {
     pub fn object_path(&self) -> String {
         format!("/org/opensuse/Agama1/Questions/{}", self.id)
     }
+
+    /// Returns the typed data of the question (see [data]).
+    ///
+    /// It returns `None` if the question does not belong to the class of `T`.
+    pub fn data_as<T: data::QuestionData>(&self) -> Option<Result<T, data::QuestionDataError>> {
+        T::from_question(&self.class, &self.data)
+    }
 }
 
 /// Composition for questions which include password.
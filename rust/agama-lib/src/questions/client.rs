@@ -0,0 +1,868 @@
+//! D-Bus helpers to create and answer questions.
+//!
+//! ```no_run
+//! # use agama_lib::proxies::Questions1Proxy;
+//! # use agama_lib::questions::{client::QuestionBuilder, data::{LuksActivationData, QuestionData}};
+//! # async fn ask(proxy: Questions1Proxy<'_>) {
+//! let question = QuestionBuilder::new(LuksActivationData::CLASS, "Activate the device?")
+//!     .options(["yes", "no"])
+//!     .default_option("no")
+//!     .data("device", "/dev/sda1")
+//!     .build(&proxy)
+//!     .await
+//!     .unwrap();
+//! question.set_answer("yes").await.unwrap();
+//! # }
+//! ```
+//!
+//! Questions asking for a password are answered with a [PasswordAnswer], whose password is
+//! zeroized when it is dropped:
+//!
+//! ```no_run
+//! # use agama_lib::proxies::Questions1Proxy;
+//! # use agama_lib::questions::{client::QuestionBuilder, data::{LuksActivationData, QuestionData}};
+//! # use std::time::Duration;
+//! # use tokio_util::sync::CancellationToken;
+//! # async fn ask(proxy: Questions1Proxy<'_>) {
+//! let answer = QuestionBuilder::new(LuksActivationData::CLASS, "Encryption password")
+//!     .options(["decrypt", "skip"])
+//!     .default_option("skip")
+//!     .ask_with_password(&proxy, Duration::from_secs(300), CancellationToken::new())
+//!     .await
+//!     .unwrap();
+//! if answer.answer == "decrypt" {
+//!     println!("Password length: {}", answer.password.len());
+//! }
+//! # }
+//! ```
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    future::Future,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use futures_util::stream;
+use thiserror::Error;
+use tokio_stream::{Stream, StreamExt};
+use tokio_util::sync::CancellationToken;
+use zbus::{fdo::ObjectManagerProxy, zvariant::OwnedObjectPath};
+use zeroize::Zeroizing;
+
+use super::data::QuestionData;
+use crate::{
+    dbus::extract_id_from_path,
+    error::ServiceError,
+    proxies::{GenericQuestionProxy, QuestionWithPasswordProxy, Questions1Proxy},
+};
+
+/// Errors found when validating a question before registering it.
+#[derive(Error, Debug, PartialEq)]
+pub enum QuestionBuilderError {
+    #[error("The question has no options")]
+    NoOptions,
+    #[error("Duplicated option: '{0}'")]
+    DuplicatedOption(String),
+    #[error("The default option '{0}' is not one of the options")]
+    UnknownDefaultOption(String),
+}
+
+/// Builds a question and registers it in the questions service.
+#[derive(Clone, Debug, Default)]
+pub struct QuestionBuilder {
+    class: String,
+    text: String,
+    options: Vec<String>,
+    default_option: Option<String>,
+    data: HashMap<String, String>,
+    with_password: bool,
+}
+
+impl QuestionBuilder {
+    /// Creates a builder for a question.
+    ///
+    /// * `class`: question class (e.g.,
+    ///   [LuksActivationData::CLASS](super::data::LuksActivationData::CLASS)).
+    /// * `text`: question text.
+    pub fn new(class: &str, text: &str) -> Self {
+        Self {
+            class: class.to_string(),
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds an option to the question.
+    pub fn option(mut self, option: impl Into<String>) -> Self {
+        self.options.push(option.into());
+        self
+    }
+
+    /// Adds several options to the question.
+    pub fn options<I, S>(mut self, options: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.options.extend(options.into_iter().map(Into::into));
+        self
+    }
+
+    /// Sets the default option, which is used as answer in the non-interactive mode.
+    pub fn default_option(mut self, option: impl Into<String>) -> Self {
+        self.default_option = Some(option.into());
+        self
+    }
+
+    /// Adds a key-value pair to the question data.
+    pub fn data(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.data.insert(key.into(), value.into());
+        self
+    }
+
+    /// Asks for a password too.
+    pub fn with_password(mut self) -> Self {
+        self.with_password = true;
+        self
+    }
+
+    /// Registers the question in the questions service.
+    ///
+    /// It fails with a [ServiceError::InvalidQuestion] error if the question is not valid (see
+    /// [QuestionBuilder::validate]).
+    ///
+    /// * `proxy`: questions service proxy.
+    pub async fn build<'a>(
+        self,
+        proxy: &Questions1Proxy<'a>,
+    ) -> Result<QuestionHandle<'a>, ServiceError> {
+        let path = self.register(proxy).await?;
+        QuestionHandle::new(proxy.connection(), path).await
+    }
+
+    /// Registers the question, asking for a password too, in the questions service.
+    ///
+    /// * `proxy`: questions service proxy.
+    pub async fn build_with_password<'a>(
+        self,
+        proxy: &Questions1Proxy<'a>,
+    ) -> Result<PasswordQuestionHandle<'a>, ServiceError> {
+        let path = self.with_password().register(proxy).await?;
+        PasswordQuestionHandle::new(proxy.connection(), path).await
+    }
+
+    /// Checks whether the question is valid.
+    ///
+    /// The options must not be empty or duplicated and the default option, if given, must be one
+    /// of them. The question is validated before registering it.
+    pub fn validate(&self) -> Result<(), QuestionBuilderError> {
+        if self.options.is_empty() {
+            return Err(QuestionBuilderError::NoOptions);
+        }
+
+        for (index, option) in self.options.iter().enumerate() {
+            if self.options[..index].contains(option) {
+                return Err(QuestionBuilderError::DuplicatedOption(option.clone()));
+            }
+        }
+
+        if let Some(default_option) = &self.default_option {
+            if !self.options.contains(default_option) {
+                return Err(QuestionBuilderError::UnknownDefaultOption(
+                    default_option.clone(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // Registers the question and returns its object path.
+    async fn register(&self, proxy: &Questions1Proxy<'_>) -> Result<OwnedObjectPath, ServiceError> {
+        self.validate()?;
+        let options: Vec<&str> = self.options.iter().map(String::as_str).collect();
+        let default_option = self.default_option.as_deref().unwrap_or_default();
+        let data: HashMap<&str, &str> = self
+            .data
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+
+        let path = if self.with_password {
+            proxy
+                .new_with_password(&self.class, &self.text, &options, default_option, data)
+                .await?
+        } else {
+            proxy
+                .new_question(&self.class, &self.text, &options, default_option, data)
+                .await?
+        };
+        Ok(path)
+    }
+
+    /// Registers the question and waits until it is answered.
+    ///
+    /// If the question is not answered in time or the operation is cancelled, the question is
+    /// deleted.
+    ///
+    /// * `proxy`: questions service proxy.
+    /// * `timeout`: maximum time to wait for the answer.
+    /// * `cancel`: token to cancel the operation.
+    pub async fn ask(
+        self,
+        proxy: &Questions1Proxy<'_>,
+        timeout: Duration,
+        cancel: CancellationToken,
+    ) -> Result<String, ServiceError> {
+        let question = self.build(proxy).await?;
+        wait_or_delete(
+            proxy,
+            &question,
+            question.wait_for_answer(),
+            timeout,
+            cancel,
+        )
+        .await
+    }
+
+    /// Registers the question, asking for a password too, and waits until it is answered.
+    ///
+    /// It behaves like [QuestionBuilder::ask], but it returns the password together with the
+    /// answer. If the question is deleted before it is answered, it returns a
+    /// [ServiceError::QuestionNotExist] error.
+    ///
+    /// * `proxy`: questions service proxy.
+    /// * `timeout`: maximum time to wait for the answer.
+    /// * `cancel`: token to cancel the operation.
+    pub async fn ask_with_password(
+        self,
+        proxy: &Questions1Proxy<'_>,
+        timeout: Duration,
+        cancel: CancellationToken,
+    ) -> Result<PasswordAnswer, ServiceError> {
+        let question = self.build_with_password(proxy).await?;
+        let answer = question.wait_for_answer();
+        wait_or_delete(proxy, question.question(), answer, timeout, cancel).await
+    }
+}
+
+// Returns the option at the given position of the question with the given ID.
+fn option_at(options: &[String], id: u32, index: usize) -> Result<&str, ServiceError> {
+    options
+        .get(index)
+        .map(String::as_str)
+        .ok_or(ServiceError::QuestionOptionOutOfRange(id, index))
+}
+
+// Waits for the given answer, deleting the question if it times out or it is cancelled.
+//
+// Failing to delete the question is just logged, so the caller gets the timeout or cancellation
+// error anyway.
+async fn wait_or_delete<T>(
+    proxy: &Questions1Proxy<'_>,
+    question: &QuestionHandle<'_>,
+    answer: impl Future<Output = Result<T, ServiceError>>,
+    timeout: Duration,
+    cancel: CancellationToken,
+) -> Result<T, ServiceError> {
+    let error = tokio::select! {
+        answer = tokio::time::timeout(timeout, answer) => match answer {
+            Ok(answer) => return answer,
+            Err(_) => ServiceError::QuestionTimeout(question.id()),
+        },
+        _ = cancel.cancelled() => ServiceError::QuestionCancelled(question.id()),
+    };
+
+    if let Err(e) = proxy.delete(question.path()).await {
+        log::warn!("Could not delete the question {}: {}", question.id(), e);
+    }
+    Err(error)
+}
+
+/// Handle to a question registered in the questions service.
+#[derive(Clone)]
+pub struct QuestionHandle<'a> {
+    id: u32,
+    path: OwnedObjectPath,
+    proxy: GenericQuestionProxy<'a>,
+}
+
+impl<'a> QuestionHandle<'a> {
+    /// Builds a handle for an existing question.
+    ///
+    /// * `connection`: D-Bus connection.
+    /// * `path`: question object path.
+    pub async fn new(
+        connection: &zbus::Connection,
+        path: OwnedObjectPath,
+    ) -> Result<QuestionHandle<'a>, ServiceError> {
+        let id = extract_id_from_path(&path)?;
+        let proxy = GenericQuestionProxy::builder(connection)
+            .path(path.clone())?
+            .build()
+            .await?;
+        Ok(Self { id, path, proxy })
+    }
+
+    /// Question ID.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Question object path.
+    pub fn path(&self) -> &OwnedObjectPath {
+        &self.path
+    }
+
+    /// Underlying D-Bus proxy.
+    pub fn proxy(&self) -> &GenericQuestionProxy<'a> {
+        &self.proxy
+    }
+
+    /// Returns the answer. It is empty if the question is not answered yet.
+    pub async fn answer(&self) -> Result<String, ServiceError> {
+        Ok(self.proxy.answer().await?)
+    }
+
+    /// Returns the options of the question.
+    pub async fn options(&self) -> Result<Vec<String>, ServiceError> {
+        Ok(self.proxy.options().await?)
+    }
+
+    /// Answers the question.
+    ///
+    /// * `answer`: selected option.
+    pub async fn set_answer(&self, answer: &str) -> Result<(), ServiceError> {
+        Ok(self.proxy.set_answer(answer).await?)
+    }
+
+    /// Answers the question using the position of the selected option in [Self::options].
+    ///
+    /// It returns a [ServiceError::QuestionOptionOutOfRange] error if there is no option at the
+    /// given position.
+    ///
+    /// * `index`: position of the selected option, starting at 0.
+    pub async fn set_answer_by_index(&self, index: usize) -> Result<(), ServiceError> {
+        let options = self.options().await?;
+        let answer = option_at(&options, self.id, index)?;
+        self.set_answer(answer).await
+    }
+
+    /// Returns the raw question data.
+    pub async fn data(&self) -> Result<HashMap<String, String>, ServiceError> {
+        Ok(self.proxy.data().await?)
+    }
+
+    /// Returns the typed question data (see [crate::questions::data]).
+    ///
+    /// It returns `None` if the question does not belong to the class of `T`.
+    pub async fn data_as<T: QuestionData>(&self) -> Result<Option<T>, ServiceError> {
+        let class = self.proxy.class().await?;
+        let data = self.data().await?;
+        Ok(T::from_question(&class, &data).transpose()?)
+    }
+
+    /// Waits until the question is answered and returns the answer.
+    pub async fn wait_for_answer(&self) -> Result<String, ServiceError> {
+        let mut changes = self.proxy.receive_answer_changed().await;
+        let answer = self.answer().await?;
+        if !answer.is_empty() {
+            return Ok(answer);
+        }
+
+        while let Some(change) = changes.next().await {
+            let answer = change.get().await?;
+            if !answer.is_empty() {
+                return Ok(answer);
+            }
+        }
+        Err(ServiceError::QuestionNotExist(self.id))
+    }
+}
+
+/// Answer to a question with password.
+///
+/// The password is zeroized when the answer is dropped.
+pub struct PasswordAnswer {
+    /// Selected option.
+    pub answer: String,
+    /// Given password. It might be empty (e.g., when the user skips the decryption).
+    pub password: Zeroizing<String>,
+}
+
+impl std::fmt::Debug for PasswordAnswer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PasswordAnswer")
+            .field("answer", &self.answer)
+            .field("password", &"<hidden>")
+            .finish()
+    }
+}
+
+/// Handle to a question with password registered in the questions service.
+#[derive(Clone)]
+pub struct PasswordQuestionHandle<'a> {
+    question: QuestionHandle<'a>,
+    proxy: QuestionWithPasswordProxy<'a>,
+}
+
+impl<'a> PasswordQuestionHandle<'a> {
+    /// Builds a handle for an existing question with password.
+    ///
+    /// * `connection`: D-Bus connection.
+    /// * `path`: question object path.
+    pub async fn new(
+        connection: &zbus::Connection,
+        path: OwnedObjectPath,
+    ) -> Result<PasswordQuestionHandle<'a>, ServiceError> {
+        let proxy = QuestionWithPasswordProxy::builder(connection)
+            .path(path.clone())?
+            .build()
+            .await?;
+        let question = QuestionHandle::new(connection, path).await?;
+        Ok(Self { question, proxy })
+    }
+
+    /// Handle to the generic part of the question (ID, answer, etc.).
+    pub fn question(&self) -> &QuestionHandle<'a> {
+        &self.question
+    }
+
+    /// Returns the password. It is empty if it is not set yet.
+    pub async fn password(&self) -> Result<Zeroizing<String>, ServiceError> {
+        Ok(Zeroizing::new(self.proxy.password().await?))
+    }
+
+    /// Sets the password.
+    ///
+    /// * `password`: password to set.
+    pub async fn set_password(&self, password: &str) -> Result<(), ServiceError> {
+        Ok(self.proxy.set_password(password).await?)
+    }
+
+    /// Answers the question, setting the password first.
+    ///
+    /// * `answer`: selected option.
+    /// * `password`: password to set.
+    pub async fn set_answer(&self, answer: &str, password: &str) -> Result<(), ServiceError> {
+        self.set_password(password).await?;
+        self.question.set_answer(answer).await
+    }
+
+    /// Waits until the question is answered and returns the answer and the password.
+    ///
+    /// The password is expected to be set before the answer, as the questions service does.
+    pub async fn wait_for_answer(&self) -> Result<PasswordAnswer, ServiceError> {
+        let answer = self.question.wait_for_answer().await?;
+        let password = self.password().await?;
+        Ok(PasswordAnswer { answer, password })
+    }
+}
+
+/// Question registered in the questions service, as emitted by [QuestionsClient::new_questions].
+#[derive(Clone)]
+pub enum NewQuestion<'a> {
+    /// Generic question.
+    Generic(QuestionHandle<'a>),
+    /// Question asking for a password too.
+    WithPassword(PasswordQuestionHandle<'a>),
+}
+
+impl<'a> NewQuestion<'a> {
+    /// Handle to the generic part of the question (ID, answer, etc.).
+    pub fn question(&self) -> &QuestionHandle<'a> {
+        match self {
+            Self::Generic(question) => question,
+            Self::WithPassword(question) => question.question(),
+        }
+    }
+}
+
+const GENERIC_QUESTION_IFACE: &str = "org.opensuse.Agama1.Questions.Generic";
+const WITH_PASSWORD_IFACE: &str = "org.opensuse.Agama1.Questions.WithPassword";
+
+/// D-Bus client for the questions service.
+#[derive(Clone)]
+pub struct QuestionsClient<'a> {
+    proxy: Questions1Proxy<'a>,
+}
+
+impl<'a> QuestionsClient<'a> {
+    pub async fn new(connection: &zbus::Connection) -> Result<QuestionsClient<'a>, ServiceError> {
+        Ok(Self {
+            proxy: Questions1Proxy::new(connection).await?,
+        })
+    }
+
+    /// Underlying D-Bus proxy.
+    pub fn proxy(&self) -> &Questions1Proxy<'a> {
+        &self.proxy
+    }
+
+    /// Returns a stream which emits the questions registered from now on.
+    ///
+    /// The questions which cannot be inspected (e.g., because they were deleted in the meantime)
+    /// are skipped. The stream finishes when the D-Bus connection is closed.
+    ///
+    /// ```no_run
+    /// # use agama_lib::questions::client::QuestionsClient;
+    /// # use tokio_stream::StreamExt;
+    /// # async fn watch(client: QuestionsClient<'_>) {
+    /// let mut questions = client.new_questions().await.unwrap();
+    /// while let Some(question) = questions.next().await {
+    ///     println!("New question: {}", question.question().id());
+    /// }
+    /// # }
+    /// ```
+    pub async fn new_questions(
+        &self,
+    ) -> Result<impl Stream<Item = NewQuestion<'static>> + Unpin + Send, ServiceError> {
+        let connection = self.proxy.connection().clone();
+        let objects = ObjectManagerProxy::builder(&connection)
+            .destination("org.opensuse.Agama1")?
+            .path("/org/opensuse/Agama1/Questions")?
+            .build()
+            .await?;
+        let added = objects.receive_interfaces_added().await?;
+
+        let questions = stream::unfold((added, connection), |(mut added, connection)| async move {
+            while let Some(signal) = added.next().await {
+                let Ok(args) = signal.args() else {
+                    continue;
+                };
+                let interfaces = args.interfaces_and_properties();
+                if !interfaces.contains_key(GENERIC_QUESTION_IFACE) {
+                    continue;
+                }
+
+                let path = OwnedObjectPath::from(args.object_path().clone());
+                let question = if interfaces.contains_key(WITH_PASSWORD_IFACE) {
+                    PasswordQuestionHandle::new(&connection, path.clone())
+                        .await
+                        .map(NewQuestion::WithPassword)
+                } else {
+                    QuestionHandle::new(&connection, path.clone())
+                        .await
+                        .map(NewQuestion::Generic)
+                };
+                match question {
+                    Ok(question) => return Some((question, (added, connection))),
+                    Err(error) => {
+                        log::warn!(
+                            "Could not inspect the question {}: {}",
+                            path.as_str(),
+                            error
+                        )
+                    }
+                }
+            }
+            None
+        });
+        Ok(Box::pin(questions))
+    }
+
+    /// Loads the answers from the given file.
+    ///
+    /// It checks that the file exists and it is readable before asking the service to load it.
+    ///
+    /// * `path`: path to the answers file.
+    pub async fn add_answer_file(&self, path: &Path) -> Result<(), ServiceError> {
+        let path = readable_file(path)?;
+        Ok(self
+            .proxy
+            .add_answer_file(path.to_string_lossy().as_ref())
+            .await?)
+    }
+
+    /// Loads the answers from the given content.
+    ///
+    /// The content is written to a temporary file which is removed once the service loads it.
+    ///
+    /// * `content`: answers in the answers file format.
+    pub async fn add_answers(&self, content: &str) -> Result<(), ServiceError> {
+        let mut file = tempfile::Builder::new()
+            .prefix("agama-answers")
+            .suffix(".yaml")
+            .tempfile()?;
+        file.write_all(content.as_bytes())?;
+        self.add_answer_file(file.path()).await
+    }
+}
+
+/// Answers automatically the questions of the configured classes.
+///
+/// It is meant for unattended installations, where an unexpected question would block the
+/// process. The rest of questions are ignored.
+///
+/// ```no_run
+/// # use agama_lib::questions::client::QuestionsAutoAnswerer;
+/// # use tokio_util::sync::CancellationToken;
+/// # async fn automate(connection: zbus::Connection) {
+/// let answerer = QuestionsAutoAnswerer::default()
+///     .answer("software.import_gpg", "Trust")
+///     .answer("storage.activate_multipath", "yes");
+/// answerer.run(&connection, CancellationToken::new()).await.unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct QuestionsAutoAnswerer {
+    answers: HashMap<String, String>,
+}
+
+impl QuestionsAutoAnswerer {
+    /// Creates an answerer using the given answers.
+    ///
+    /// * `answers`: map from question class to answer.
+    pub fn new(answers: HashMap<String, String>) -> Self {
+        Self { answers }
+    }
+
+    /// Sets the answer for the questions of the given class.
+    ///
+    /// * `class`: question class (e.g., "storage.luks_activation").
+    /// * `answer`: option to answer.
+    pub fn answer(mut self, class: impl Into<String>, answer: impl Into<String>) -> Self {
+        self.answers.insert(class.into(), answer.into());
+        self
+    }
+
+    /// Returns the answer for the given class, if any.
+    ///
+    /// * `class`: question class.
+    pub fn answer_for(&self, class: &str) -> Option<&str> {
+        self.answers.get(class).map(String::as_str)
+    }
+
+    /// Answers the pending and the new questions until the operation is cancelled.
+    ///
+    /// It fails only if it cannot subscribe to the questions service. The errors answering a
+    /// question (e.g., because it was deleted in the meantime) are logged.
+    ///
+    /// * `connection`: D-Bus connection.
+    /// * `cancel`: token to stop answering.
+    pub async fn run(
+        &self,
+        connection: &zbus::Connection,
+        cancel: CancellationToken,
+    ) -> Result<(), ServiceError> {
+        let objects = ObjectManagerProxy::builder(connection)
+            .destination("org.opensuse.Agama1")?
+            .path("/org/opensuse/Agama1/Questions")?
+            .build()
+            .await?;
+        // subscribe before listing the questions to not miss any of them
+        let client = QuestionsClient::new(connection).await?;
+        let mut questions = client.new_questions().await?;
+
+        for path in objects.get_managed_objects().await?.into_keys() {
+            match QuestionHandle::new(connection, path.clone()).await {
+                Ok(question) => self.try_answer(&question).await,
+                Err(error) => {
+                    log::warn!(
+                        "Could not inspect the question {}: {}",
+                        path.as_str(),
+                        error
+                    )
+                }
+            }
+        }
+
+        loop {
+            tokio::select! {
+                question = questions.next() => {
+                    let Some(question) = question else {
+                        return Ok(());
+                    };
+                    self.try_answer(question.question()).await;
+                }
+                _ = cancel.cancelled() => return Ok(()),
+            }
+        }
+    }
+
+    // Answers the given question if its class is configured, logging any error.
+    async fn try_answer(&self, question: &QuestionHandle<'_>) {
+        if let Err(error) = self.answer_question(question).await {
+            log::warn!("Could not answer the question {}: {}", question.id(), error);
+        }
+    }
+
+    async fn answer_question(&self, question: &QuestionHandle<'_>) -> Result<(), ServiceError> {
+        let class = question.proxy().class().await?;
+        let Some(answer) = self.answer_for(&class) else {
+            return Ok(());
+        };
+        if !question.answer().await?.is_empty() {
+            return Ok(());
+        }
+
+        question.set_answer(answer).await?;
+        log::info!(
+            "Question {} ({}) answered automatically: {}",
+            question.id(),
+            class,
+            answer
+        );
+        Ok(())
+    }
+}
+
+/// Sets the questions service in non-interactive mode and restores the previous mode on drop.
+///
+/// As dropping cannot be asynchronous, the restoration is performed by a task which uses a clone
+/// of the proxy. Hence, it requires a Tokio runtime and the mode might not be restored yet when
+/// the guard is dropped. Use [NonInteractiveGuard::restore] to wait until the mode is restored.
+///
+/// ```no_run
+/// # use agama_lib::{proxies::Questions1Proxy, questions::client::NonInteractiveGuard};
+/// # async fn automate(proxy: Questions1Proxy<'static>) {
+/// let guard = NonInteractiveGuard::new(&proxy).await.unwrap();
+/// // questions are answered automatically until the guard is dropped
+/// guard.restore().await.unwrap();
+/// # }
+/// ```
+pub struct NonInteractiveGuard {
+    proxy: Questions1Proxy<'static>,
+    previous: Option<bool>,
+}
+
+impl NonInteractiveGuard {
+    /// Sets the non-interactive mode, remembering the current one.
+    ///
+    /// * `proxy`: questions service proxy.
+    pub async fn new(proxy: &Questions1Proxy<'static>) -> Result<Self, ServiceError> {
+        let previous = proxy.interactive().await?;
+        proxy.set_interactive(false).await?;
+        Ok(Self {
+            proxy: proxy.clone(),
+            previous: Some(previous),
+        })
+    }
+
+    /// Restores the previous mode, waiting until it is done.
+    pub async fn restore(mut self) -> Result<(), ServiceError> {
+        if let Some(previous) = self.previous.take() {
+            self.proxy.set_interactive(previous).await?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for NonInteractiveGuard {
+    fn drop(&mut self) {
+        let Some(previous) = self.previous.take() else {
+            return;
+        };
+
+        let proxy = self.proxy.clone();
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(error) = proxy.set_interactive(previous).await {
+                        log::error!("Could not restore the questions mode: {}", error);
+                    }
+                });
+            }
+            Err(_) => log::error!("Could not restore the questions mode: no runtime available"),
+        }
+    }
+}
+
+// Checks whether the given path is a readable file and returns its absolute path (the service
+// may run in a different working directory).
+fn readable_file(path: &Path) -> Result<PathBuf, ServiceError> {
+    let unreadable = |e| ServiceError::UnreadableAnswersFile(path.display().to_string(), e);
+    let file = File::open(path).map_err(unreadable)?;
+    if !file.metadata().map_err(unreadable)?.is_file() {
+        return Err(unreadable(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "not a regular file",
+        )));
+    }
+    path.canonicalize().map_err(unreadable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        option_at, readable_file, PasswordAnswer, QuestionBuilder, QuestionBuilderError,
+        QuestionsAutoAnswerer,
+    };
+    use crate::error::ServiceError;
+    use std::{io::Write, path::Path};
+    use zeroize::Zeroizing;
+
+    #[test]
+    fn test_option_at() {
+        let options = vec!["yes".to_string(), "no".to_string()];
+        assert_eq!(option_at(&options, 1, 1).unwrap(), "no");
+        assert!(matches!(
+            option_at(&options, 1, 2),
+            Err(ServiceError::QuestionOptionOutOfRange(1, 2))
+        ));
+    }
+
+    #[test]
+    fn test_question_builder_validate() {
+        let question = QuestionBuilder::new("storage.commit_error", "Continue?")
+            .options(["yes", "no"])
+            .default_option("no");
+        assert_eq!(question.validate(), Ok(()));
+        assert_eq!(
+            question.clone().default_option("maybe").validate(),
+            Err(QuestionBuilderError::UnknownDefaultOption(
+                "maybe".to_string()
+            ))
+        );
+        assert_eq!(
+            question.option("yes").validate(),
+            Err(QuestionBuilderError::DuplicatedOption("yes".to_string()))
+        );
+        assert_eq!(
+            QuestionBuilder::new("storage.commit_error", "Continue?").validate(),
+            Err(QuestionBuilderError::NoOptions)
+        );
+    }
+
+    #[test]
+    fn test_auto_answerer_answer_for() {
+        let answerer = QuestionsAutoAnswerer::default()
+            .answer("software.import_gpg", "Trust")
+            .answer("storage.luks_activation", "skip")
+            .answer("software.import_gpg", "Skip");
+        assert_eq!(answerer.answer_for("software.import_gpg"), Some("Skip"));
+        assert_eq!(answerer.answer_for("storage.luks_activation"), Some("skip"));
+        assert_eq!(answerer.answer_for("unknown"), None);
+    }
+
+    #[test]
+    fn test_password_answer_debug() {
+        let answer = PasswordAnswer {
+            answer: "decrypt".to_string(),
+            password: Zeroizing::new("secret".to_string()),
+        };
+        let debug = format!("{:?}", answer);
+        assert!(debug.contains("decrypt"));
+        assert!(!debug.contains("secret"));
+    }
+
+    #[test]
+    fn test_readable_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "answers: []").unwrap();
+        let path = readable_file(file.path()).unwrap();
+        assert!(path.is_absolute());
+
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            readable_file(dir.path()),
+            Err(ServiceError::UnreadableAnswersFile(_, _))
+        ));
+        assert!(matches!(
+            readable_file(Path::new("/does/not/exist.yaml")),
+            Err(ServiceError::UnreadableAnswersFile(_, _))
+        ));
+    }
+}
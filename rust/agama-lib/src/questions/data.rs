@@ -0,0 +1,238 @@
+//! Typed access to the data of the known question classes.
+//!
+//! The questions carry a flat map of strings (see [GenericQuestion::data](super::GenericQuestion)).
+//! This module defines a type for each known class, so the clients do not need to deal with the
+//! keys and the parsing of the values.
+//!
+//! ```
+//! # use std::collections::HashMap;
+//! # use agama_lib::questions::data::{LuksActivationData, QuestionData};
+//! let data = HashMap::from([
+//!     ("device".to_string(), "/dev/sda1".to_string()),
+//!     ("attempt".to_string(), "2".to_string()),
+//! ]);
+//! let luks = LuksActivationData::from_data(&data).unwrap();
+//! assert_eq!(luks.device, "/dev/sda1");
+//! assert_eq!(luks.attempt, 2);
+//! ```
+
+use std::{collections::HashMap, str::FromStr};
+
+use thiserror::Error;
+
+/// Errors extracting the data of a question.
+#[derive(Error, Debug, PartialEq)]
+pub enum QuestionDataError {
+    #[error("Missing question data: '{0}'")]
+    MissingKey(&'static str),
+    #[error("Invalid value for question data '{0}': '{1}'")]
+    InvalidValue(&'static str, String),
+}
+
+/// Data of a known question class.
+pub trait QuestionData: Sized {
+    /// Question class (e.g., "storage.luks_activation").
+    const CLASS: &'static str;
+
+    /// Extracts the data from the question data map.
+    ///
+    /// * `data`: question data.
+    fn from_data(data: &HashMap<String, String>) -> Result<Self, QuestionDataError>;
+
+    /// Extracts the data if the question belongs to [QuestionData::CLASS].
+    ///
+    /// It returns `None` if the question belongs to another class.
+    ///
+    /// * `class`: question class.
+    /// * `data`: question data.
+    fn from_question(
+        class: &str,
+        data: &HashMap<String, String>,
+    ) -> Option<Result<Self, QuestionDataError>> {
+        (class == Self::CLASS).then(|| Self::from_data(data))
+    }
+}
+
+/// Data of the question to activate an encrypted (LUKS) device.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LuksActivationData {
+    /// Device name (e.g., "/dev/sda1").
+    pub device: String,
+    /// Device label, if any.
+    pub label: Option<String>,
+    /// Human readable device size (e.g., "5.00 GiB"), if known.
+    pub size: Option<String>,
+    /// Activation attempt, starting at 1.
+    pub attempt: u32,
+}
+
+impl QuestionData for LuksActivationData {
+    const CLASS: &'static str = "storage.luks_activation";
+
+    fn from_data(data: &HashMap<String, String>) -> Result<Self, QuestionDataError> {
+        Ok(Self {
+            device: required(data, "device")?,
+            label: optional(data, "label"),
+            size: optional(data, "size"),
+            attempt: parse(data, "attempt")?,
+        })
+    }
+}
+
+/// Data of the question to continue after a storage commit error.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommitErrorData {
+    /// Error details.
+    pub details: String,
+}
+
+impl QuestionData for CommitErrorData {
+    const CLASS: &'static str = "storage.commit_error";
+
+    fn from_data(data: &HashMap<String, String>) -> Result<Self, QuestionDataError> {
+        Ok(Self {
+            details: required(data, "details")?,
+        })
+    }
+}
+
+/// Data of the question to retry after a medium (repository) error.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MediumErrorData {
+    /// URL of the medium.
+    pub url: String,
+}
+
+impl QuestionData for MediumErrorData {
+    const CLASS: &'static str = "software.medium_error";
+
+    fn from_data(data: &HashMap<String, String>) -> Result<Self, QuestionDataError> {
+        Ok(Self {
+            url: required(data, "url")?,
+        })
+    }
+}
+
+/// Data of the question to accept an unsigned file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnsignedFileData {
+    /// Name of the unsigned file.
+    pub filename: String,
+}
+
+impl QuestionData for UnsignedFileData {
+    const CLASS: &'static str = "software.unsigned_file";
+
+    fn from_data(data: &HashMap<String, String>) -> Result<Self, QuestionDataError> {
+        Ok(Self {
+            filename: required(data, "filename")?,
+        })
+    }
+}
+
+/// Data of the question to trust (import) a GPG key.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportGpgData {
+    /// Key ID.
+    pub id: String,
+    /// Key name.
+    pub name: String,
+    /// Key fingerprint.
+    pub fingerprint: String,
+}
+
+impl QuestionData for ImportGpgData {
+    const CLASS: &'static str = "software.import_gpg";
+
+    fn from_data(data: &HashMap<String, String>) -> Result<Self, QuestionDataError> {
+        Ok(Self {
+            id: required(data, "id")?,
+            name: required(data, "name")?,
+            fingerprint: required(data, "fingerprint")?,
+        })
+    }
+}
+
+fn required(
+    data: &HashMap<String, String>,
+    key: &'static str,
+) -> Result<String, QuestionDataError> {
+    data.get(key)
+        .cloned()
+        .ok_or(QuestionDataError::MissingKey(key))
+}
+
+// Empty values are considered as missing.
+fn optional(data: &HashMap<String, String>, key: &'static str) -> Option<String> {
+    data.get(key).filter(|v| !v.is_empty()).cloned()
+}
+
+fn parse<T: FromStr>(
+    data: &HashMap<String, String>,
+    key: &'static str,
+) -> Result<T, QuestionDataError> {
+    let value = data.get(key).ok_or(QuestionDataError::MissingKey(key))?;
+    value
+        .parse()
+        .map_err(|_| QuestionDataError::InvalidValue(key, value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_luks_activation_data() {
+        let luks = LuksActivationData::from_data(&data(&[
+            ("device", "/dev/sda1"),
+            ("label", ""),
+            ("size", "5.00 GiB"),
+            ("attempt", "1"),
+        ]))
+        .unwrap();
+        assert_eq!(
+            luks,
+            LuksActivationData {
+                device: "/dev/sda1".to_string(),
+                label: None,
+                size: Some("5.00 GiB".to_string()),
+                attempt: 1
+            }
+        );
+
+        assert_eq!(
+            LuksActivationData::from_data(&data(&[("device", "/dev/sda1"), ("attempt", "one")])),
+            Err(QuestionDataError::InvalidValue(
+                "attempt",
+                "one".to_string()
+            ))
+        );
+        assert_eq!(
+            LuksActivationData::from_data(&data(&[("attempt", "1")])),
+            Err(QuestionDataError::MissingKey("device"))
+        );
+    }
+
+    #[test]
+    fn test_from_question() {
+        let data = data(&[("url", "http://example.net/repo")]);
+        let medium = MediumErrorData::from_question("software.medium_error", &data);
+        assert_eq!(
+            medium,
+            Some(Ok(MediumErrorData {
+                url: "http://example.net/repo".to_string()
+            }))
+        );
+        assert_eq!(
+            MediumErrorData::from_question("software.unsigned_file", &data),
+            None
+        );
+    }
+}
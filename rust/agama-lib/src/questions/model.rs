@@ -2,6 +2,8 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+use super::data::{QuestionData, QuestionDataError};
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Question {
@@ -28,6 +30,15 @@ pub struct GenericQuestion {
     pub data: HashMap<String, String>,
 }
 
+impl GenericQuestion {
+    /// Returns the typed data of the question (see [super::data]).
+    ///
+    /// It returns `None` if the question does not belong to the class of `T`.
+    pub fn data_as<T: QuestionData>(&self) -> Option<Result<T, QuestionDataError>> {
+        T::from_question(&self.class, &self.data)
+    }
+}
+
 /// Facade of agama_lib::questions::WithPassword
 /// For fields details see it.
 /// Reason why it does not use directly WithPassword from lib
@@ -0,0 +1,449 @@
+//! D-Bus proxies which survive the restart of the Agama services.
+//!
+//! The proxies built with [crate::connection] become useless when the D-Bus connection is lost
+//! (e.g., when the Agama bus is restarted). [ReconnectingConnection] keeps track of the connection
+//! and rebuilds it, using an exponential backoff, when a call fails because of a connection
+//! problem. The [ReconnectingProxy] wrapper rebuilds the proxies on top of the new connection.
+//!
+//! ```no_run
+//! # use agama_lib::reconnect::ReconnectingConnection;
+//! # async fn monitor() {
+//! let connection = ReconnectingConnection::new("unix:path=/run/agama/bus")
+//!     .await
+//!     .unwrap();
+//! let mut state = connection.state();
+//! tokio::spawn(async move {
+//!     while state.changed().await.is_ok() {
+//!         println!("D-Bus connection: {:?}", *state.borrow());
+//!     }
+//! });
+//!
+//! let manager = connection.manager();
+//! let phase = manager
+//!     .call(|proxy| async move { proxy.current_installation_phase().await })
+//!     .await
+//!     .unwrap();
+//! # }
+//! ```
+
+use std::{future::Future, marker::PhantomData, sync::Arc, time::Duration};
+
+use futures_util::stream;
+use tokio::sync::{watch, RwLock};
+use tokio_stream::{Stream, StreamExt};
+use zbus::{fdo, ProxyDefault};
+
+use crate::{
+    connection_to,
+    error::ServiceError,
+    proxies::{IssuesProxy, Manager1Proxy, ProgressProxy, Questions1Proxy},
+};
+
+/// Exponential backoff settings.
+#[derive(Clone, Debug)]
+pub struct Backoff {
+    /// Delay before the first attempt.
+    pub initial: Duration,
+    /// Maximum delay between attempts.
+    pub max: Duration,
+    /// Factor to multiply the delay by after each attempt.
+    pub factor: u32,
+    /// Maximum number of attempts. `None` means trying forever.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(250),
+            max: Duration::from_secs(30),
+            factor: 2,
+            max_attempts: None,
+        }
+    }
+}
+
+impl Backoff {
+    /// Returns the delay before the given attempt (starting at 0).
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = self.factor.saturating_pow(attempt);
+        self.initial.saturating_mul(factor).min(self.max)
+    }
+
+    /// Whether the given attempt (starting at 0) exceeds the maximum number of attempts.
+    fn exhausted(&self, attempt: u32) -> bool {
+        self.max_attempts.is_some_and(|max| attempt >= max)
+    }
+}
+
+/// State of a [ReconnectingConnection].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConnectionState {
+    /// The connection is working.
+    Connected,
+    /// The connection or the service was lost and it is trying to recover.
+    Reconnecting {
+        /// Current attempt (starting at 0).
+        attempt: u32,
+    },
+}
+
+/// Determines whether the error is caused by a connection problem (so the call can be retried).
+///
+/// It includes the lost connections and the services which are not available (e.g., because they
+/// are restarting).
+pub fn is_connection_error(error: &zbus::Error) -> bool {
+    needs_new_connection(error) || is_service_unavailable(error)
+}
+
+// Whether the connection itself is broken.
+fn needs_new_connection(error: &zbus::Error) -> bool {
+    match error {
+        zbus::Error::InputOutput(_) => true,
+        zbus::Error::FDO(error) => matches!(**error, fdo::Error::Disconnected(_)),
+        _ => false,
+    }
+}
+
+// Whether the service is not available (yet).
+fn is_service_unavailable(error: &zbus::Error) -> bool {
+    const UNAVAILABLE_ERRORS: [&str; 3] = [
+        "org.freedesktop.DBus.Error.ServiceUnknown",
+        "org.freedesktop.DBus.Error.NameHasNoOwner",
+        "org.freedesktop.DBus.Error.NoReply",
+    ];
+
+    match error {
+        zbus::Error::FDO(error) => matches!(
+            **error,
+            fdo::Error::ServiceUnknown(_) | fdo::Error::NameHasNoOwner(_) | fdo::Error::NoReply(_)
+        ),
+        zbus::Error::MethodError(name, _, _) => UNAVAILABLE_ERRORS.contains(&name.as_str()),
+        _ => false,
+    }
+}
+
+/// D-Bus connection which is rebuilt when it is lost.
+///
+/// It can be cloned to share the connection.
+#[derive(Clone)]
+pub struct ReconnectingConnection {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    address: String,
+    backoff: Backoff,
+    connection: RwLock<zbus::Connection>,
+    state: watch::Sender<ConnectionState>,
+}
+
+impl ReconnectingConnection {
+    /// Connects to the given address using the default backoff settings.
+    ///
+    /// * `address`: D-Bus address (e.g., "unix:path=/run/agama/bus").
+    pub async fn new(address: &str) -> Result<Self, ServiceError> {
+        Self::with_backoff(address, Backoff::default()).await
+    }
+
+    /// Connects to the given address.
+    ///
+    /// * `address`: D-Bus address (e.g., "unix:path=/run/agama/bus").
+    /// * `backoff`: backoff settings to use when reconnecting.
+    pub async fn with_backoff(address: &str, backoff: Backoff) -> Result<Self, ServiceError> {
+        let connection = connection_to(address).await?;
+        let (state, _) = watch::channel(ConnectionState::Connected);
+        let inner = Inner {
+            address: address.to_string(),
+            backoff,
+            connection: RwLock::new(connection),
+            state,
+        };
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
+    }
+
+    /// Returns the current connection.
+    pub async fn connection(&self) -> zbus::Connection {
+        self.inner.connection.read().await.clone()
+    }
+
+    /// Returns a receiver to watch the connection state.
+    pub fn state(&self) -> watch::Receiver<ConnectionState> {
+        self.inner.state.subscribe()
+    }
+
+    /// Builds a reconnecting proxy using the default destination and path of `P`.
+    pub fn proxy<P>(&self) -> ReconnectingProxy<P> {
+        ReconnectingProxy {
+            connection: self.clone(),
+            destination: None,
+            path: None,
+            proxy: PhantomData,
+        }
+    }
+
+    /// Builds a reconnecting proxy for the given destination and path.
+    ///
+    /// * `destination`: D-Bus service name (e.g., "org.opensuse.Agama.Software1").
+    /// * `path`: D-Bus object path (e.g., "/org/opensuse/Agama/Software1").
+    pub fn proxy_at<P>(&self, destination: &str, path: &str) -> ReconnectingProxy<P> {
+        ReconnectingProxy {
+            connection: self.clone(),
+            destination: Some(destination.to_string()),
+            path: Some(path.to_string()),
+            proxy: PhantomData,
+        }
+    }
+
+    /// Reconnecting proxy for the manager service.
+    pub fn manager(&self) -> ReconnectingProxy<Manager1Proxy<'static>> {
+        self.proxy()
+    }
+
+    /// Reconnecting proxy for the progress of the manager service.
+    pub fn progress(&self) -> ReconnectingProxy<ProgressProxy<'static>> {
+        self.proxy()
+    }
+
+    /// Reconnecting proxy for the questions service.
+    pub fn questions(&self) -> ReconnectingProxy<Questions1Proxy<'static>> {
+        self.proxy()
+    }
+
+    /// Reconnecting proxy for the issues of the given D-Bus object.
+    ///
+    /// * `destination`: D-Bus service name (e.g., "org.opensuse.Agama.Software1").
+    /// * `path`: D-Bus object path (e.g., "/org/opensuse/Agama/Software1").
+    pub fn issues(&self, destination: &str, path: &str) -> ReconnectingProxy<IssuesProxy<'static>> {
+        self.proxy_at(destination, path)
+    }
+
+    // Waits according to the backoff settings and, if needed, replaces the connection.
+    //
+    // If the connection was already replaced (e.g., by another proxy), it does not connect again.
+    //
+    // * `stale`: connection which failed.
+    // * `new_connection`: whether the connection must be replaced.
+    // * `attempt`: current attempt (starting at 0).
+    async fn recover(
+        &self,
+        stale: &zbus::Connection,
+        new_connection: bool,
+        attempt: u32,
+    ) -> Result<(), ServiceError> {
+        self.inner
+            .state
+            .send_replace(ConnectionState::Reconnecting { attempt });
+        tokio::time::sleep(self.inner.backoff.delay(attempt)).await;
+        if !new_connection {
+            return Ok(());
+        }
+
+        let mut current = self.inner.connection.write().await;
+        if current.unique_name() != stale.unique_name() {
+            return Ok(());
+        }
+
+        *current = connection_to(&self.inner.address).await?;
+        log::info!("Reconnected to the D-Bus at {}", &self.inner.address);
+        Ok(())
+    }
+
+    fn connected(&self) {
+        self.inner.state.send_if_modified(|state| {
+            let changed = *state != ConnectionState::Connected;
+            *state = ConnectionState::Connected;
+            changed
+        });
+    }
+}
+
+/// D-Bus proxy which is rebuilt when the connection is lost.
+pub struct ReconnectingProxy<P> {
+    connection: ReconnectingConnection,
+    destination: Option<String>,
+    path: Option<String>,
+    proxy: PhantomData<fn() -> P>,
+}
+
+impl<P> Clone for ReconnectingProxy<P> {
+    fn clone(&self) -> Self {
+        Self {
+            connection: self.connection.clone(),
+            destination: self.destination.clone(),
+            path: self.path.clone(),
+            proxy: PhantomData,
+        }
+    }
+}
+
+impl<P> ReconnectingProxy<P>
+where
+    P: From<zbus::Proxy<'static>> + ProxyDefault + Send + Sync + 'static,
+{
+    /// Builds the proxy using the current connection.
+    pub async fn build(&self) -> Result<P, ServiceError> {
+        let connection = self.connection.connection().await;
+        Ok(self.build_on(&connection).await?)
+    }
+
+    async fn build_on(&self, connection: &zbus::Connection) -> zbus::Result<P> {
+        let mut builder = zbus::ProxyBuilder::<P>::new(connection);
+        if let Some(destination) = &self.destination {
+            builder = builder.destination(destination.clone())?;
+        }
+        if let Some(path) = &self.path {
+            builder = builder.path(path.clone())?;
+        }
+        builder.build().await
+    }
+
+    /// Runs the given function using the proxy, retrying it if the connection is lost.
+    ///
+    /// The function must be idempotent, as it might run several times.
+    ///
+    /// * `f`: function which receives the proxy and performs the D-Bus calls.
+    pub async fn call<T, F, Fut>(&self, f: F) -> Result<T, ServiceError>
+    where
+        F: Fn(P) -> Fut,
+        Fut: Future<Output = zbus::Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            let connection = self.connection.connection().await;
+            let result = match self.build_on(&connection).await {
+                Ok(proxy) => f(proxy).await,
+                Err(error) => Err(error),
+            };
+
+            match result {
+                Ok(value) => {
+                    self.connection.connected();
+                    return Ok(value);
+                }
+                Err(error)
+                    if is_connection_error(&error)
+                        && !self.connection.inner.backoff.exhausted(attempt) =>
+                {
+                    log::warn!("D-Bus call failed, retrying: {}", error);
+                    let new_connection = needs_new_connection(&error);
+                    // a failed reconnection is just another attempt
+                    if let Err(error) = self
+                        .connection
+                        .recover(&connection, new_connection, attempt)
+                        .await
+                    {
+                        log::warn!("Could not reconnect: {}", error);
+                    }
+                    attempt += 1;
+                }
+                Err(error) => return Err(error.into()),
+            }
+        }
+    }
+
+    /// Returns a stream which subscribes again when the connection is lost.
+    ///
+    /// The subscription is established before returning. When the underlying stream finishes
+    /// (the connection was closed), it reconnects and subscribes again using the `subscribe`
+    /// function. Hence, the property subscriptions are preserved across reconnections. The
+    /// stream finishes if the maximum number of attempts (see [Backoff]) is reached.
+    ///
+    /// ```no_run
+    /// # use agama_lib::reconnect::ReconnectingConnection;
+    /// # use tokio_stream::StreamExt;
+    /// # async fn watch(connection: ReconnectingConnection) {
+    /// let phases = connection
+    ///     .manager()
+    ///     .stream(|proxy| async move { proxy.receive_current_installation_phase_changed().await })
+    ///     .await
+    ///     .unwrap();
+    /// tokio::pin!(phases);
+    /// while let Some(change) = phases.next().await {
+    ///     println!("Phase: {:?}", change.get().await);
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// * `subscribe`: function which receives the proxy and returns the stream.
+    pub async fn stream<S, F, Fut>(
+        &self,
+        subscribe: F,
+    ) -> Result<impl Stream<Item = S::Item>, ServiceError>
+    where
+        S: Stream + Unpin,
+        F: Fn(P) -> Fut,
+        Fut: Future<Output = S>,
+    {
+        let proxy = self.build().await?;
+        let current = subscribe(proxy).await;
+        let state = (self.clone(), subscribe, Some(current));
+
+        let items = stream::unfold(state, |(this, subscribe, mut current)| async move {
+            let mut attempt = 0;
+            loop {
+                if let Some(inner) = current.as_mut() {
+                    if let Some(item) = inner.next().await {
+                        return Some((item, (this, subscribe, current)));
+                    }
+                    log::info!("D-Bus subscription finished, subscribing again");
+                    current = None;
+                }
+
+                if this.connection.inner.backoff.exhausted(attempt) {
+                    return None;
+                }
+                let connection = this.connection.connection().await;
+                if let Err(error) = this.connection.recover(&connection, true, attempt).await {
+                    log::warn!("Could not reconnect: {}", error);
+                }
+                attempt += 1;
+
+                if let Ok(proxy) = this.build().await {
+                    current = Some(subscribe(proxy).await);
+                    this.connection.connected();
+                }
+            }
+        });
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_connection_error, Backoff};
+    use std::{io, sync::Arc, time::Duration};
+    use zbus::fdo;
+
+    #[test]
+    fn test_backoff_delay() {
+        let backoff = Backoff {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(1),
+            factor: 2,
+            max_attempts: Some(3),
+        };
+        assert_eq!(backoff.delay(0), Duration::from_millis(100));
+        assert_eq!(backoff.delay(2), Duration::from_millis(400));
+        assert_eq!(backoff.delay(10), Duration::from_secs(1));
+        assert_eq!(backoff.delay(u32::MAX), Duration::from_secs(1));
+        assert!(!backoff.exhausted(2));
+        assert!(backoff.exhausted(3));
+        assert!(!Backoff::default().exhausted(u32::MAX));
+    }
+
+    #[test]
+    fn test_is_connection_error() {
+        let io_error = io::Error::new(io::ErrorKind::BrokenPipe, "broken pipe");
+        assert!(is_connection_error(&zbus::Error::InputOutput(Arc::new(
+            io_error
+        ))));
+        let unknown = fdo::Error::ServiceUnknown("org.opensuse.Agama1".to_string());
+        assert!(is_connection_error(&zbus::Error::FDO(Box::new(unknown))));
+        let failed = fdo::Error::Failed("failed".to_string());
+        assert!(!is_connection_error(&zbus::Error::FDO(Box::new(failed))));
+        assert!(!is_connection_error(&zbus::Error::Unsupported));
+    }
+}
@@ -0,0 +1,128 @@
+//! This module offers a typed representation of the `org.opensuse.Agama1.ServiceStatus` interface.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_repr::Serialize_repr;
+use zbus::{zvariant::OwnedValue, Connection};
+
+use crate::{
+    dbus::{get_property, get_u32_property},
+    error::ServiceError,
+    proxies::ServiceStatusProxy,
+};
+
+/// Status of a service.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize_repr)]
+#[repr(u32)]
+pub enum ServiceStatusValue {
+    /// The service is ready to process requests.
+    Idle = 0,
+    /// The service is processing a request.
+    Busy = 1,
+}
+
+impl TryFrom<u32> for ServiceStatusValue {
+    type Error = ServiceError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Idle),
+            1 => Ok(Self::Busy),
+            _ => Err(ServiceError::UnknownServiceStatus(value)),
+        }
+    }
+}
+
+/// Describes a service status as reported by the `All` D-Bus property.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ServiceStatusEntry {
+    /// Status ID (see [ServiceStatusValue]).
+    pub id: u32,
+    /// Status label (e.g., "idle").
+    pub label: String,
+}
+
+impl TryFrom<&HashMap<String, OwnedValue>> for ServiceStatusEntry {
+    type Error = ServiceError;
+
+    fn try_from(value: &HashMap<String, OwnedValue>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: get_u32_property(value, "id")?,
+            label: get_property(value, "label")?,
+        })
+    }
+}
+
+/// D-Bus client for the `org.opensuse.Agama1.ServiceStatus` interface.
+#[derive(Clone)]
+pub struct ServiceStatusClient<'a> {
+    proxy: ServiceStatusProxy<'a>,
+}
+
+impl<'a> ServiceStatusClient<'a> {
+    /// Builds a client for the status of the given D-Bus object.
+    ///
+    /// * `connection`: D-Bus connection.
+    /// * `destination`: D-Bus service name (e.g., "org.opensuse.Agama.Software1").
+    /// * `path`: D-Bus object path (e.g., "/org/opensuse/Agama/Software1").
+    pub async fn new(
+        connection: &Connection,
+        destination: &str,
+        path: &str,
+    ) -> Result<ServiceStatusClient<'a>, ServiceError> {
+        let proxy = ServiceStatusProxy::builder(connection)
+            .destination(destination.to_string())?
+            .path(path.to_string())?
+            .build()
+            .await?;
+        Ok(Self { proxy })
+    }
+
+    /// Returns the list of possible statuses.
+    ///
+    /// Use the `all` method of [ServiceStatusProxy] to get the raw values.
+    pub async fn all(&self) -> Result<Vec<ServiceStatusEntry>, ServiceError> {
+        let entries = self.proxy.all().await?;
+        entries.iter().map(ServiceStatusEntry::try_from).collect()
+    }
+
+    /// Returns the current status.
+    ///
+    /// Use the `current` method of [ServiceStatusProxy] to get the raw value.
+    pub async fn current(&self) -> Result<ServiceStatusValue, ServiceError> {
+        self.proxy.current().await?.try_into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ServiceStatusEntry, ServiceStatusValue};
+    use crate::dbus::to_owned_hash;
+    use std::collections::HashMap;
+    use zbus::zvariant::Value;
+
+    #[test]
+    fn test_service_status_value_try_from() {
+        assert_eq!(
+            ServiceStatusValue::try_from(0).unwrap(),
+            ServiceStatusValue::Idle
+        );
+        assert_eq!(
+            ServiceStatusValue::try_from(1).unwrap(),
+            ServiceStatusValue::Busy
+        );
+        assert!(ServiceStatusValue::try_from(2).is_err());
+    }
+
+    #[test]
+    fn test_service_status_entry_try_from() {
+        let entry = HashMap::from([("id", Value::new(1_i32)), ("label", Value::new("busy"))]);
+        let entry = ServiceStatusEntry::try_from(&to_owned_hash(&entry)).unwrap();
+        assert_eq!(entry.id, 1);
+        assert_eq!(entry.label, "busy");
+
+        let missing = HashMap::from([("id", Value::new(1_i32))]);
+        assert!(ServiceStatusEntry::try_from(&to_owned_hash(&missing)).is_err());
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! <https://freedesktop.org/Software/XKeyboardConfig>
 
+use crate::KeymapId;
 use quick_xml::de::from_str;
 use serde::Deserialize;
 use std::{error::Error, fs};
@@ -28,6 +29,26 @@ impl XkbConfigRegistry {
     pub fn from_system() -> Result<Self, Box<dyn Error>> {
         Self::from(DB_PATH)
     }
+
+    /// Returns the description of the given keymap (e.g., "German" for "de"), if it is known.
+    ///
+    /// - `keymap`: keymap to describe.
+    pub fn description(&self, keymap: &KeymapId) -> Option<&str> {
+        let layout = self
+            .layout_list
+            .layouts
+            .iter()
+            .find(|l| l.config_item.name == keymap.layout)?;
+        match &keymap.variant {
+            None => Some(&layout.config_item.description),
+            Some(variant) => layout
+                .variants_list
+                .variants
+                .iter()
+                .find(|v| &v.config_item.name == variant)
+                .map(|v| v.config_item.description.as_str()),
+        }
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -1,6 +1,7 @@
 use serde::Deserialize;
 
-use crate::ranked::{RankedLocales, RankedTerritories};
+use crate::ranked::{RankedLocales, RankedScripts, RankedTerritories};
+use crate::territory::Territory;
 
 #[derive(Debug, Deserialize)]
 pub struct Language {
@@ -8,9 +9,33 @@ pub struct Language {
     pub id: String,
     pub territories: RankedTerritories,
     pub locales: RankedLocales,
+    #[serde(default)]
+    pub scripts: RankedScripts,
     pub names: crate::localization::Localization,
 }
 
+impl Language {
+    /// Returns the script (ISO 15924 code, e.g., "Latn") used to write the language.
+    ///
+    /// When the territory is given, it prefers the scripts which are also used in that territory
+    /// (e.g., "Hant" for Chinese in Taiwan).
+    ///
+    /// * `territory`: territory where the language is used.
+    pub fn script_for(&self, territory: Option<&Territory>) -> Option<&str> {
+        let territory_script = territory.and_then(|t| {
+            self.scripts
+                .script
+                .iter()
+                .filter_map(|s| t.scripts.rank_of(&s.id).map(|rank| (rank, s)))
+                .max_by_key(|(rank, _)| *rank)
+                .map(|(_, s)| s)
+        });
+        territory_script
+            .or_else(|| self.scripts.first())
+            .map(|s| s.id.as_str())
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Languages {
     pub language: Vec<Language>,
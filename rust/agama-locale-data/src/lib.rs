@@ -21,6 +21,24 @@ use keyboard::xkeyboard;
 
 pub use locale::{InvalidKeymap, InvalidLocaleCode, KeymapId, LocaleId};
 
+const KEYBOARDS_FILE: &str = "/usr/share/langtable/data/keyboards.xml.gz";
+const LANGUAGES_FILE: &str = "/usr/share/langtable/data/languages.xml.gz";
+const TERRITORIES_FILE: &str = "/usr/share/langtable/data/territories.xml.gz";
+const TIMEZONE_PARTS_FILE: &str = "/usr/share/langtable/data/timezoneidparts.xml.gz";
+const ZONE_TAB_FILE: &str = "/usr/share/zoneinfo/zone.tab";
+
+/// Data files read by this crate (from langtable and the timezone database).
+pub const DATA_FILES: [&str; 5] = [
+    KEYBOARDS_FILE,
+    LANGUAGES_FILE,
+    TERRITORIES_FILE,
+    TIMEZONE_PARTS_FILE,
+    ZONE_TAB_FILE,
+];
+
+/// Version of the IANA timezone database the list of timezones is built from (e.g., "2024a").
+pub const TZDB_VERSION: &str = chrono_tz::IANA_TZDB_VERSION;
+
 fn file_reader(file_path: &str) -> anyhow::Result<impl BufRead> {
     let file = File::open(file_path)
         .with_context(|| format!("Failed to read langtable-data ({})", file_path))?;
@@ -30,8 +48,7 @@ fn file_reader(file_path: &str) -> anyhow::Result<impl BufRead> {
 
 /// Gets list of X11 keyboards structs
 pub fn get_xkeyboards() -> anyhow::Result<xkeyboard::XKeyboards> {
-    const FILE_PATH: &str = "/usr/share/langtable/data/keyboards.xml.gz";
-    let reader = file_reader(FILE_PATH)?;
+    let reader = file_reader(KEYBOARDS_FILE)?;
     let mut deserializer = Deserializer::from_reader(reader);
     let ret = xkeyboard::XKeyboards::deserialize(&mut deserializer)
         .context("Failed to deserialize keyboard entry")?;
@@ -62,10 +79,26 @@ pub fn get_localectl_keymaps() -> anyhow::Result<Vec<KeymapId>> {
     Ok(ret)
 }
 
+/// Returns the description of the given keymap (e.g., "German" for "de").
+///
+/// It reads the description from the X Keyboard Configuration Database, without running
+/// `localectl`. It returns `None` if the keymap is unknown or the database cannot be read.
+///
+/// ```no_run
+/// use agama_locale_data::KeymapId;
+///
+/// let de: KeymapId = "de".parse().unwrap();
+/// let description = agama_locale_data::get_keymap_description(&de);
+/// assert_eq!(description, Some("German".to_string()));
+/// ```
+pub fn get_keymap_description(keymap: &KeymapId) -> Option<String> {
+    let registry = keyboard::XkbConfigRegistry::from_system().ok()?;
+    registry.description(keymap).map(str::to_string)
+}
+
 /// Returns struct which contain list of known languages
 pub fn get_languages() -> anyhow::Result<language::Languages> {
-    const FILE_PATH: &str = "/usr/share/langtable/data/languages.xml.gz";
-    let reader = file_reader(FILE_PATH)?;
+    let reader = file_reader(LANGUAGES_FILE)?;
     let mut deserializer = Deserializer::from_reader(reader);
     let ret = language::Languages::deserialize(&mut deserializer)
         .context("Failed to deserialize language entry")?;
@@ -74,8 +107,7 @@ pub fn get_languages() -> anyhow::Result<language::Languages> {
 
 /// Returns struct which contain list of known territories
 pub fn get_territories() -> anyhow::Result<territory::Territories> {
-    const FILE_PATH: &str = "/usr/share/langtable/data/territories.xml.gz";
-    let reader = file_reader(FILE_PATH)?;
+    let reader = file_reader(TERRITORIES_FILE)?;
     let mut deserializer = Deserializer::from_reader(reader);
     let ret = territory::Territories::deserialize(&mut deserializer)
         .context("Failed to deserialize territory entry")?;
@@ -84,8 +116,7 @@ pub fn get_territories() -> anyhow::Result<territory::Territories> {
 
 /// Returns struct which contain list of known parts of timezones. Useful for translation
 pub fn get_timezone_parts() -> anyhow::Result<timezone_part::TimezoneIdParts> {
-    const FILE_PATH: &str = "/usr/share/langtable/data/timezoneidparts.xml.gz";
-    let reader = file_reader(FILE_PATH)?;
+    let reader = file_reader(TIMEZONE_PARTS_FILE)?;
     let mut deserializer = Deserializer::from_reader(reader);
     let ret = timezone_part::TimezoneIdParts::deserialize(&mut deserializer)
         .context("Failed to deserialize timezone part entry")?;
@@ -96,9 +127,8 @@ pub fn get_timezone_parts() -> anyhow::Result<timezone_part::TimezoneIdParts> {
 /// the city that is used to name the timezone). The information is read from the
 /// file /usr/share/zoneinfo/zone.tab.
 pub fn get_timezone_countries() -> anyhow::Result<HashMap<String, String>> {
-    const FILE_PATH: &str = "/usr/share/zoneinfo/zone.tab";
-    let content = std::fs::read_to_string(FILE_PATH)
-        .with_context(|| format!("Failed to read {}", FILE_PATH))?;
+    let content = std::fs::read_to_string(ZONE_TAB_FILE)
+        .with_context(|| format!("Failed to read {}", ZONE_TAB_FILE))?;
 
     let countries = content
         .lines()
@@ -122,6 +152,18 @@ pub fn get_timezones() -> Vec<String> {
         .collect()
 }
 
+/// Returns the timezone that replaces the given deprecated one (e.g., "Asia/Kolkata" for
+/// "Asia/Calcutta").
+///
+/// The comparison is case insensitive. It returns `None` if the given timezone is not a known
+/// alias.
+pub fn timezone_alias_target(alias: &str) -> Option<&'static str> {
+    crate::deprecated_timezones::TIMEZONE_ALIASES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(alias))
+        .map(|(_, target)| *target)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -133,6 +175,36 @@ mod tests {
         assert_eq!(first.id, "ad")
     }
 
+    #[test]
+    fn test_get_keymap_description() {
+        let description = |id: &str| get_keymap_description(&id.parse().unwrap());
+        assert_eq!(description("de"), Some("German".to_string()));
+        assert_eq!(
+            description("de(nodeadkeys)"),
+            Some("German (no dead keys)".to_string())
+        );
+        assert_eq!(description("de(unknown)"), None);
+        assert_eq!(description("unknown"), None);
+    }
+
+    #[test]
+    fn test_timezone_alias_target() {
+        assert_eq!(timezone_alias_target("Asia/Calcutta"), Some("Asia/Kolkata"));
+        assert_eq!(
+            timezone_alias_target("us/eastern"),
+            Some("America/New_York")
+        );
+        assert_eq!(timezone_alias_target("Europe/Berlin"), None);
+
+        let timezones = get_timezones();
+        for (_, target) in crate::deprecated_timezones::TIMEZONE_ALIASES {
+            assert!(
+                timezones.contains(&target.to_string()),
+                "{target} is unknown"
+            );
+        }
+    }
+
     #[test]
     fn test_get_languages() {
         let result = get_languages().unwrap();
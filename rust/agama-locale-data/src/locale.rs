@@ -6,6 +6,19 @@ use std::sync::OnceLock;
 use std::{fmt::Display, str::FromStr};
 use thiserror::Error;
 
+/// Locale identifier
+///
+/// It follows the glibc format: `language_TERRITORY.encoding@modifier`, where the encoding and
+/// the modifier are optional.
+///
+/// ```
+/// use agama_locale_data::LocaleId;
+///
+/// let id: LocaleId = "ca_ES.UTF-8@valencia".try_into().unwrap();
+/// assert_eq!(id.language, "ca");
+/// assert_eq!(id.modifier, Some("valencia".to_string()));
+/// assert_eq!(id.to_string(), "ca_ES.UTF-8@valencia");
+/// ```
 #[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct LocaleId {
     // ISO-639
@@ -13,6 +26,9 @@ pub struct LocaleId {
     // ISO-3166
     pub territory: String,
     pub encoding: String,
+    // e.g., "valencia" or "euro"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modifier: Option<String>,
 }
 
 impl Display for LocaleId {
@@ -21,7 +37,11 @@ impl Display for LocaleId {
             f,
             "{}_{}.{}",
             &self.language, &self.territory, &self.encoding
-        )
+        )?;
+        if let Some(modifier) = &self.modifier {
+            write!(f, "@{}", modifier)?;
+        }
+        Ok(())
     }
 }
 
@@ -31,6 +51,7 @@ impl Default for LocaleId {
             language: "en".to_string(),
             territory: "US".to_string(),
             encoding: "UTF-8".to_string(),
+            modifier: None,
         }
     }
 }
@@ -39,12 +60,19 @@ impl Default for LocaleId {
 #[error("Not a valid locale string: {0}")]
 pub struct InvalidLocaleCode(String);
 
+impl InvalidLocaleCode {
+    /// Returns the value that could not be parsed.
+    pub fn value(&self) -> &str {
+        &self.0
+    }
+}
+
 impl TryFrom<&str> for LocaleId {
     type Error = InvalidLocaleCode;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         let locale_regexp: Regex =
-            Regex::new(r"^([[:alpha:]]+)_([[:alpha:]]+)(?:\.(.+))?").unwrap();
+            Regex::new(r"^([[:alpha:]]+)_([[:alpha:]]+)(?:\.([^@]+))?(?:@(.+))?").unwrap();
 
         let captures = locale_regexp
             .captures(value)
@@ -60,6 +88,7 @@ impl TryFrom<&str> for LocaleId {
             language: captures.get(1).unwrap().as_str().to_string(),
             territory: captures.get(2).unwrap().as_str().to_string(),
             encoding,
+            modifier: captures.get(4).map(|m| m.as_str().to_string()),
         })
     }
 }
@@ -99,6 +128,13 @@ impl Default for KeymapId {
 #[error("Invalid keymap ID: {0}")]
 pub struct InvalidKeymap(String);
 
+impl InvalidKeymap {
+    /// Returns the value that could not be parsed.
+    pub fn value(&self) -> &str {
+        &self.0
+    }
+}
+
 impl KeymapId {
     pub fn dashed(&self) -> String {
         if let Some(variant) = &self.variant {
@@ -161,9 +197,26 @@ impl FromStr for KeymapId {
 
 #[cfg(test)]
 mod test {
-    use super::KeymapId;
+    use super::{KeymapId, LocaleId};
     use std::str::FromStr;
 
+    #[test]
+    fn test_parse_locale_id_modifier() {
+        let locale = LocaleId::try_from("ca_ES.UTF-8@valencia").unwrap();
+        assert_eq!(locale.encoding, "UTF-8");
+        assert_eq!(locale.modifier, Some("valencia".to_string()));
+        assert_eq!(locale.to_string(), "ca_ES.UTF-8@valencia");
+
+        let locale = LocaleId::try_from("de_DE@euro").unwrap();
+        assert_eq!(locale.encoding, "UTF-8");
+        assert_eq!(locale.modifier, Some("euro".to_string()));
+        assert_eq!(locale.to_string(), "de_DE.UTF-8@euro");
+
+        let locale = LocaleId::try_from("es_ES.UTF-8").unwrap();
+        assert_eq!(locale.modifier, None);
+        assert_eq!(locale.to_string(), "es_ES.UTF-8");
+    }
+
     #[test]
     fn test_parse_keymap_id() {
         let keymap_id0 = KeymapId::from_str("es").unwrap();
@@ -41,3 +41,50 @@ pub struct RankedLocales {
     #[serde(default)]
     pub locale: Vec<RankedLocale>,
 }
+
+#[derive(Debug, Deserialize)]
+pub struct RankedTimezone {
+    #[serde(rename(deserialize = "timezoneId"))]
+    pub id: String,
+    /// Bigger rank means it is more important
+    pub rank: u16,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RankedTimezones {
+    #[serde(default)]
+    pub timezone: Vec<RankedTimezone>,
+}
+
+impl RankedTimezones {
+    /// Returns the rank of the given timezone, if it is included.
+    pub fn rank_of(&self, id: &str) -> Option<u16> {
+        self.timezone.iter().find(|t| t.id == id).map(|t| t.rank)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RankedScript {
+    #[serde(rename(deserialize = "scriptId"))]
+    pub id: String,
+    /// Bigger rank means it is more important
+    pub rank: u16,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RankedScripts {
+    #[serde(default)]
+    pub script: Vec<RankedScript>,
+}
+
+impl RankedScripts {
+    /// Returns the most important script, if any.
+    pub fn first(&self) -> Option<&RankedScript> {
+        self.script.iter().max_by_key(|s| s.rank)
+    }
+
+    /// Returns the rank of the given script, if it is included.
+    pub fn rank_of(&self, id: &str) -> Option<u16> {
+        self.script.iter().find(|s| s.id == id).map(|s| s.rank)
+    }
+}
@@ -5,6 +5,10 @@ pub struct Territory {
     #[serde(rename(deserialize = "territoryId"))]
     pub id: String,
     pub languages: crate::ranked::RankedLanguages,
+    #[serde(default)]
+    pub scripts: crate::ranked::RankedScripts,
+    #[serde(default)]
+    pub timezones: crate::ranked::RankedTimezones,
     pub names: crate::localization::Localization,
 }
 
@@ -6,7 +6,10 @@ use axum::{
 };
 use serde_json::json;
 
-use crate::{l10n::LocaleError, questions::QuestionsError};
+use crate::{
+    l10n::{LocaleError, LocaleErrorBody},
+    questions::QuestionsError,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -40,11 +43,80 @@ impl From<Error> for zbus::fdo::Error {
     }
 }
 
+impl Error {
+    /// Returns the HTTP status code for the error.
+    ///
+    /// The localization errors have their own mapping (see [LocaleError::status_code]). The rest
+    /// of errors are reported as "400 Bad Request".
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Self::Locale(error) => error.status_code(),
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
+        let status = self.status_code();
+        if let Self::Locale(error) = &self {
+            return (status, Json(LocaleErrorBody::from(error))).into_response();
+        }
+
         let body = json!({
             "error": self.to_string()
         });
-        (StatusCode::BAD_REQUEST, Json(body)).into_response()
+        (status, Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+    use crate::l10n::LocaleError;
+    use agama_locale_data::{KeymapId, LocaleId};
+    use axum::{http::StatusCode, response::IntoResponse};
+
+    fn status(error: LocaleError) -> StatusCode {
+        Error::Locale(error).into_response().status()
+    }
+
+    #[test]
+    fn test_locale_error_status() {
+        assert_eq!(
+            status(LocaleError::UnknownLocale("xx_XX".to_string())),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert_eq!(
+            status(LocaleError::UnknownTimezone("Unknown/Unknown".to_string())),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert_eq!(
+            status(LocaleError::UnknownKeymap(KeymapId::default())),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+
+        let invalid_keymap = "us(".parse::<KeymapId>().unwrap_err();
+        assert_eq!(
+            status(LocaleError::InvalidKeymap(invalid_keymap)),
+            StatusCode::BAD_REQUEST
+        );
+        let invalid_locale = LocaleId::try_from("wrong").unwrap_err();
+        assert_eq!(
+            status(LocaleError::InvalidLocale(invalid_locale)),
+            StatusCode::BAD_REQUEST
+        );
+
+        let io_error = std::io::Error::other("failed");
+        assert_eq!(
+            status(LocaleError::Commit(io_error)),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[test]
+    fn test_other_error_status() {
+        let error = Error::Anyhow("failed".to_string());
+        assert_eq!(error.into_response().status(), StatusCode::BAD_REQUEST);
     }
 }
@@ -1,16 +1,23 @@
+mod collation;
 mod dbus;
 pub mod error;
 pub mod helpers;
+mod history;
 mod keyboard;
 pub mod l10n;
 mod locale;
+mod metrics;
+mod preview;
+mod recent;
 mod timezone;
+mod tools;
 pub mod web;
 
 pub use agama_lib::localization::model::LocaleConfig;
 pub use dbus::export_dbus_objects;
-pub use error::LocaleError;
-pub use keyboard::Keymap;
+pub use error::{LocaleError, LocaleErrorBody};
+pub use keyboard::{Keymap, KeymapMatch, UiKeymapMethod};
 pub use l10n::L10n;
-pub use locale::LocaleEntry;
+pub use locale::{LanguageEntry, LocaleEntry, SelectedLocale, TextDirection};
+pub use metrics::L10nMetricsSnapshot;
 pub use timezone::TimezoneEntry;
@@ -0,0 +1,151 @@
+//! Locale-aware sorting of texts.
+//!
+//! The accented letters are usually sorted as their base letter (e.g., "é" as "e"), but some
+//! languages consider them as separate letters. For instance, Swedish sorts "å", "ä" and "ö"
+//! after "z". This module implements a simple collation which covers those cases for the most
+//! common languages, without depending on ICU.
+
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+// Letters sorted as separate letters, by language. Each letter is sorted right after the given
+// one (and after the previous letters in the list which are sorted after the same one).
+const TAILORINGS: [(&str, &[(char, char)]); 12] = [
+    ("cs", &[('č', 'c'), ('ř', 'r'), ('š', 's'), ('ž', 'z')]),
+    ("da", &[('æ', 'z'), ('ø', 'z'), ('å', 'z')]),
+    ("es", &[('ñ', 'n')]),
+    ("fi", &[('å', 'z'), ('ä', 'z'), ('ö', 'z')]),
+    ("hu", &[('ö', 'o'), ('ő', 'o'), ('ü', 'u'), ('ű', 'u')]),
+    ("nb", &[('æ', 'z'), ('ø', 'z'), ('å', 'z')]),
+    ("nn", &[('æ', 'z'), ('ø', 'z'), ('å', 'z')]),
+    ("no", &[('æ', 'z'), ('ø', 'z'), ('å', 'z')]),
+    (
+        "pl",
+        &[
+            ('ą', 'a'),
+            ('ć', 'c'),
+            ('ę', 'e'),
+            ('ł', 'l'),
+            ('ń', 'n'),
+            ('ó', 'o'),
+            ('ś', 's'),
+            ('ź', 'z'),
+            ('ż', 'z'),
+        ],
+    ),
+    (
+        "sk",
+        &[('ä', 'a'), ('č', 'c'), ('ô', 'o'), ('š', 's'), ('ž', 'z')],
+    ),
+    ("sv", &[('å', 'z'), ('ä', 'z'), ('ö', 'z')]),
+    (
+        "tr",
+        &[
+            ('ç', 'c'),
+            ('ğ', 'g'),
+            ('ı', 'h'),
+            ('ö', 'o'),
+            ('ş', 's'),
+            ('ü', 'u'),
+        ],
+    ),
+];
+
+/// Collation (sorting rules) for a language.
+#[derive(Clone, Copy, Debug)]
+pub struct Collation {
+    tailoring: &'static [(char, char)],
+}
+
+impl Collation {
+    /// Returns the collation for the given language.
+    ///
+    /// The languages without specific rules use the default collation, which sorts the accented
+    /// letters as their base letter.
+    ///
+    /// * `language`: language code (e.g., "sv").
+    pub fn for_language(language: &str) -> Self {
+        let tailoring = TAILORINGS
+            .iter()
+            .find(|(l, _)| *l == language)
+            .map_or(&[][..], |(_, t)| *t);
+        Self { tailoring }
+    }
+
+    /// Sorts the given items using the collation.
+    ///
+    /// The texts are compared ignoring the case and the accents of the letters which are not
+    /// separate letters in the language. When they are equal in that regard, the accents and
+    /// then the case decide the order.
+    ///
+    /// * `items`: items to sort.
+    /// * `text`: function to get the text to sort each item by.
+    pub fn sort_by<T>(&self, items: &mut [T], text: impl Fn(&T) -> String) {
+        items.sort_by_cached_key(|i| self.full_key(&text(i)));
+    }
+
+    // Returns the primary key (letters ignoring the case and the non-significant accents),
+    // followed by the lowercase text and the text itself to break ties.
+    fn full_key(&self, text: &str) -> (Vec<(char, usize)>, String, String) {
+        (self.sort_key(text), text.to_lowercase(), text.to_string())
+    }
+
+    // Returns the letters of the text, ignoring the case and the accents of the letters which
+    // are not separate letters in the language. The separate letters are represented by the
+    // letter they are sorted after and their position in the tailoring.
+    fn sort_key(&self, text: &str) -> Vec<(char, usize)> {
+        text.nfc()
+            .flat_map(char::to_lowercase)
+            .flat_map(|c| match self.tailoring.iter().position(|(l, _)| *l == c) {
+                Some(index) => vec![(self.tailoring[index].1, index + 1)],
+                None => c
+                    .to_string()
+                    .nfd()
+                    .filter(|c| !is_combining_mark(*c))
+                    .map(|c| (c, 0))
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Collation;
+
+    fn sorted(language: &str, words: &[&str]) -> Vec<String> {
+        let mut words: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+        Collation::for_language(language).sort_by(&mut words, |w| w.clone());
+        words
+    }
+
+    #[test]
+    fn test_swedish_collation() {
+        assert_eq!(
+            sorted("sv", &["Östersund", "Zürich", "Åre", "Ängelholm", "Arboga"]),
+            vec!["Arboga", "Zürich", "Åre", "Ängelholm", "Östersund"]
+        );
+    }
+
+    #[test]
+    fn test_default_collation() {
+        assert_eq!(
+            sorted(
+                "de",
+                &["Zürich", "Österreich", "Osnabrück", "Ägypten", "Albanien"]
+            ),
+            vec!["Ägypten", "Albanien", "Osnabrück", "Österreich", "Zürich"]
+        );
+        assert_eq!(
+            sorted("en", &["resume", "Résumé", "résumé", "Resume"]),
+            vec!["Resume", "resume", "Résumé", "résumé"]
+        );
+    }
+
+    #[test]
+    fn test_spanish_collation() {
+        assert_eq!(
+            sorted("es", &["ñu", "nube", "oso"]),
+            vec!["nube", "ñu", "oso"]
+        );
+    }
+}
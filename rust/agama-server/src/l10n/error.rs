@@ -1,15 +1,193 @@
-use agama_locale_data::{InvalidKeymap, KeymapId};
+use agama_locale_data::{InvalidKeymap, InvalidLocaleCode, KeymapId};
+use axum::http::StatusCode;
+use serde::Serialize;
+use serde_json::{json, Value};
 
+/// Localization errors.
+///
+/// Each error is mapped to an HTTP status code (see [LocaleError::status_code]).
 #[derive(thiserror::Error, Debug)]
 pub enum LocaleError {
     #[error("Unknown locale code: {0}")]
     UnknownLocale(String),
+    #[error("Unknown locale codes: {}", .0.join(", "))]
+    UnknownLocales(Vec<String>),
+    #[error("At least one locale is required")]
+    NoLocales,
+    #[error("Duplicated locale codes: {}", .0.join(", "))]
+    DuplicatedLocales(Vec<String>),
+    #[error("Invalid locale: {0}")]
+    InvalidLocale(#[from] InvalidLocaleCode),
     #[error("Unknown timezone: {0}")]
     UnknownTimezone(String),
+    #[error("The user interface is not translated to {0}")]
+    TranslationUnavailable(String),
     #[error("Unknown keymap: {0}")]
     UnknownKeymap(KeymapId),
     #[error("Invalid keymap: {0}")]
     InvalidKeymap(#[from] InvalidKeymap),
+    #[error("The keymap already exists: {0}")]
+    DuplicatedKeymap(KeymapId),
+    #[error("Unknown keyboard options: {}", .0.join(", "))]
+    UnknownKeyboardOptions(Vec<String>),
+    #[error("Invalid NTP servers: {}", .0.join(", "))]
+    InvalidNtpServers(Vec<String>),
+    #[error("Missing required field: {0}")]
+    MissingField(String),
+    #[error("Unknown fields: {}", .0.join(", "))]
+    UnknownFields(Vec<String>),
+    #[error("The configuration was modified by someone else (current ETag: {0})")]
+    ConfigChanged(String),
+    #[error("The keymap cannot be changed, missing tools: {}", .0.join(", "))]
+    MissingTools(Vec<String>),
+    #[error("The localization data could not be loaded: {0}")]
+    DataUnavailable(String),
     #[error("Could not apply the changes")]
     Commit(#[from] std::io::Error),
+    #[error("The changes were only partially applied: {0}")]
+    PartialCommit(String),
+}
+
+impl LocaleError {
+    /// Returns a stable code to identify the error (e.g., "unknown_locale").
+    ///
+    /// Unlike the message, it is not expected to change, so the clients can rely on it.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::UnknownLocale(_) => "unknown_locale",
+            Self::UnknownLocales(_) => "unknown_locales",
+            Self::NoLocales => "no_locales",
+            Self::DuplicatedLocales(_) => "duplicated_locales",
+            Self::InvalidLocale(_) => "invalid_locale",
+            Self::UnknownTimezone(_) => "unknown_timezone",
+            Self::TranslationUnavailable(_) => "translation_unavailable",
+            Self::UnknownKeymap(_) => "unknown_keymap",
+            Self::InvalidKeymap(_) => "invalid_keymap",
+            Self::DuplicatedKeymap(_) => "duplicated_keymap",
+            Self::UnknownKeyboardOptions(_) => "unknown_keyboard_options",
+            Self::InvalidNtpServers(_) => "invalid_ntp_servers",
+            Self::MissingField(_) => "missing_field",
+            Self::UnknownFields(_) => "unknown_fields",
+            Self::ConfigChanged(_) => "config_changed",
+            Self::MissingTools(_) => "missing_tools",
+            Self::DataUnavailable(_) => "data_unavailable",
+            Self::Commit(_) => "commit_failed",
+            Self::PartialCommit(_) => "partial_commit",
+        }
+    }
+
+    /// Returns the offending value, if any.
+    ///
+    /// It is a list for the errors involving several values (e.g., `UnknownLocales`).
+    pub fn value(&self) -> Option<Value> {
+        match self {
+            Self::UnknownLocale(value)
+            | Self::UnknownTimezone(value)
+            | Self::TranslationUnavailable(value)
+            | Self::MissingField(value)
+            | Self::ConfigChanged(value) => Some(json!(value)),
+            Self::UnknownLocales(values)
+            | Self::DuplicatedLocales(values)
+            | Self::UnknownKeyboardOptions(values)
+            | Self::InvalidNtpServers(values)
+            | Self::UnknownFields(values)
+            | Self::MissingTools(values) => Some(json!(values)),
+            Self::InvalidLocale(error) => Some(json!(error.value())),
+            Self::UnknownKeymap(keymap) | Self::DuplicatedKeymap(keymap) => {
+                Some(json!(keymap.to_string()))
+            }
+            Self::InvalidKeymap(error) => Some(json!(error.value())),
+            Self::NoLocales
+            | Self::Commit(_)
+            | Self::PartialCommit(_)
+            | Self::DataUnavailable(_) => None,
+        }
+    }
+
+    /// Returns the HTTP status code for the error.
+    ///
+    /// * 400 (Bad Request): the value is malformed (e.g., "es-" as a locale) or missing, or the
+    ///   configuration contains unknown fields.
+    /// * 422 (Unprocessable Entity): the value is well-formed but unknown or not acceptable (e.g.,
+    ///   "xx_XX" as a locale or a UI locale the user interface is not translated to).
+    /// * 409 (Conflict): the keymap to add already exists.
+    /// * 412 (Precondition Failed): the configuration does not match the `If-Match` header.
+    /// * 500 (Internal Server Error): the changes could not be applied to the system.
+    /// * 503 (Service Unavailable): the tools to apply the keymap are missing or the localization
+    ///   data could not be loaded.
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Self::InvalidLocale(_)
+            | Self::InvalidKeymap(_)
+            | Self::InvalidNtpServers(_)
+            | Self::MissingField(_)
+            | Self::UnknownFields(_) => StatusCode::BAD_REQUEST,
+            Self::UnknownLocale(_)
+            | Self::UnknownLocales(_)
+            | Self::NoLocales
+            | Self::DuplicatedLocales(_)
+            | Self::UnknownTimezone(_)
+            | Self::TranslationUnavailable(_)
+            | Self::UnknownKeymap(_)
+            | Self::UnknownKeyboardOptions(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::DuplicatedKeymap(_) => StatusCode::CONFLICT,
+            Self::ConfigChanged(_) => StatusCode::PRECONDITION_FAILED,
+            Self::Commit(_) | Self::PartialCommit(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::MissingTools(_) | Self::DataUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
+
+/// Machine-readable representation of a [LocaleError].
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct LocaleErrorBody {
+    /// Stable error code (e.g., "unknown_locale").
+    error: &'static str,
+    /// Offending value (e.g., "xx_YY"), if any. It is a list for the errors involving several
+    /// values.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Object)]
+    value: Option<Value>,
+    /// Human-readable message (in English).
+    message: String,
+}
+
+impl From<&LocaleError> for LocaleErrorBody {
+    fn from(error: &LocaleError) -> Self {
+        Self {
+            error: error.code(),
+            value: error.value(),
+            message: error.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LocaleError, LocaleErrorBody};
+    use serde_json::json;
+
+    #[test]
+    fn test_error_body() {
+        let error = LocaleError::UnknownLocale("xx_YY".to_string());
+        let body = serde_json::to_value(LocaleErrorBody::from(&error)).unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "error": "unknown_locale",
+                "value": "xx_YY",
+                "message": "Unknown locale code: xx_YY"
+            })
+        );
+
+        let error = LocaleError::UnknownLocales(vec!["xx_YY".to_string(), "zz_ZZ".to_string()]);
+        let body = serde_json::to_value(LocaleErrorBody::from(&error)).unwrap();
+        assert_eq!(body["value"], json!(["xx_YY", "zz_ZZ"]));
+
+        let body = serde_json::to_value(LocaleErrorBody::from(&LocaleError::NoLocales)).unwrap();
+        assert_eq!(
+            body,
+            json!({ "error": "no_locales", "message": "At least one locale is required" })
+        );
+    }
 }
@@ -2,9 +2,10 @@
 //!
 //! FIXME: find a better place for the localization function
 
-use agama_locale_data::LocaleId;
+use agama_locale_data::{InvalidLocaleCode, LocaleId};
 use gettextrs::{bind_textdomain_codeset, setlocale, textdomain, LocaleCategory};
-use std::env;
+use std::{collections::HashSet, env, fs, net::IpAddr, path::Path};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
 
 /// Initializes the service locale.
 ///
@@ -26,3 +27,260 @@ pub fn set_service_locale(locale: &LocaleId) {
         log::warn!("Could not set the locale");
     }
 }
+
+/// Returns the locales for which there is a web UI translation in the given directory.
+///
+/// The translations are stored in `po.<locale>.js` files, where the locale can be a language
+/// (e.g., "po.de.js") or a language and a territory (e.g., "po.pt_BR.js").
+///
+/// * `dir`: directory containing the web UI.
+pub fn ui_translations(dir: &Path) -> HashSet<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return HashSet::new();
+    };
+
+    entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().into_string().ok()?;
+            let locale = name.strip_prefix("po.")?.strip_suffix(".js")?;
+            Some(locale.to_string())
+        })
+        .collect()
+}
+
+/// Determines whether the web UI is translated to the given locale.
+///
+/// English is always available because it is the language of the original texts. Otherwise, it
+/// looks for a translation for the language and territory (e.g., "pt_BR") or for the language
+/// only (e.g., "pt"), like the `/po.js` handler does.
+///
+/// * `locale`: locale to check.
+/// * `translations`: available translations (see [ui_translations]).
+pub fn has_ui_translation(locale: &LocaleId, translations: &HashSet<String>) -> bool {
+    locale.language == "en"
+        || translations.contains(&format!("{}_{}", locale.language, locale.territory))
+        || translations.contains(&locale.language)
+}
+
+/// Normalizes a text to make case and accent insensitive comparisons.
+///
+/// It decomposes the text, removes the combining marks (e.g., accents) and
+/// converts it to lowercase. For instance, "Español" becomes "espanol".
+///
+/// * `text`: text to normalize.
+pub fn normalize_text(text: &str) -> String {
+    text.nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Returns the Levenshtein distance between two texts.
+///
+/// It is the number of single-character insertions, deletions and substitutions needed to change
+/// one text into the other (e.g., 1 for "quertz" and "qwertz").
+///
+/// * `a`: first text.
+/// * `b`: second text.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Determines whether the text is a valid host: an IP address or a hostname.
+///
+/// The hostnames follow RFC 1123: dot-separated labels of up to 63 alphanumeric characters or
+/// hyphens, not starting or ending with a hyphen, and up to 253 characters in total. A trailing
+/// dot is accepted.
+///
+/// * `host`: host to check (e.g., "pool.ntp.org" or "192.168.1.1").
+pub fn is_valid_host(host: &str) -> bool {
+    if host.parse::<IpAddr>().is_ok() {
+        return true;
+    }
+    let name = host.strip_suffix('.').unwrap_or(host);
+    if name.is_empty() || name.len() > 253 {
+        return false;
+    }
+    name.split('.').all(|label| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    })
+}
+
+/// Parses a locale identifier, accepting several forms.
+///
+/// The accepted forms are "en_US.UTF-8", "en_US" (it defaults to "UTF-8") and "en-US" (a
+/// BCP 47 like tag). The "utf8" encoding (in any case) is normalized as "UTF-8". A modifier
+/// (e.g., "@valencia") is kept as it is.
+///
+/// * `locale`: locale identifier.
+pub fn parse_locale(locale: &str) -> Result<LocaleId, InvalidLocaleCode> {
+    let (locale, modifier) = match locale.trim().split_once('@') {
+        Some((locale, modifier)) => (locale, Some(modifier.to_string())),
+        None => (locale.trim(), None),
+    };
+    let locale = match locale.split_once('.') {
+        Some((name, encoding)) => format!("{}.{}", name.replace('-', "_"), encoding),
+        None => locale.replace('-', "_"),
+    };
+    let mut locale_id = LocaleId::try_from(locale.as_str())?;
+    if locale_id
+        .encoding
+        .replace('-', "")
+        .eq_ignore_ascii_case("utf8")
+    {
+        locale_id.encoding = "UTF-8".to_string();
+    }
+    locale_id.modifier = modifier;
+    Ok(locale_id)
+}
+
+/// Parses the value of an `Accept-Language` header.
+///
+/// It returns the language ranges sorted by their quality value (higher first), keeping the
+/// original order for ranges with the same quality. Ranges with a quality of 0 are discarded.
+///
+/// * `header`: header value (e.g., "es-ES,es;q=0.9,en;q=0.8").
+pub fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut ranges: Vec<(String, f32)> = header
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let range = parts.next()?.trim();
+            if range.is_empty() {
+                return None;
+            }
+            let quality = parts
+                .filter_map(|p| p.trim().strip_prefix("q="))
+                .find_map(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            Some((range.to_string(), quality))
+        })
+        .filter(|(_, quality)| *quality > 0.0)
+        .collect();
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranges.into_iter().map(|(range, _)| range).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        edit_distance, has_ui_translation, is_valid_host, normalize_text, parse_accept_language,
+        parse_locale, ui_translations,
+    };
+    use std::fs;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("qwertz", "qwertz"), 0);
+        assert_eq!(edit_distance("quertz", "qwertz"), 1);
+        assert_eq!(edit_distance("germn", "german"), 1);
+        assert_eq!(edit_distance("", "us"), 2);
+        assert_eq!(edit_distance("abc", "xyz"), 3);
+    }
+
+    #[test]
+    fn test_normalize_text() {
+        assert_eq!(normalize_text("Español"), "espanol");
+        assert_eq!(normalize_text("Português"), "portugues");
+        assert_eq!(normalize_text("Deutsch"), "deutsch");
+    }
+
+    #[test]
+    fn test_ui_translations() {
+        let dir = std::env::temp_dir().join(format!("agama-ui-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for file in ["po.de.js", "po.pt_BR.js", "index.js"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        let translations = ui_translations(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(translations.len(), 2);
+        for (locale, expected) in [
+            ("de_AT", true),
+            ("pt_BR", true),
+            ("pt_PT", false),
+            ("en_GB", true),
+            ("fr_FR", false),
+        ] {
+            let locale = parse_locale(locale).unwrap();
+            assert_eq!(has_ui_translation(&locale, &translations), expected);
+        }
+    }
+
+    #[test]
+    fn test_is_valid_host() {
+        for host in [
+            "pool.ntp.org",
+            "ntp1.example.net.",
+            "localhost",
+            "10.0.0.1",
+            "::1",
+        ] {
+            assert!(is_valid_host(host), "{host} should be valid");
+        }
+        for host in [
+            "",
+            ".",
+            "-ntp.org",
+            "ntp-.org",
+            "ntp..org",
+            "ntp_1.org",
+            "ntp.org:123",
+        ] {
+            assert!(!is_valid_host(host), "{host} should not be valid");
+        }
+        assert!(!is_valid_host(&format!("{}.org", "a".repeat(64))));
+    }
+
+    #[test]
+    fn test_parse_accept_language() {
+        let ranges = parse_accept_language("en;q=0.8, es-ES,fr;q=0, es;q=0.9,de;q=0.8");
+        assert_eq!(ranges, vec!["es-ES", "es", "en", "de"]);
+        assert!(parse_accept_language("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_locale() {
+        for locale in [
+            "en_US.UTF-8",
+            "en_US",
+            "en-US",
+            "en_US.utf8",
+            " en_US.utf-8",
+        ] {
+            assert_eq!(parse_locale(locale).unwrap().to_string(), "en_US.UTF-8");
+        }
+        assert_eq!(
+            parse_locale("de_DE.ISO-8859-1").unwrap().to_string(),
+            "de_DE.ISO-8859-1"
+        );
+        assert!(parse_locale("en").is_err());
+
+        for locale in [
+            "ca_ES.UTF-8@valencia",
+            "ca_ES@valencia",
+            "ca-ES.utf8@valencia",
+        ] {
+            assert_eq!(
+                parse_locale(locale).unwrap().to_string(),
+                "ca_ES.UTF-8@valencia"
+            );
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! This module implements a bounded history of localization configurations and keeps track of
+//! when each attribute was last changed.
+
+use super::LocaleConfig;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, VecDeque};
+
+/// Default number of configurations to keep in the history.
+pub const DEFAULT_HISTORY_DEPTH: usize = 10;
+
+/// Keeps the previous localization configurations, so the changes can be undone.
+///
+/// When the history is full, the oldest configuration is discarded.
+#[derive(Debug)]
+pub struct ConfigHistory {
+    snapshots: VecDeque<LocaleConfig>,
+    depth: usize,
+}
+
+impl ConfigHistory {
+    /// Creates an empty history.
+    ///
+    /// * `depth`: maximum number of configurations to keep.
+    pub fn new(depth: usize) -> Self {
+        Self {
+            snapshots: VecDeque::with_capacity(depth),
+            depth,
+        }
+    }
+
+    /// Adds a configuration to the history, discarding the oldest one if it is full.
+    ///
+    /// * `config`: configuration to add.
+    pub fn push(&mut self, config: LocaleConfig) {
+        if self.depth == 0 {
+            return;
+        }
+        if self.snapshots.len() == self.depth {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(config);
+    }
+
+    /// Removes and returns the most recent configuration, if any.
+    pub fn pop(&mut self) -> Option<LocaleConfig> {
+        self.snapshots.pop_back()
+    }
+
+    /// Returns the number of configurations in the history.
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+}
+
+impl Default for ConfigHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_DEPTH)
+    }
+}
+
+/// Keeps the time each configuration attribute was last changed.
+///
+/// The timestamps never go backwards: if the system clock is moved back, the attributes changed
+/// afterwards get the most recent timestamp instead.
+#[derive(Debug, Default)]
+pub struct FieldTimestamps {
+    timestamps: BTreeMap<&'static str, DateTime<Utc>>,
+    latest: Option<DateTime<Utc>>,
+}
+
+impl FieldTimestamps {
+    /// Records that the given attributes changed at the given time.
+    ///
+    /// * `fields`: names of the attributes (e.g., "uiLocale").
+    /// * `now`: time of the change.
+    pub fn touch(&mut self, fields: &[&'static str], now: DateTime<Utc>) {
+        if fields.is_empty() {
+            return;
+        }
+        let now = self.latest.map_or(now, |latest| latest.max(now));
+        self.latest = Some(now);
+        for field in fields {
+            self.timestamps.insert(field, now);
+        }
+    }
+
+    /// Returns the timestamps in RFC 3339 format, indexed by attribute.
+    ///
+    /// The attributes which did not change since the service started are not included.
+    pub fn to_rfc3339(&self) -> BTreeMap<&'static str, String> {
+        self.timestamps
+            .iter()
+            .map(|(field, time)| (*field, time.to_rfc3339()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfigHistory, FieldTimestamps};
+    use crate::l10n::LocaleConfig;
+    use chrono::{TimeZone, Utc};
+
+    fn config(timezone: &str) -> LocaleConfig {
+        LocaleConfig {
+            timezone: Some(timezone.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_push_and_pop() {
+        let mut history = ConfigHistory::new(2);
+        history.push(config("Europe/Berlin"));
+        history.push(config("Europe/Madrid"));
+        history.push(config("Europe/Prague"));
+
+        assert_eq!(history.pop(), Some(config("Europe/Prague")));
+        assert_eq!(history.pop(), Some(config("Europe/Madrid")));
+        assert_eq!(history.pop(), None);
+    }
+
+    #[test]
+    fn test_no_history() {
+        let mut history = ConfigHistory::new(0);
+        history.push(config("Europe/Berlin"));
+        assert_eq!(history.pop(), None);
+    }
+
+    #[test]
+    fn test_field_timestamps() {
+        let mut timestamps = FieldTimestamps::default();
+        let first = Utc.with_ymd_and_hms(2024, 5, 1, 10, 0, 0).unwrap();
+        timestamps.touch(&["locales", "timezone"], first);
+
+        // the clock goes backwards
+        let earlier = Utc.with_ymd_and_hms(2024, 5, 1, 9, 0, 0).unwrap();
+        timestamps.touch(&["timezone"], earlier);
+        timestamps.touch(&[], Utc.with_ymd_and_hms(2024, 5, 2, 0, 0, 0).unwrap());
+
+        let later = Utc.with_ymd_and_hms(2024, 5, 1, 11, 0, 0).unwrap();
+        timestamps.touch(&["keymap"], later);
+
+        let timestamps = timestamps.to_rfc3339();
+        assert_eq!(timestamps.len(), 3);
+        assert_eq!(timestamps["locales"], first.to_rfc3339());
+        assert_eq!(timestamps["timezone"], first.to_rfc3339());
+        assert_eq!(timestamps["keymap"], later.to_rfc3339());
+    }
+}
@@ -1,4 +1,5 @@
-use agama_locale_data::{get_localectl_keymaps, keyboard::XkbConfigRegistry, KeymapId};
+use super::helpers::{edit_distance, normalize_text};
+use agama_locale_data::{get_localectl_keymaps, keyboard::XkbConfigRegistry, KeymapId, LocaleId};
 use gettextrs::*;
 use serde::Serialize;
 use serde_with::{serde_as, DisplayFromStr};
@@ -11,6 +12,10 @@ pub struct Keymap {
     /// Keymap identifier (e.g., "us")
     #[serde_as(as = "DisplayFromStr")]
     pub id: KeymapId,
+    /// Keyboard layout (e.g., "us")
+    layout: String,
+    /// Keyboard layout variant (e.g., "dvorak"), if any
+    variant: Option<String>,
     /// Keymap description
     description: String,
 }
@@ -18,16 +23,176 @@ pub struct Keymap {
 impl Keymap {
     pub fn new(id: KeymapId, description: &str) -> Self {
         Self {
+            layout: id.layout.clone(),
+            variant: id.variant.clone(),
             id,
             description: description.to_string(),
         }
     }
 
+    /// Determines whether the keymap matches the given layout and variant.
+    ///
+    /// * `layout`: layout to match (any layout if it is `None`).
+    /// * `variant`: variant to match (any variant if it is `None`).
+    pub fn matches(&self, layout: Option<&str>, variant: Option<&str>) -> bool {
+        layout.map_or(true, |l| self.layout == l)
+            && variant.map_or(true, |v| self.variant.as_deref() == Some(v))
+    }
+
+    /// Returns the keymap description (e.g., "English (US)").
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
     pub fn localized_description(&self) -> String {
         gettext(&self.description)
     }
 }
 
+/// Keymap matching a search (see [KeymapsDatabase::search]).
+#[derive(Clone, Debug, Serialize, utoipa::ToSchema)]
+pub struct KeymapMatch {
+    #[serde(flatten)]
+    pub keymap: Keymap,
+    /// Number of edits (typos) needed to match the search
+    pub distance: usize,
+    /// Score from 0 to 1 (1 means an exact match)
+    pub score: f32,
+}
+
+/// Maximum number of edits (typos) allowed for each word of a search.
+pub const MAX_EDIT_DISTANCE: usize = 2;
+
+/// Method used to apply the keymap to the user interface.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum UiKeymapMethod {
+    /// Applied to the running X server with `setxkbmap`
+    X11,
+    /// Applied to the virtual console with `localectl set-keymap`
+    Console,
+}
+
+impl UiKeymapMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::X11 => "x11",
+            Self::Console => "console",
+        }
+    }
+}
+
+// Suggested keymaps for locales (e.g., "de_CH") and languages (e.g., "de"). Locales take
+// precedence over languages. There is no need to add an entry when the keymap ID matches the
+// language code (e.g., "de" for German) or the lowercase territory code (e.g., "be" for Belgium).
+const SUGGESTED_KEYMAPS: [(&str, &str); 31] = [
+    ("de_CH", "ch"),
+    ("en_GB", "gb"),
+    ("en_IE", "ie"),
+    ("es_AR", "latam"),
+    ("es_CL", "latam"),
+    ("es_CO", "latam"),
+    ("es_MX", "latam"),
+    ("es_PE", "latam"),
+    ("es_VE", "latam"),
+    ("fr_CA", "ca"),
+    ("fr_CH", "ch(fr)"),
+    ("nl_BE", "be"),
+    ("pt_BR", "br"),
+    ("ar", "ara"),
+    ("cs", "cz"),
+    ("da", "dk"),
+    ("el", "gr"),
+    ("en", "us"),
+    ("et", "ee"),
+    ("he", "il"),
+    ("hy", "am"),
+    ("ja", "jp"),
+    ("ka", "ge"),
+    ("kk", "kz"),
+    ("ko", "kr"),
+    ("nb", "no"),
+    ("sl", "si"),
+    ("sr", "rs"),
+    ("sv", "se"),
+    ("uk", "ua"),
+    ("zh", "cn"),
+];
+
+// Console fonts for the layouts whose characters are not included in the default console font
+// ("eurlatgr", which covers the Latin scripts).
+const CONSOLE_FONTS: [(&str, &str); 14] = [
+    ("am", "armscii8-8x16"),
+    ("ara", "LatArCyrHeb-16"),
+    ("bg", "cyr-sun16"),
+    ("by", "cyr-sun16"),
+    ("gr", "iso07u-16"),
+    ("il", "LatArCyrHeb-16"),
+    ("kg", "cyr-sun16"),
+    ("kz", "cyr-sun16"),
+    ("mk", "cyr-sun16"),
+    ("mn", "cyr-sun16"),
+    ("rs", "cyr-sun16"),
+    ("ru", "cyr-sun16"),
+    ("tj", "cyr-sun16"),
+    ("ua", "cyr-sun16"),
+];
+
+/// XKB options supported for the user-interface keyboard, along with their descriptions.
+///
+/// They are limited to the behavior of the Num Lock and Caps Lock keys, which is what kiosk-like
+/// deployments usually need.
+pub const KEYBOARD_OPTIONS: [(&str, &str); 7] = [
+    (
+        "numpad:mac",
+        "Numeric keypad always enters digits (as if Num Lock were on)",
+    ),
+    ("caps:none", "Caps Lock is disabled"),
+    ("caps:escape", "Caps Lock is an additional Esc"),
+    ("caps:backspace", "Caps Lock is an additional Backspace"),
+    ("ctrl:nocaps", "Caps Lock is an additional Ctrl"),
+    ("ctrl:swapcaps", "Swap Ctrl and Caps Lock"),
+    (
+        "shift:both_capslock",
+        "Both Shift keys together enable Caps Lock",
+    ),
+];
+
+/// Returns the console font needed to display the characters produced by the keymap.
+///
+/// It returns `None` when the default console font suffices. The variants using the Latin
+/// script (e.g., "rs(latin)") do not need a different font.
+///
+/// * `keymap`: keymap ID.
+pub fn console_font(keymap: &KeymapId) -> Option<&'static str> {
+    if keymap.variant.as_ref().is_some_and(|v| v.contains("latin")) {
+        return None;
+    }
+    CONSOLE_FONTS
+        .iter()
+        .find(|(layout, _)| *layout == keymap.layout)
+        .map(|(_, font)| *font)
+}
+
+/// Returns the language associated to the keymap, if it can be derived.
+///
+/// It looks for the keymap, and then for its layout, in the table of suggested keymaps (e.g.,
+/// "cz(qwerty)" for Czech). As a fallback, the layout is the language when it is a language code
+/// (e.g., "de").
+///
+/// * `keymap`: keymap ID.
+/// * `is_language`: function to determine whether a code is a known language code.
+pub fn keymap_language(keymap: &KeymapId, is_language: impl Fn(&str) -> bool) -> Option<String> {
+    let id = keymap.to_string();
+    let language = |code: &str| code.split('_').next().unwrap_or(code).to_string();
+    SUGGESTED_KEYMAPS
+        .iter()
+        .find(|(_, k)| *k == id)
+        .or_else(|| SUGGESTED_KEYMAPS.iter().find(|(_, k)| *k == keymap.layout))
+        .map(|(code, _)| language(code))
+        .or_else(|| is_language(&keymap.layout).then(|| keymap.layout.clone()))
+}
+
 /// Represents the keymaps database.
 ///
 /// The list of supported keymaps is read from `systemd-localed` and the
@@ -53,10 +218,118 @@ impl KeymapsDatabase {
         self.keymaps.iter().any(|k| &k.id == id)
     }
 
+    /// Returns the keymap with the given ID, if it exists.
+    pub fn find(&self, id: &KeymapId) -> Option<&Keymap> {
+        self.keymaps.iter().find(|k| &k.id == id)
+    }
+
+    /// Adds a keymap which is not included in the system database (e.g., a custom XKB layout).
+    ///
+    /// It is kept only in memory, so it is lost when the service is restarted. It returns `false`
+    /// if a keymap with the same ID already exists.
+    ///
+    /// * `keymap`: keymap to add.
+    pub fn add(&mut self, keymap: Keymap) -> bool {
+        if self.exists(&keymap.id) {
+            return false;
+        }
+        self.keymaps.push(keymap);
+        true
+    }
+
     /// Returns the list of keymaps.
     pub fn entries(&self) -> &Vec<Keymap> {
         &self.keymaps
     }
+
+    /// Searches for keymaps, tolerating typos.
+    ///
+    /// Each word of the query must match (as a prefix or with a few typos) a word of the
+    /// description (original or localized) or the keymap ID. The number of typos allowed for each
+    /// word depends on its length (one for each 3 characters, up to [MAX_EDIT_DISTANCE]). The
+    /// comparison is case and accent insensitive. The results are sorted by distance and
+    /// description.
+    ///
+    /// * `query`: text to search for (e.g., "german quertz").
+    /// * `limit`: maximum number of results.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<KeymapMatch> {
+        let query = normalize_text(query);
+        let words: Vec<&str> = query
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect();
+        if words.is_empty() {
+            return vec![];
+        }
+        let query_len: usize = words.iter().map(|w| w.chars().count()).sum();
+
+        let mut matches: Vec<KeymapMatch> = self
+            .keymaps
+            .iter()
+            .filter_map(|keymap| {
+                let text = format!(
+                    "{} {} {}",
+                    keymap.id,
+                    keymap.description,
+                    keymap.localized_description()
+                );
+                let text = normalize_text(&text);
+                let candidates: Vec<&str> = text
+                    .split(|c: char| !c.is_alphanumeric())
+                    .filter(|w| !w.is_empty())
+                    .collect();
+                let mut distance = 0;
+                for word in &words {
+                    let best = candidates.iter().map(|c| word_distance(word, c)).min()?;
+                    if best > (word.chars().count() / 3).min(MAX_EDIT_DISTANCE) {
+                        return None;
+                    }
+                    distance += best;
+                }
+                let score = 1.0 - (distance as f32 / query_len as f32);
+                Some(KeymapMatch {
+                    keymap: keymap.clone(),
+                    distance,
+                    score: score.max(0.0),
+                })
+            })
+            .collect();
+        matches.sort_by(|a, b| {
+            a.distance
+                .cmp(&b.distance)
+                .then_with(|| a.keymap.description.cmp(&b.keymap.description))
+        });
+        matches.truncate(limit);
+        matches
+    }
+
+    /// Returns the suggested keymap for the given locale, if any.
+    ///
+    /// It looks for the locale and its language in a table of well-known keymaps. As a fallback,
+    /// it looks for a keymap whose ID matches the language or the territory code.
+    ///
+    /// * `locale`: locale to get the keymap for (e.g., "de_DE").
+    pub fn suggest(&self, locale: &LocaleId) -> Option<&Keymap> {
+        let full_id = format!("{}_{}", &locale.language, &locale.territory);
+        let suggested = |id: &str| {
+            SUGGESTED_KEYMAPS
+                .iter()
+                .find(|(code, _)| *code == id)
+                .map(|(_, keymap)| keymap.to_string())
+        };
+
+        let candidates = [
+            suggested(&full_id),
+            suggested(&locale.language),
+            Some(locale.language.clone()),
+            Some(locale.territory.to_lowercase()),
+        ];
+        candidates
+            .into_iter()
+            .flatten()
+            .filter_map(|id| id.parse::<KeymapId>().ok())
+            .find_map(|id| self.find(&id))
+    }
 }
 
 /// Returns the list of keymaps to offer.
@@ -97,3 +370,123 @@ fn get_keymap_descriptions() -> HashMap<String, String> {
 
     keymaps
 }
+
+// Returns the distance between a word of the query and a word of the text. A prefix of the word
+// is considered an exact match, so the results do not change while typing.
+fn word_distance(query: &str, word: &str) -> usize {
+    if word.starts_with(query) {
+        return 0;
+    }
+    // compare also with the beginning of the word to tolerate typos while typing
+    let prefix: String = word.chars().take(query.chars().count()).collect();
+    edit_distance(query, word).min(edit_distance(query, &prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{console_font, keymap_language, Keymap, KeymapsDatabase};
+    use agama_locale_data::LocaleId;
+
+    fn suggest(db: &KeymapsDatabase, locale: &str) -> Option<String> {
+        let locale = LocaleId::try_from(locale).unwrap();
+        db.suggest(&locale).map(|k| k.id.to_string())
+    }
+
+    #[test]
+    fn test_search() {
+        let keymaps = [
+            ("de", "German"),
+            ("ch", "German (Switzerland)"),
+            ("cz(qwerty)", "Czech (QWERTY)"),
+            ("de(nodeadkeys)", "German (QWERTZ, no dead keys)"),
+            ("us", "English (US)"),
+        ]
+        .iter()
+        .map(|(id, description)| Keymap::new(id.parse().unwrap(), description))
+        .collect();
+        let db = KeymapsDatabase { keymaps };
+
+        let ids = |query: &str| -> Vec<String> {
+            db.search(query, 10)
+                .iter()
+                .map(|m| m.keymap.id.to_string())
+                .collect()
+        };
+        // "qwerty" is two typos away
+        assert_eq!(ids("quertz"), vec!["de(nodeadkeys)", "cz(qwerty)"]);
+        assert_eq!(ids("germn swit"), vec!["ch"]);
+        assert_eq!(ids("Germ"), vec!["de", "de(nodeadkeys)", "ch"]);
+        assert!(ids("xyz").is_empty());
+        assert!(ids("  ").is_empty());
+        assert_eq!(db.search("german", 1).len(), 1);
+
+        let found = db.search("qwertz", 10);
+        assert_eq!(found[0].distance, 0);
+        assert_eq!(found[0].score, 1.0);
+        let found = db.search("quertz", 10);
+        assert_eq!(found[0].distance, 1);
+    }
+
+    #[test]
+    fn test_add_keymap() {
+        let mut db = KeymapsDatabase::new();
+        let keymap = Keymap::new("oem(special)".parse().unwrap(), "OEM (Special)");
+        assert!(db.add(keymap.clone()));
+        assert!(db.exists(&keymap.id));
+        assert!(!db.add(keymap));
+        assert_eq!(db.entries().len(), 1);
+    }
+
+    #[test]
+    fn test_keymap_matches() {
+        let keymap = Keymap::new("us(dvorak)".parse().unwrap(), "English (Dvorak)");
+        assert!(keymap.matches(None, None));
+        assert!(keymap.matches(Some("us"), None));
+        assert!(keymap.matches(Some("us"), Some("dvorak")));
+        assert!(keymap.matches(None, Some("dvorak")));
+        assert!(!keymap.matches(Some("cz"), None));
+        assert!(!keymap.matches(Some("us"), Some("intl")));
+
+        let json = serde_json::to_string(&keymap).unwrap();
+        assert!(json.contains(r#""layout":"us","variant":"dvorak""#));
+    }
+
+    #[test]
+    fn test_console_font() {
+        let font = |id: &str| console_font(&id.parse().unwrap());
+        assert_eq!(font("ru"), Some("cyr-sun16"));
+        assert_eq!(font("ua(phonetic)"), Some("cyr-sun16"));
+        assert_eq!(font("gr"), Some("iso07u-16"));
+        assert_eq!(font("rs(latin)"), None);
+        assert_eq!(font("de(nodeadkeys)"), None);
+        assert_eq!(font("us"), None);
+    }
+
+    #[test]
+    fn test_keymap_language() {
+        let language = |id: &str| keymap_language(&id.parse().unwrap(), |l| l == "de" || l == "fr");
+        assert_eq!(language("de(nodeadkeys)"), Some("de".to_string()));
+        assert_eq!(language("ch"), Some("de".to_string()));
+        assert_eq!(language("ch(fr)"), Some("fr".to_string()));
+        assert_eq!(language("cz(qwerty)"), Some("cs".to_string()));
+        assert_eq!(language("latam"), Some("es".to_string()));
+        assert_eq!(language("us(dvorak)"), Some("en".to_string()));
+        assert_eq!(language("fr(bepo)"), Some("fr".to_string()));
+        assert_eq!(language("epo"), None);
+    }
+
+    #[test]
+    fn test_suggest() {
+        let keymaps = ["be", "ch", "ch(fr)", "de", "es", "us"]
+            .iter()
+            .map(|id| Keymap::new(id.parse().unwrap(), id))
+            .collect();
+        let db = KeymapsDatabase { keymaps };
+        assert_eq!(suggest(&db, "de_DE"), Some("de".to_string()));
+        assert_eq!(suggest(&db, "de_CH"), Some("ch".to_string()));
+        assert_eq!(suggest(&db, "fr_CH"), Some("ch(fr)".to_string()));
+        assert_eq!(suggest(&db, "fr_BE"), Some("be".to_string()));
+        assert_eq!(suggest(&db, "en_AU"), Some("us".to_string()));
+        assert_eq!(suggest(&db, "ja_JP"), None);
+    }
+}
@@ -1,6 +1,10 @@
+use std::collections::HashMap;
 use std::env;
+use std::future::Future;
 use std::io;
-use std::process::Command;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
 use std::time::Duration;
 
 use crate::error::Error;
@@ -8,9 +12,11 @@ use agama_locale_data::{KeymapId, LocaleId};
 use regex::Regex;
 use subprocess::{Popen, PopenConfig, PopenError, Redirection};
 
-use super::keyboard::KeymapsDatabase;
-use super::locale::LocalesDatabase;
-use super::timezone::TimezonesDatabase;
+use super::keyboard::{Keymap, KeymapMatch, KeymapsDatabase, UiKeymapMethod, KEYBOARD_OPTIONS};
+use super::locale::{LanguageEntry, LocaleEntry, LocalesDatabase, SelectedLocale};
+use super::timezone::{TimezoneEntry, TimezonesDatabase};
+use super::tools::{ToolsCheck, LOCALECTL, SETXKBMAP};
+use super::LocaleConfig;
 use super::{helpers, LocaleError};
 
 pub struct L10n {
@@ -19,15 +25,109 @@ pub struct L10n {
     pub locales: Vec<String>,
     pub locales_db: LocalesDatabase,
     pub keymap: KeymapId,
+    /// Keymap to install by default (see [L10n::set_default_keymap]).
+    pub default_keymap: KeymapId,
     pub keymaps_db: KeymapsDatabase,
     pub ui_locale: LocaleId,
     pub ui_keymap: KeymapId,
+    /// UI keymap detected when the service started.
+    pub default_ui_keymap: KeymapId,
+    /// XKB options applied along with the UI keymap (see [KEYBOARD_OPTIONS]).
+    pub ui_keyboard_options: Vec<String>,
+    /// Whether the hardware clock keeps the time in UTC.
+    pub rtc_utc: bool,
+    /// Hardware clock mode detected when the service started.
+    pub default_rtc_utc: bool,
+    /// NTP servers. They are not applied by this service.
+    pub ntp_servers: Vec<String>,
+    /// Databases translated to other languages, indexed by language code.
+    ///
+    /// They are kept to avoid reading the translations again when switching back and forth
+    /// between UI locales.
+    translations: HashMap<String, (LocalesDatabase, TimezonesDatabase)>,
+}
+
+/// Localization configuration which has been already validated.
+///
+/// See [L10n::validate_config].
+#[derive(Default)]
+pub struct ValidatedConfig {
+    pub locales: Option<Vec<String>>,
+    pub keymap: Option<KeymapId>,
+    pub timezone: Option<String>,
+    pub ui_locale: Option<LocaleId>,
+    pub ui_keymap: Option<KeymapId>,
+    pub ui_keyboard_options: Option<Vec<String>>,
+    pub rtc_utc: Option<bool>,
+    pub ntp_servers: Option<Vec<String>>,
 }
 
 // timeout for the setxkbmap call (in seconds), when there is an authentication
 // problem when accessing the X server then it enters an infinite loop
 const SETXKBMAP_TIMEOUT: u64 = 3;
 
+// timeout for the localectl calls (in seconds)
+const LOCALECTL_TIMEOUT: u64 = 10;
+
+// timezone to use by default
+const DEFAULT_TIMEZONE: &str = "Europe/Berlin";
+
+// timeout for the timedatectl calls (in seconds)
+const TIMEDATECTL_TIMEOUT: u64 = 10;
+
+// file where the hardware clock mode is stored
+const ADJTIME_PATH: &str = "/etc/adjtime";
+
+/// Runs the given step and, if it fails, the rollback of the changes done before it.
+///
+/// If the rollback succeeds, it returns the error of the step. Otherwise, the system is left
+/// half-applied and it returns a [LocaleError::PartialCommit] error.
+///
+/// * `step`: step to run.
+/// * `rollback`: rollback to run if the step fails.
+/// * `restored`: what the rollback restores, used in the logs and the error message (e.g., "the
+///   previous X11 keymap").
+pub async fn rollback_on_error<T>(
+    step: impl Future<Output = Result<T, LocaleError>>,
+    rollback: impl Future<Output = Result<(), LocaleError>>,
+    restored: &str,
+) -> Result<T, LocaleError> {
+    let error = match step.await {
+        Ok(value) => return Ok(value),
+        Err(error) => error,
+    };
+
+    if let Err(rollback_error) = rollback.await {
+        return Err(LocaleError::PartialCommit(format!(
+            "{error} ({restored} could not be restored: {rollback_error})"
+        )));
+    }
+    tracing::info!("{} was restored", restored);
+    Err(error)
+}
+
+// helper function which runs a command asynchronously with timeout and collects
+// its output, the process is killed when the timeout is reached
+async fn run_async_with_timeout(cmd: &[&str], timeout: u64) -> Result<Output, io::Error> {
+    let mut command = tokio::process::Command::new(cmd[0]);
+    command.args(&cmd[1..]).kill_on_drop(true);
+
+    match tokio::time::timeout(Duration::from_secs(timeout), command.output()).await {
+        Ok(Ok(output)) => {
+            tracing::debug!(command = ?cmd, status = %output.status, "Command finished");
+            Ok(output)
+        }
+        Ok(Err(error)) => {
+            tracing::debug!(command = ?cmd, %error, "Command could not be run");
+            Err(error)
+        }
+        Err(_) => {
+            tracing::warn!("Command {:?} timed out!", cmd);
+            Err(io::Error::new(io::ErrorKind::TimedOut, "Timeout reached"))
+        }
+    }
+}
+
 // helper function which runs a command with timeout and collects it's standard
 // output
 fn run_with_timeout(cmd: &[&str], timeout: u64) -> Result<Option<String>, PopenError> {
@@ -77,8 +177,45 @@ fn default_display() -> String {
     String::from(":0")
 }
 
-// helper function to get the X display name, if not set it returns the default display
-fn display() -> String {
+/// Determines whether there is an X server running on the given display.
+///
+/// It checks whether the X server socket (e.g., `/tmp/.X11-unix/X0` for ":0") exists.
+///
+/// * `x_display`: X display (e.g., ":0" or ":1.0").
+pub fn x_display_available(x_display: &str) -> bool {
+    x11_socket_path(x_display).is_some_and(|path| path.exists())
+}
+
+// returns the path of the X server socket for the given display
+fn x11_socket_path(x_display: &str) -> Option<PathBuf> {
+    let number = x_display.strip_prefix(':')?.split('.').next()?;
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(PathBuf::from(format!("/tmp/.X11-unix/X{number}")))
+}
+
+// Determines whether the hardware clock keeps the time in UTC according to the content of the
+// adjtime file. The mode is in the third line ("UTC" or "LOCAL"), defaulting to UTC.
+fn rtc_utc_from_adjtime(content: &str) -> bool {
+    content.lines().nth(2).map(str::trim) != Some("LOCAL")
+}
+
+// Returns the content of the adjtime file setting the hardware clock mode. It keeps the drift
+// information (the first two lines) of the given content, if any.
+fn adjtime_with_rtc_utc(content: &str, utc: bool) -> String {
+    let mut lines = content.lines();
+    let drift = lines.next().unwrap_or("0.0 0 0.0");
+    let adjust = lines.next().unwrap_or("0");
+    let mode = if utc { "UTC" } else { "LOCAL" };
+    format!("{drift}\n{adjust}\n{mode}\n")
+}
+
+/// Returns the X display to use.
+///
+/// It uses the `DISPLAY` environment variable when it points to a local X server
+/// and falls back to `:0` otherwise.
+pub fn display() -> String {
     let display = env::var("DISPLAY");
 
     match display {
@@ -99,104 +236,678 @@ fn display() -> String {
 
 impl L10n {
     pub fn new_with_locale(ui_locale: &LocaleId) -> Result<Self, Error> {
-        const DEFAULT_TIMEZONE: &str = "Europe/Berlin";
-
         let locale = ui_locale.to_string();
         let mut locales_db = LocalesDatabase::new();
         locales_db.read(&locale)?;
 
-        let mut default_locale = ui_locale.to_string();
-        if !locales_db.exists(locale.as_str()) {
-            // TODO: handle the case where the database is empty (not expected!)
-            default_locale = locales_db.entries().first().unwrap().id.to_string();
-        };
-
         let mut timezones_db = TimezonesDatabase::new();
         timezones_db.read(&ui_locale.language)?;
 
-        let mut default_timezone = DEFAULT_TIMEZONE.to_string();
-        if !timezones_db.exists(&default_timezone) {
-            default_timezone = timezones_db.entries().first().unwrap().code.to_string();
-        };
-
         let mut keymaps_db = KeymapsDatabase::new();
         keymaps_db.read()?;
 
-        let ui_keymap = Self::x11_keymap().unwrap_or("us".to_string());
+        let rtc_utc = std::fs::read_to_string(ADJTIME_PATH)
+            .map(|c| rtc_utc_from_adjtime(&c))
+            .unwrap_or(true);
+
+        let ui_keymap: KeymapId = Self::x11_keymap()
+            .unwrap_or("us".to_string())
+            .parse()
+            .unwrap_or_default();
 
-        let locale = Self {
-            keymap: "us".parse().unwrap(),
-            timezone: default_timezone,
-            locales: vec![default_locale],
+        let mut locale = Self {
+            keymap: KeymapId::default(),
+            default_keymap: KeymapId::default(),
+            timezone: String::new(),
+            locales: vec![],
             locales_db,
             timezones_db,
             keymaps_db,
             ui_locale: ui_locale.clone(),
-            ui_keymap: ui_keymap.parse().unwrap_or_default(),
+            ui_keymap: ui_keymap.clone(),
+            default_ui_keymap: ui_keymap,
+            ui_keyboard_options: vec![],
+            rtc_utc,
+            default_rtc_utc: rtc_utc,
+            ntp_servers: vec![],
+            translations: HashMap::new(),
         };
+        locale.locales = vec![locale.default_locale(ui_locale)];
+        locale.timezone = locale.default_timezone();
+        locale.default_keymap = locale.suggested_keymap(&locale.locales[0]);
+        locale.keymap = locale.default_keymap.clone();
 
         Ok(locale)
     }
 
-    pub fn set_locales(&mut self, locales: &Vec<String>) -> Result<(), LocaleError> {
-        for loc in locales {
-            if !self.locales_db.exists(loc.as_str()) {
-                return Err(LocaleError::UnknownLocale(loc.to_string()))?;
+    /// Returns an instance with empty databases.
+    ///
+    /// It is meant to keep the service running when the databases cannot be read (see
+    /// [L10n::new_with_locale]).
+    ///
+    /// * `ui_locale`: UI locale.
+    pub fn new_empty(ui_locale: &LocaleId) -> Self {
+        Self {
+            keymap: KeymapId::default(),
+            default_keymap: KeymapId::default(),
+            timezone: DEFAULT_TIMEZONE.to_string(),
+            locales: vec![ui_locale.to_string()],
+            locales_db: LocalesDatabase::new(),
+            timezones_db: TimezonesDatabase::new(),
+            keymaps_db: KeymapsDatabase::new(),
+            ui_locale: ui_locale.clone(),
+            ui_keymap: KeymapId::default(),
+            default_ui_keymap: KeymapId::default(),
+            ui_keyboard_options: vec![],
+            rtc_utc: true,
+            default_rtc_utc: true,
+            ntp_servers: vec![],
+            translations: HashMap::new(),
+        }
+    }
+
+    /// Returns the default configuration.
+    ///
+    /// It corresponds to the configuration of a service started with the default locale.
+    pub fn default_config(&self) -> LocaleConfig {
+        let ui_locale = LocaleId::default();
+        LocaleConfig {
+            locales: Some(vec![self.default_locale(&ui_locale)]),
+            keymap: Some(self.default_keymap.to_string()),
+            timezone: Some(self.default_timezone()),
+            ui_locale: Some(ui_locale.to_string()),
+            ui_keymap: Some(self.default_ui_keymap.to_string()),
+            ui_keyboard_options: Some(vec![]),
+            rtc_utc: Some(self.default_rtc_utc),
+            ntp_servers: Some(vec![]),
+        }
+    }
+
+    /// Sets the keymap to install by default, replacing the one suggested for the default locale.
+    ///
+    /// The current keymap is set too, so it is meant to be called before applying any other
+    /// configuration.
+    ///
+    /// * `keymap`: keymap ID.
+    pub fn set_default_keymap(&mut self, keymap: KeymapId) -> Result<(), LocaleError> {
+        if self.keymaps_db.find(&keymap).is_none() {
+            return Err(LocaleError::UnknownKeymap(keymap));
+        }
+        self.keymap = keymap.clone();
+        self.default_keymap = keymap;
+        Ok(())
+    }
+
+    /// Returns the locale to install by default.
+    ///
+    /// It corresponds to the locale of a service started with the default locale.
+    pub fn default_locale_entry(&self) -> Option<&LocaleEntry> {
+        let locale = self.default_locale(&LocaleId::default());
+        let locale_id = LocaleId::try_from(locale.as_str()).ok()?;
+        self.locales_db.find(&locale_id)
+    }
+
+    /// Returns the details of the locales to install.
+    ///
+    /// The locales which are not included in the locales database are kept but marked as not
+    /// valid, so the drift between the configuration and the database is visible.
+    pub fn selected_locales(&self) -> Vec<SelectedLocale> {
+        self.locales
+            .iter()
+            // the stored locales are canonical, so they are always well-formed
+            .filter_map(|id| helpers::parse_locale(id).ok())
+            .map(|id| match self.locales_db.find(&id) {
+                Some(entry) => SelectedLocale {
+                    entry: entry.clone(),
+                    valid: true,
+                },
+                None => SelectedLocale {
+                    entry: LocaleEntry::unknown(id),
+                    valid: false,
+                },
+            })
+            .collect()
+    }
+
+    /// Returns the known locales, translated to the UI language.
+    ///
+    /// They are the same entries listed by the `/locales` endpoint, so the clients which do not
+    /// use the web API (e.g., a command-line tool) can get them without setting up the service:
+    ///
+    /// ```no_run
+    /// # use agama_locale_data::LocaleId;
+    /// # use agama_server::l10n::L10n;
+    /// let l10n = L10n::new_with_locale(&LocaleId::default()).unwrap();
+    /// for locale in l10n.locale_entries() {
+    ///     println!("{}: {} ({})", locale.id, locale.language, locale.territory);
+    /// }
+    /// ```
+    pub fn locale_entries(&self) -> &[LocaleEntry] {
+        self.locales_db.entries()
+    }
+
+    /// Returns the languages of the known locales, sorted by the endonym.
+    pub fn language_entries(&self) -> Vec<LanguageEntry> {
+        self.locales_db.languages()
+    }
+
+    /// Returns the known keymaps.
+    pub fn keymap_entries(&self) -> &[Keymap] {
+        self.keymaps_db.entries()
+    }
+
+    /// Searches for keymaps, tolerating typos (see the `/keymaps/search` endpoint).
+    ///
+    /// * `query`: text to search for (e.g., "german quertz").
+    /// * `limit`: maximum number of results.
+    pub fn search_keymaps(&self, query: &str, limit: usize) -> Vec<KeymapMatch> {
+        self.keymaps_db.search(query, limit)
+    }
+
+    /// Returns the known timezones, translated to the UI language and including their current
+    /// UTC offset.
+    ///
+    /// * `country`: ISO 3166 alpha-2 code of the country to filter by (e.g., "ES"), if any.
+    pub fn timezone_entries(&self, country: Option<&str>) -> Vec<TimezoneEntry> {
+        self.timezones_db.entries_with_offset(country)
+    }
+
+    // returns the locale to install by default: the UI locale if it is known or the first one
+    // (the UI locale if the database is empty)
+    fn default_locale(&self, ui_locale: &LocaleId) -> String {
+        let locale = ui_locale.to_string();
+        if self.locales_db.exists(locale.as_str()) {
+            return locale;
+        }
+        self.locales_db
+            .entries()
+            .first()
+            .map_or(locale, |l| l.id.to_string())
+    }
+
+    // returns the keymap suggested for the given locale or "us" if there is no suggestion
+    fn suggested_keymap(&self, locale: &str) -> KeymapId {
+        LocaleId::try_from(locale)
+            .ok()
+            .and_then(|id| self.keymaps_db.suggest(&id))
+            .map_or_else(KeymapId::default, |k| k.id.clone())
+    }
+
+    // returns the default timezone: Europe/Berlin if it is known or the first one (Europe/Berlin
+    // if the database is empty)
+    fn default_timezone(&self) -> String {
+        let timezone = DEFAULT_TIMEZONE.to_string();
+        if self.timezones_db.exists(&timezone) {
+            return timezone;
+        }
+        self.timezones_db
+            .entries()
+            .first()
+            .map_or(timezone, |t| t.code.to_string())
+    }
+
+    /// Applies the given configuration without running any command.
+    ///
+    /// It is meant to restore a previously saved configuration, so the whole configuration is
+    /// validated before applying any change. The UI keymap is ignored because it is read from the
+    /// system.
+    ///
+    /// * `config`: configuration to restore.
+    pub fn restore_config(&mut self, config: &LocaleConfig) -> Result<(), Error> {
+        let config = self
+            .validate_config(config)
+            .map_err(|mut errors| errors.swap_remove(0).1)?;
+
+        if let Some(ui_locale) = config.ui_locale {
+            self.translate(&ui_locale)?;
+        }
+        if let Some(locales) = config.locales {
+            self.locales = locales;
+        }
+        if let Some(timezone) = config.timezone {
+            self.timezone = timezone;
+        }
+        if let Some(keymap) = config.keymap {
+            self.keymap = keymap;
+        }
+        if let Some(options) = config.ui_keyboard_options {
+            self.ui_keyboard_options = options;
+        }
+        if let Some(rtc_utc) = config.rtc_utc {
+            self.rtc_utc = rtc_utc;
+        }
+        if let Some(ntp_servers) = config.ntp_servers {
+            self.ntp_servers = ntp_servers;
+        }
+        Ok(())
+    }
+
+    /// Validates the given configuration without applying any change.
+    ///
+    /// It returns all the problems found (instead of stopping at the first one), each one along with
+    /// the name of the affected field.
+    ///
+    /// * `config`: configuration to validate.
+    pub fn validate_config(
+        &self,
+        config: &LocaleConfig,
+    ) -> Result<ValidatedConfig, Vec<(&'static str, LocaleError)>> {
+        let mut valid = ValidatedConfig::default();
+        let mut errors = vec![];
+
+        if let Some(locales) = &config.locales {
+            match self.validate_locales(locales) {
+                Ok(locales) => valid.locales = Some(locales),
+                Err(e) => errors.push(("locales", e)),
             }
         }
-        self.locales.clone_from(locales);
+
+        if let Some(timezone) = &config.timezone {
+            match self.validate_timezone(timezone) {
+                Ok(timezone) => valid.timezone = Some(timezone),
+                Err(e) => errors.push(("timezone", e)),
+            }
+        }
+
+        if let Some(keymap) = &config.keymap {
+            match self.parse_keymap(keymap) {
+                Ok(keymap_id) => valid.keymap = Some(keymap_id),
+                Err(e) => errors.push(("keymap", e)),
+            }
+        }
+
+        if let Some(ui_locale) = &config.ui_locale {
+            match helpers::parse_locale(ui_locale) {
+                Ok(locale) => valid.ui_locale = Some(locale),
+                Err(e) => errors.push(("uiLocale", e.into())),
+            }
+        }
+
+        if let Some(ui_keymap) = &config.ui_keymap {
+            match self.parse_keymap(ui_keymap) {
+                Ok(keymap_id) => valid.ui_keymap = Some(keymap_id),
+                Err(e) => errors.push(("uiKeymap", e)),
+            }
+        }
+
+        if let Some(options) = &config.ui_keyboard_options {
+            match Self::validate_keyboard_options(options) {
+                Ok(options) => valid.ui_keyboard_options = Some(options),
+                Err(e) => errors.push(("uiKeyboardOptions", e)),
+            }
+        }
+
+        valid.rtc_utc = config.rtc_utc;
+
+        if let Some(ntp_servers) = &config.ntp_servers {
+            match Self::validate_ntp_servers(ntp_servers) {
+                Ok(ntp_servers) => valid.ntp_servers = Some(ntp_servers),
+                Err(e) => errors.push(("ntpServers", e)),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(valid)
+        } else {
+            Err(errors)
+        }
+    }
+
+    // parses the keymap and checks whether it is known
+    fn parse_keymap(&self, keymap: &str) -> Result<KeymapId, LocaleError> {
+        let keymap_id = keymap.parse()?;
+        self.validate_keymap(&keymap_id)?;
+        Ok(keymap_id)
+    }
+
+    /// Sets the locales, storing them in their canonical form (e.g., "en_US.UTF-8").
+    ///
+    /// See [helpers::parse_locale] for the accepted forms.
+    pub fn set_locales(&mut self, locales: &[String]) -> Result<(), LocaleError> {
+        self.locales = self.validate_locales(locales)?;
         Ok(())
     }
 
-    pub fn set_timezone(&mut self, timezone: &str) -> Result<(), LocaleError> {
-        // TODO: modify exists() to receive an `&str`
-        if !self.timezones_db.exists(&timezone.to_string()) {
-            return Err(LocaleError::UnknownTimezone(timezone.to_string()))?;
+    /// Checks whether all the given locales are known, returning their canonical form.
+    ///
+    /// The list cannot be empty, as the first locale is the primary one, and it cannot contain
+    /// duplicates (after converting them to the canonical form). A malformed locale (e.g., "es-")
+    /// is reported as [LocaleError::InvalidLocale]. If some of the well-formed locales are
+    /// unknown, the error includes all of them.
+    pub fn validate_locales(&self, locales: &[String]) -> Result<Vec<String>, LocaleError> {
+        if locales.is_empty() {
+            return Err(LocaleError::NoLocales);
+        }
+
+        let mut canonical: Vec<String> = Vec::with_capacity(locales.len());
+        let mut unknown = vec![];
+        let mut duplicated = vec![];
+        for locale in locales {
+            let id = helpers::parse_locale(locale)?.to_string();
+            if !self.locales_db.exists(id.as_str()) {
+                unknown.push(locale.to_string());
+            } else if canonical.contains(&id) {
+                duplicated.push(id);
+            } else {
+                canonical.push(id);
+            }
+        }
+
+        if !unknown.is_empty() {
+            return Err(LocaleError::UnknownLocales(unknown));
         }
-        timezone.clone_into(&mut self.timezone);
+        if !duplicated.is_empty() {
+            return Err(LocaleError::DuplicatedLocales(duplicated));
+        }
+        Ok(canonical)
+    }
+
+    /// Returns the primary locale of the target system, which is the first one of the list.
+    pub fn primary_locale(&self) -> Option<&str> {
+        self.locales.first().map(String::as_str)
+    }
+
+    pub fn set_timezone(&mut self, timezone: &str) -> Result<(), LocaleError> {
+        self.timezone = self.validate_timezone(timezone)?;
         Ok(())
     }
 
-    pub fn set_keymap(&mut self, keymap_id: KeymapId) -> Result<(), LocaleError> {
-        if !self.keymaps_db.exists(&keymap_id) {
-            return Err(LocaleError::UnknownKeymap(keymap_id));
+    /// Checks whether the given timezone is known, returning its canonical name.
+    ///
+    /// The check is case insensitive and accepts deprecated names (e.g., "Asia/Calcutta").
+    pub fn validate_timezone(&self, timezone: &str) -> Result<String, LocaleError> {
+        self.timezones_db
+            .canonical_name(timezone)
+            .map(str::to_string)
+            .ok_or_else(|| LocaleError::UnknownTimezone(timezone.to_string()))
+    }
+
+    /// Checks whether all the given NTP servers are valid hosts, returning them without the
+    /// surrounding whitespace.
+    ///
+    /// If some of them are not valid, the error includes all of them. See
+    /// [helpers::is_valid_host].
+    pub fn validate_ntp_servers(servers: &[String]) -> Result<Vec<String>, LocaleError> {
+        let servers: Vec<_> = servers.iter().map(|s| s.trim().to_string()).collect();
+        let invalid: Vec<_> = servers
+            .iter()
+            .filter(|s| !helpers::is_valid_host(s))
+            .cloned()
+            .collect();
+        if invalid.is_empty() {
+            Ok(servers)
+        } else {
+            Err(LocaleError::InvalidNtpServers(invalid))
         }
+    }
 
+    /// Checks whether all the given keyboard options are supported (see [KEYBOARD_OPTIONS]),
+    /// returning them without the surrounding whitespace and duplicates.
+    ///
+    /// If some of them are not supported, the error includes all of them.
+    pub fn validate_keyboard_options(options: &[String]) -> Result<Vec<String>, LocaleError> {
+        let mut valid: Vec<String> = Vec::with_capacity(options.len());
+        let mut unknown = vec![];
+        for option in options.iter().map(|o| o.trim()) {
+            if !KEYBOARD_OPTIONS.iter().any(|(o, _)| *o == option) {
+                unknown.push(option.to_string());
+            } else if !valid.iter().any(|o| o == option) {
+                valid.push(option.to_string());
+            }
+        }
+        if unknown.is_empty() {
+            Ok(valid)
+        } else {
+            Err(LocaleError::UnknownKeyboardOptions(unknown))
+        }
+    }
+
+    pub fn set_keymap(&mut self, keymap_id: KeymapId) -> Result<(), LocaleError> {
+        self.validate_keymap(&keymap_id)?;
         self.keymap = keymap_id;
         Ok(())
     }
 
+    /// Checks whether the given keymap is known.
+    pub fn validate_keymap(&self, keymap_id: &KeymapId) -> Result<(), LocaleError> {
+        if !self.keymaps_db.exists(keymap_id) {
+            return Err(LocaleError::UnknownKeymap(keymap_id.clone()));
+        }
+        Ok(())
+    }
+
+    /// Translates the locales and timezones databases to the given locale.
+    ///
+    /// The translated databases are cached by language, so switching to a previously used
+    /// language (or to another locale of the same language) does not read the translations again.
+    ///
+    /// * `locale`: UI locale.
     // TODO: use LocaleError
     pub fn translate(&mut self, locale: &LocaleId) -> Result<(), Error> {
+        if self.ui_locale.language != locale.language {
+            let (locales_db, timezones_db) = match self.translations.remove(&locale.language) {
+                Some(databases) => databases,
+                None => Self::read_translations(&locale.language)?,
+            };
+            let previous = (
+                mem::replace(&mut self.locales_db, locales_db),
+                mem::replace(&mut self.timezones_db, timezones_db),
+            );
+            self.translations
+                .insert(self.ui_locale.language.clone(), previous);
+        }
+
         helpers::set_service_locale(locale);
-        self.timezones_db.read(&locale.language)?;
-        self.locales_db.read(&locale.language)?;
         self.ui_locale = locale.clone();
         Ok(())
     }
 
-    // TODO: use LocaleError
-    pub fn set_ui_keymap(&mut self, keymap_id: KeymapId) -> Result<(), LocaleError> {
-        if !self.keymaps_db.exists(&keymap_id) {
-            return Err(LocaleError::UnknownKeymap(keymap_id));
+    /// Returns the languages whose translations are loaded, sorted by their code.
+    pub fn translated_languages(&self) -> Vec<String> {
+        let mut languages: Vec<_> = self.translations.keys().cloned().collect();
+        languages.sort();
+        languages
+    }
+
+    /// Determines whether the translations for the given locale must be read.
+    ///
+    /// They are not needed when the language does not change or when they are cached.
+    ///
+    /// * `locale`: UI locale.
+    pub fn needs_translations(&self, locale: &LocaleId) -> bool {
+        self.ui_locale.language != locale.language
+            && !self.translations.contains_key(&locale.language)
+    }
+
+    /// Returns the timezones database translated to the given language, if it is available.
+    ///
+    /// It does not read any translation, so it only returns the current database or a cached
+    /// one.
+    ///
+    /// * `language`: language code (e.g., "fr").
+    pub fn timezones_for(&self, language: &str) -> Option<&TimezonesDatabase> {
+        if self.ui_locale.language == language {
+            return Some(&self.timezones_db);
         }
+        self.translations.get(language).map(|(_, db)| db)
+    }
 
-        let keymap = keymap_id.to_string();
+    /// Adds the given translated databases to the cache, so they are used by [Self::translate].
+    ///
+    /// * `language`: language of the translations.
+    /// * `translations`: databases returned by [Self::read_translations].
+    pub fn add_translations(
+        &mut self,
+        language: &str,
+        translations: (LocalesDatabase, TimezonesDatabase),
+    ) {
+        self.translations.insert(language.to_string(), translations);
+    }
+
+    /// Reads the locales and timezones databases translated to the given language.
+    ///
+    /// It is an expensive operation that does not need an [L10n] instance, so it can run
+    /// without holding any lock.
+    ///
+    /// * `language`: language code (e.g., "es").
+    pub fn read_translations(
+        language: &str,
+    ) -> Result<(LocalesDatabase, TimezonesDatabase), Error> {
+        let mut locales_db = LocalesDatabase::new();
+        locales_db.read(language)?;
+        let mut timezones_db = TimezonesDatabase::new();
+        timezones_db.read(language)?;
+        Ok((locales_db, timezones_db))
+    }
+
+    /// Sets the keymap for the user interface.
+    ///
+    /// See [Self::apply_ui_keymap] for further details.
+    ///
+    /// * `keymap_id`: keymap to use.
+    /// * `x_display`: X display to apply the keymap to (e.g., ":0").
+    pub async fn set_ui_keymap(
+        &mut self,
+        keymap_id: KeymapId,
+        x_display: &str,
+    ) -> Result<UiKeymapMethod, LocaleError> {
+        self.validate_keymap(&keymap_id)?;
+        let options = &self.ui_keyboard_options;
+        let method =
+            Self::apply_ui_keymap(&keymap_id, options, &self.ui_keymap, options, x_display).await?;
         self.ui_keymap = keymap_id;
+        Ok(method)
+    }
 
-        Command::new("/usr/bin/localectl")
-            .args(["set-x11-keymap", &keymap])
-            .output()
-            .map_err(LocaleError::Commit)?;
+    /// Applies the keymap to the user interface without changing the [L10n] state.
+    ///
+    /// When the X server is running, it changes the X11 keymap using `localectl` and applies it
+    /// with `setxkbmap`. If the latter fails (including a non-zero exit status), it restores the
+    /// previous X11 keymap and returns a [LocaleError::Commit] error. If the previous keymap cannot
+    /// be restored, the system is left half-applied and it returns a [LocaleError::PartialCommit]
+    /// error instead (see [rollback_on_error]).
+    ///
+    /// Without an X server (e.g., text-mode installations), it applies the keymap to the virtual
+    /// console using `localectl set-keymap`.
+    ///
+    /// The keyboard options are applied only to the X server, as the virtual console does not
+    /// support them. When there are no options, neither now nor before, the options already set
+    /// in the X server are kept untouched.
+    ///
+    /// If any of the required tools is missing, it returns a [LocaleError::MissingTools] error
+    /// without changing anything.
+    ///
+    /// It returns the method used to apply the keymap. As it runs slow commands, it does not
+    /// need an [L10n] instance, so it can run without holding any lock.
+    ///
+    /// * `keymap_id`: keymap to use.
+    /// * `options`: keyboard options to use (see [KEYBOARD_OPTIONS]).
+    /// * `previous`: keymap to restore if something fails.
+    /// * `previous_options`: keyboard options to restore if something fails.
+    /// * `x_display`: X display to apply the keymap to (e.g., ":0").
+    pub async fn apply_ui_keymap(
+        keymap_id: &KeymapId,
+        options: &[String],
+        previous: &KeymapId,
+        previous_options: &[String],
+        x_display: &str,
+    ) -> Result<UiKeymapMethod, LocaleError> {
+        let keymap = keymap_id.to_string();
+        let x11 = x_display_available(x_display);
+        let tools: &[&str] = if x11 {
+            &[LOCALECTL, SETXKBMAP]
+        } else {
+            &[LOCALECTL]
+        };
+        ToolsCheck::run_for(tools).require(tools)?;
 
-        let output = run_with_timeout(
-            &["setxkbmap", "-display", &display(), &keymap],
-            SETXKBMAP_TIMEOUT,
+        if !x11 {
+            tracing::info!(
+                "No X server on display {}, applying the keymap {} to the console",
+                x_display,
+                &keymap
+            );
+            if !options.is_empty() {
+                tracing::info!("Keyboard options are not supported by the console, ignoring them");
+            }
+            Self::run_localectl(&["set-keymap", &keymap]).await?;
+            return Ok(UiKeymapMethod::Console);
+        }
+
+        let set_options = !options.is_empty() || !previous_options.is_empty();
+        Self::set_x11_keymap(keymap_id, set_options.then_some(options)).await?;
+
+        tracing::info!(
+            "Applying the keymap {} to the X display {}",
+            &keymap,
+            x_display
         );
-        output.map_err(|e| {
-            LocaleError::Commit(io::Error::new(io::ErrorKind::Other, e.to_string()))
-        })?;
+        let mut command = vec![SETXKBMAP, "-display", x_display, &keymap];
+        if set_options {
+            // an empty option clears the current ones
+            command.extend(["-option", ""]);
+            for option in options {
+                command.extend(["-option", option.as_str()]);
+            }
+        }
+        rollback_on_error(
+            Self::run_checked(&command, SETXKBMAP_TIMEOUT),
+            Self::set_x11_keymap(previous, set_options.then_some(previous_options)),
+            "the previous X11 keymap",
+        )
+        .await?;
+
+        Ok(UiKeymapMethod::X11)
+    }
+
+    // sets the X11 keymap using localectl, replacing the keyboard options if they are given
+    async fn set_x11_keymap(
+        keymap: &KeymapId,
+        options: Option<&[String]>,
+    ) -> Result<(), LocaleError> {
+        let Some(options) = options else {
+            return Self::run_localectl(&["set-x11-keymap", &keymap.to_string()]).await;
+        };
+        let variant = keymap.variant.as_deref().unwrap_or_default();
+        let options = options.join(",");
+        Self::run_localectl(&["set-x11-keymap", &keymap.layout, "", variant, &options]).await
+    }
+
+    /// Sets whether the hardware clock of the running system keeps the time in UTC or in local
+    /// time.
+    ///
+    /// It uses `timedatectl set-local-rtc`, as `localectl` does not handle the hardware clock.
+    ///
+    /// * `utc`: whether the hardware clock is in UTC.
+    pub async fn apply_rtc_utc(utc: bool) -> Result<(), LocaleError> {
+        let local_rtc = if utc { "false" } else { "true" };
+        Self::run_checked(
+            &["/usr/bin/timedatectl", "set-local-rtc", local_rtc],
+            TIMEDATECTL_TIMEOUT,
+        )
+        .await
+    }
+
+    // runs localectl with the given arguments
+    async fn run_localectl(args: &[&str]) -> Result<(), LocaleError> {
+        let mut command = vec![LOCALECTL];
+        command.extend_from_slice(args);
+        Self::run_checked(&command, LOCALECTL_TIMEOUT).await
+    }
+
+    // runs the command, reporting an error if it fails
+    async fn run_checked(command: &[&str], timeout: u64) -> Result<(), LocaleError> {
+        let output = run_async_with_timeout(command, timeout)
+            .await
+            .map_err(LocaleError::Commit)?;
 
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(LocaleError::Commit(io::Error::new(
+                io::ErrorKind::Other,
+                stderr.trim().to_string(),
+            )));
+        }
         Ok(())
     }
 
@@ -210,19 +921,27 @@ impl L10n {
                 ROOT,
                 "--force",
                 "--locale",
-                self.locales.first().unwrap_or(&"en_US.UTF-8".to_string()),
+                self.primary_locale().unwrap_or("en_US.UTF-8"),
                 "--keymap",
                 &self.keymap.to_string(),
                 "--timezone",
                 &self.timezone,
             ])
             .status()?;
+
+        // systemd-firstboot does not handle the hardware clock
+        let adjtime = Path::new(ROOT).join(ADJTIME_PATH.trim_start_matches('/'));
+        if let Some(parent) = adjtime.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = std::fs::read_to_string(&adjtime).unwrap_or_default();
+        std::fs::write(&adjtime, adjtime_with_rtc_utc(&content, self.rtc_utc))?;
         Ok(())
     }
 
     fn x11_keymap() -> Result<String, io::Error> {
         let output = run_with_timeout(
-            &["setxkbmap", "-query", "-display", &display()],
+            &[SETXKBMAP, "-query", "-display", &display()],
             SETXKBMAP_TIMEOUT,
         );
         let output = output.map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
@@ -238,3 +957,132 @@ impl L10n {
         Ok(keymap)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        adjtime_with_rtc_utc, rollback_on_error, rtc_utc_from_adjtime, x11_socket_path, L10n,
+    };
+    use crate::l10n::{LocaleConfig, LocaleError};
+    use agama_locale_data::LocaleId;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_x11_socket_path() {
+        assert_eq!(
+            x11_socket_path(":0"),
+            Some(PathBuf::from("/tmp/.X11-unix/X0"))
+        );
+        assert_eq!(
+            x11_socket_path(":1.0"),
+            Some(PathBuf::from("/tmp/.X11-unix/X1"))
+        );
+        assert_eq!(x11_socket_path("localhost:10.0"), None);
+        assert_eq!(x11_socket_path(":"), None);
+    }
+
+    #[test]
+    fn test_new_empty() {
+        let l10n = L10n::new_empty(&LocaleId::default());
+        let config = l10n.default_config();
+        assert_eq!(config.locales, Some(vec!["en_US.UTF-8".to_string()]));
+        assert_eq!(config.timezone, Some("Europe/Berlin".to_string()));
+        assert!(l10n.default_locale_entry().is_none());
+    }
+
+    #[test]
+    fn test_validate_malformed_locales() {
+        let l10n = L10n::new_empty(&LocaleId::default());
+        let config = LocaleConfig {
+            locales: Some(vec!["es-".to_string()]),
+            ui_locale: Some("es-".to_string()),
+            ..Default::default()
+        };
+        let Err(errors) = l10n.validate_config(&config) else {
+            panic!("Expected validation errors");
+        };
+        assert!(matches!(
+            errors.as_slice(),
+            [
+                ("locales", LocaleError::InvalidLocale(_)),
+                ("uiLocale", LocaleError::InvalidLocale(_))
+            ]
+        ));
+
+        let result = l10n.validate_locales(&["xx_XX".to_string()]);
+        assert!(matches!(result, Err(LocaleError::UnknownLocales(_))));
+    }
+
+    #[test]
+    fn test_validate_ntp_servers() {
+        let servers = vec![" pool.ntp.org".to_string(), "10.0.0.1".to_string()];
+        assert_eq!(
+            L10n::validate_ntp_servers(&servers).unwrap(),
+            vec!["pool.ntp.org", "10.0.0.1"]
+        );
+
+        let servers = vec![
+            "pool.ntp.org".to_string(),
+            "ntp_1".to_string(),
+            "".to_string(),
+        ];
+        let error = L10n::validate_ntp_servers(&servers).unwrap_err();
+        assert!(matches!(error, LocaleError::InvalidNtpServers(s) if s == ["ntp_1", ""]));
+    }
+
+    #[test]
+    fn test_validate_keyboard_options() {
+        let options = vec![
+            "numpad:mac".to_string(),
+            " caps:none".to_string(),
+            "numpad:mac".to_string(),
+        ];
+        assert_eq!(
+            L10n::validate_keyboard_options(&options).unwrap(),
+            vec!["numpad:mac", "caps:none"]
+        );
+
+        let options = vec!["numpad:mac".to_string(), "grp:alt_shift_toggle".to_string()];
+        let error = L10n::validate_keyboard_options(&options).unwrap_err();
+        assert!(
+            matches!(error, LocaleError::UnknownKeyboardOptions(o) if o == ["grp:alt_shift_toggle"])
+        );
+    }
+
+    #[test]
+    fn test_rtc_utc_from_adjtime() {
+        assert!(rtc_utc_from_adjtime("0.0 0 0.0\n0\nUTC\n"));
+        assert!(!rtc_utc_from_adjtime("0.0 0 0.0\n0\nLOCAL\n"));
+        assert!(rtc_utc_from_adjtime("0.0 0 0.0\n0\n"));
+        assert!(rtc_utc_from_adjtime(""));
+    }
+
+    #[test]
+    fn test_adjtime_with_rtc_utc() {
+        assert_eq!(
+            adjtime_with_rtc_utc("0.5 1700000000 0.0\n1700000000\nUTC\n", false),
+            "0.5 1700000000 0.0\n1700000000\nLOCAL\n"
+        );
+        assert_eq!(adjtime_with_rtc_utc("", true), "0.0 0 0.0\n0\nUTC\n");
+        let content = adjtime_with_rtc_utc("", false);
+        assert!(!rtc_utc_from_adjtime(&content));
+    }
+
+    #[tokio::test]
+    async fn test_rollback_on_error() {
+        let failing = || L10n::run_checked(&["/usr/bin/false"], 5);
+        let succeeding = || L10n::run_checked(&["/usr/bin/true"], 5);
+
+        let result = rollback_on_error(succeeding(), failing(), "the previous keymap").await;
+        assert!(result.is_ok());
+
+        let result = rollback_on_error(failing(), succeeding(), "the previous keymap").await;
+        assert!(matches!(result, Err(LocaleError::Commit(_))));
+
+        let result = rollback_on_error(failing(), failing(), "the previous keymap").await;
+        let Err(LocaleError::PartialCommit(message)) = result else {
+            panic!("Expected a PartialCommit error");
+        };
+        assert!(message.contains("the previous keymap could not be restored"));
+    }
+}
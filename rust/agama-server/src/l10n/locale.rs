@@ -1,11 +1,12 @@
 //! This module provides support for reading the locales database.
 
+use super::helpers::normalize_text;
 use crate::error::Error;
 use agama_locale_data::{InvalidLocaleCode, LocaleId};
 use anyhow::Context;
 use serde::Serialize;
 use serde_with::{serde_as, DisplayFromStr};
-use std::{fs, process::Command};
+use std::{collections::HashSet, fs, process::Command};
 
 /// Represents a locale, including the localized language and territory.
 #[serde_as]
@@ -18,6 +19,94 @@ pub struct LocaleEntry {
     pub language: String,
     /// Localized territory name (e.g., "Spain", "España", etc.)
     pub territory: String,
+    /// Language name in the language itself (e.g., "Español")
+    pub endonym: String,
+    /// Language name in English (e.g., "Spanish")
+    pub english_name: String,
+    /// ISO 15924 code of the script (e.g., "Latn", "Arab", "Hans"), if known
+    pub script: Option<String>,
+    /// Text direction of the script
+    pub direction: TextDirection,
+}
+
+/// Represents a language, regardless of the territory.
+#[derive(Debug, Serialize, Clone, PartialEq, utoipa::ToSchema)]
+pub struct LanguageEntry {
+    /// The language code (e.g., "es").
+    pub code: String,
+    /// Localized language name (e.g., "Spanish", "Español", etc.)
+    pub name: String,
+    /// Language name in the language itself (e.g., "Español")
+    pub endonym: String,
+}
+
+/// Locale selected for installation.
+#[derive(Debug, Serialize, Clone, utoipa::ToSchema)]
+pub struct SelectedLocale {
+    #[serde(flatten)]
+    pub entry: LocaleEntry,
+    /// Whether the locale is known in the locales database
+    pub valid: bool,
+}
+
+/// Text direction of a script.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TextDirection {
+    /// Left-to-right
+    #[default]
+    Ltr,
+    /// Right-to-left
+    Rtl,
+}
+
+impl TextDirection {
+    // ISO 15924 codes of the right-to-left scripts.
+    const RTL_SCRIPTS: [&'static str; 10] = [
+        "Adlm", "Arab", "Hebr", "Mand", "Nkoo", "Rohg", "Samr", "Syrc", "Thaa", "Yezi",
+    ];
+
+    /// Returns the text direction of the given script.
+    ///
+    /// It defaults to left-to-right when the script is unknown.
+    ///
+    /// * `script`: ISO 15924 code of the script (e.g., "Arab").
+    pub fn for_script(script: Option<&str>) -> Self {
+        match script {
+            Some(script) if Self::RTL_SCRIPTS.contains(&script) => Self::Rtl,
+            _ => Self::Ltr,
+        }
+    }
+}
+
+impl LocaleEntry {
+    /// Builds an entry for a locale which is not included in the locales database.
+    ///
+    /// As there are no translations, the language and territory codes are used as names.
+    ///
+    /// * `id`: locale code.
+    pub fn unknown(id: LocaleId) -> Self {
+        Self {
+            language: id.language.clone(),
+            territory: id.territory.clone(),
+            endonym: id.language.clone(),
+            english_name: id.language.clone(),
+            script: None,
+            direction: TextDirection::default(),
+            id,
+        }
+    }
+
+    /// Determines whether the localized language or territory names contain the given text.
+    ///
+    /// The comparison is case and accent insensitive (e.g., "espanol" matches "Español").
+    ///
+    /// * `search`: text to search for.
+    pub fn matches(&self, search: &str) -> bool {
+        let search = normalize_text(search);
+        normalize_text(&self.language).contains(&search)
+            || normalize_text(&self.territory).contains(&search)
+    }
 }
 
 /// Represents the locales database.
@@ -43,7 +132,7 @@ impl LocalesDatabase {
     /// * `ui_language`: language to translate the descriptions (e.g., "en").
     pub fn read(&mut self, ui_language: &str) -> Result<(), Error> {
         self.known_locales = Self::get_locales_list()?;
-        self.locales = self.get_locales(ui_language)?;
+        self.locales = Self::sort_and_dedup(self.get_locales(ui_language)?);
         Ok(())
     }
 
@@ -60,11 +149,62 @@ impl LocalesDatabase {
         false
     }
 
+    /// Returns the locale with the given ID, if it exists.
+    pub fn find(&self, id: &LocaleId) -> Option<&LocaleEntry> {
+        self.locales.iter().find(|l| &l.id == id)
+    }
+
+    /// Returns the known locales matching the given language ranges.
+    ///
+    /// It implements the RFC 4647 basic filtering: a range matches a locale when it is equal to
+    /// (or a prefix of) its language tag (e.g., "es" and "es-ES" match "es_ES.UTF-8"). The "*"
+    /// range matches any locale. The result is sorted according to the ranges order.
+    ///
+    /// * `ranges`: language ranges sorted by preference (e.g., ["es-ES", "en"]).
+    pub fn negotiate(&self, ranges: &[String]) -> Vec<LocaleId> {
+        let mut result: Vec<LocaleId> = vec![];
+        for range in ranges {
+            let range = range.to_lowercase();
+            for locale in &self.known_locales {
+                let tag = format!("{}-{}", &locale.language, &locale.territory).to_lowercase();
+                let matches = range == "*"
+                    || tag == range
+                    || tag
+                        .strip_prefix(&range)
+                        .is_some_and(|rest| rest.starts_with('-'));
+                if matches && !result.contains(locale) {
+                    result.push(locale.clone());
+                }
+            }
+        }
+        result
+    }
+
     /// Returns the list of locales.
     pub fn entries(&self) -> &Vec<LocaleEntry> {
         &self.locales
     }
 
+    /// Returns the list of languages of the known locales.
+    ///
+    /// Each language is included only once, even if several territories share it. The list is
+    /// sorted by the endonym (case and accent insensitive).
+    pub fn languages(&self) -> Vec<LanguageEntry> {
+        let mut seen = HashSet::new();
+        let mut languages: Vec<_> = self
+            .locales
+            .iter()
+            .filter(|l| seen.insert(l.id.language.as_str()))
+            .map(|l| LanguageEntry {
+                code: l.id.language.clone(),
+                name: l.language.clone(),
+                endonym: l.endonym.clone(),
+            })
+            .collect();
+        languages.sort_by_cached_key(|l| normalize_text(&l.endonym));
+        languages
+    }
+
     /// Gets the supported locales information.
     ///
     /// * `ui_language`: language to use in the translations.
@@ -84,6 +224,13 @@ impl LocalesDatabase {
                 .or_else(|| names.name_for(DEFAULT_LANG))
                 .unwrap_or(language.id.to_string());
 
+            let endonym = names
+                .name_for(&code.language)
+                .unwrap_or(language_label.to_string());
+            let english_name = names
+                .name_for(DEFAULT_LANG)
+                .unwrap_or(language.id.to_string());
+
             let territory = territories
                 .find_by_id(&code.territory)
                 .context("territory not found")?;
@@ -94,10 +241,15 @@ impl LocalesDatabase {
                 .or_else(|| names.name_for(DEFAULT_LANG))
                 .unwrap_or(territory.id.to_string());
 
+            let script = language.script_for(Some(territory));
             let entry = LocaleEntry {
                 id: code.clone(),
                 language: language_label,
                 territory: territory_label,
+                endonym,
+                english_name,
+                script: script.map(|s| s.to_string()),
+                direction: TextDirection::for_script(script),
             };
             result.push(entry)
         }
@@ -105,6 +257,22 @@ impl LocalesDatabase {
         Ok(result)
     }
 
+    // Sorts the entries by the localized language and territory names (and the ID, to break the
+    // ties), dropping the entries with a duplicated ID. The comparison of the names is case and
+    // accent insensitive.
+    fn sort_and_dedup(mut locales: Vec<LocaleEntry>) -> Vec<LocaleEntry> {
+        let mut seen = HashSet::new();
+        locales.retain(|l| seen.insert(l.id.to_string()));
+        locales.sort_by_cached_key(|l| {
+            (
+                normalize_text(&l.language),
+                normalize_text(&l.territory),
+                l.id.to_string(),
+            )
+        });
+        locales
+    }
+
     fn get_locales_list() -> Result<Vec<LocaleId>, Error> {
         const LOCALES_LIST_PATH: &str = "/etc/agama.d/locales";
 
@@ -138,7 +306,7 @@ impl LocalesDatabase {
 
 #[cfg(test)]
 mod tests {
-    use super::LocalesDatabase;
+    use super::{LanguageEntry, LocaleEntry, LocalesDatabase, TextDirection};
     use agama_locale_data::LocaleId;
 
     #[test]
@@ -153,6 +321,20 @@ mod tests {
             .expect("Spanish locale not found?! Suggestion: zypper in glibc-locale");
         assert_eq!(&found.language, "Spanisch");
         assert_eq!(&found.territory, "Spanien");
+        assert_eq!(&found.endonym, "Español");
+        assert_eq!(&found.english_name, "Spanish");
+        assert_eq!(found.script.as_deref(), Some("Latn"));
+        assert_eq!(found.direction, TextDirection::Ltr);
+    }
+
+    #[test]
+    fn test_unknown_locale_entry() {
+        let id: LocaleId = "xx_YY.UTF-8".try_into().unwrap();
+        let entry = LocaleEntry::unknown(id.clone());
+        assert_eq!(entry.id, id);
+        assert_eq!(&entry.language, "xx");
+        assert_eq!(&entry.territory, "YY");
+        assert_eq!(entry.direction, TextDirection::Ltr);
     }
 
     #[test]
@@ -162,4 +344,102 @@ mod tests {
         assert!(db.exists("en_US"));
         assert!(!db.exists("unknown_UNKNOWN"));
     }
+
+    #[test]
+    fn test_locale_entry_matches() {
+        let entry = LocaleEntry {
+            id: "es_ES".try_into().unwrap(),
+            language: "Español".to_string(),
+            territory: "España".to_string(),
+            endonym: "Español".to_string(),
+            english_name: "Spanish".to_string(),
+            script: Some("Latn".to_string()),
+            direction: TextDirection::Ltr,
+        };
+        assert!(entry.matches("espanol"));
+        assert!(entry.matches("PAÑ"));
+        assert!(!entry.matches("port"));
+    }
+
+    #[test]
+    fn test_languages() {
+        let entry = |id: &str, language: &str, endonym: &str| {
+            let mut entry = LocaleEntry::unknown(id.try_into().unwrap());
+            entry.language = language.to_string();
+            entry.endonym = endonym.to_string();
+            entry
+        };
+        let db = LocalesDatabase {
+            known_locales: vec![],
+            locales: vec![
+                entry("es_ES", "Spanish", "Español"),
+                entry("de_DE", "German", "Deutsch"),
+                entry("es_AR", "Spanish", "Español"),
+                entry("en_US", "English", "English"),
+            ],
+        };
+        let language = |code: &str, name: &str, endonym: &str| LanguageEntry {
+            code: code.to_string(),
+            name: name.to_string(),
+            endonym: endonym.to_string(),
+        };
+        assert_eq!(
+            db.languages(),
+            vec![
+                language("de", "German", "Deutsch"),
+                language("en", "English", "English"),
+                language("es", "Spanish", "Español"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_and_dedup() {
+        let entry = |id: &str, language: &str, territory: &str| {
+            let mut entry = LocaleEntry::unknown(id.try_into().unwrap());
+            entry.language = language.to_string();
+            entry.territory = territory.to_string();
+            entry
+        };
+        let locales = LocalesDatabase::sort_and_dedup(vec![
+            entry("es_ES", "Spanish", "Spain"),
+            entry("de_DE", "German", "Germany"),
+            entry("es_AR", "Spanish", "Argentina"),
+            entry("es_ES.UTF-8", "Spanish", "Spain"),
+            entry("de_AT", "German", "Austria"),
+        ]);
+        let ids: Vec<_> = locales.iter().map(|l| l.id.to_string()).collect();
+        assert_eq!(
+            ids,
+            vec!["de_AT.UTF-8", "de_DE.UTF-8", "es_AR.UTF-8", "es_ES.UTF-8"]
+        );
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let db = LocalesDatabase {
+            known_locales: ["en_US", "es_AR", "es_ES", "pt_BR"]
+                .iter()
+                .map(|l| LocaleId::try_from(*l).unwrap())
+                .collect(),
+            locales: vec![],
+        };
+        let ranges = vec!["es-ES".to_string(), "es".to_string(), "EN".to_string()];
+        let found: Vec<_> = db
+            .negotiate(&ranges)
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        assert_eq!(found, vec!["es_ES.UTF-8", "es_AR.UTF-8", "en_US.UTF-8"]);
+        assert!(db.negotiate(&["e".to_string()]).is_empty());
+        assert_eq!(db.negotiate(&["*".to_string()]).len(), 4);
+    }
+
+    #[test]
+    fn test_text_direction_for_script() {
+        assert_eq!(TextDirection::for_script(Some("Arab")), TextDirection::Rtl);
+        assert_eq!(TextDirection::for_script(Some("Hebr")), TextDirection::Rtl);
+        assert_eq!(TextDirection::for_script(Some("Hans")), TextDirection::Ltr);
+        assert_eq!(TextDirection::for_script(None), TextDirection::Ltr);
+    }
 }
@@ -0,0 +1,70 @@
+//! Usage counters of the localization API.
+//!
+//! They are kept in memory and exposed through the `/metrics` endpoint, so it is possible to find
+//! out which endpoints are used and which errors are the most common ones.
+
+use serde::Serialize;
+use std::{collections::BTreeMap, sync::Mutex};
+
+/// Counters of the localization API usage.
+#[derive(Debug, Default)]
+pub struct L10nMetrics {
+    requests: Mutex<BTreeMap<String, u64>>,
+    failures: Mutex<BTreeMap<&'static str, u64>>,
+}
+
+/// Snapshot of the [L10nMetrics] counters.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, utoipa::ToSchema)]
+pub struct L10nMetricsSnapshot {
+    /// Number of requests by endpoint (e.g., "GET /locales").
+    pub requests: BTreeMap<String, u64>,
+    /// Number of failures by error code (e.g., "unknown_locale").
+    pub failures: BTreeMap<&'static str, u64>,
+}
+
+impl L10nMetrics {
+    /// Counts a request to an endpoint.
+    ///
+    /// * `method`: HTTP method (e.g., "GET").
+    /// * `path`: route of the endpoint (e.g., "/locales/:id").
+    pub fn record_request(&self, method: &str, path: &str) {
+        let mut requests = self.requests.lock().unwrap();
+        *requests.entry(format!("{method} {path}")).or_default() += 1;
+    }
+
+    /// Counts a failure.
+    ///
+    /// * `code`: error code (see [LocaleError::code](super::LocaleError::code)).
+    pub fn record_failure(&self, code: &'static str) {
+        let mut failures = self.failures.lock().unwrap();
+        *failures.entry(code).or_default() += 1;
+    }
+
+    /// Returns the current value of the counters.
+    pub fn snapshot(&self) -> L10nMetricsSnapshot {
+        L10nMetricsSnapshot {
+            requests: self.requests.lock().unwrap().clone(),
+            failures: self.failures.lock().unwrap().clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::L10nMetrics;
+
+    #[test]
+    fn test_metrics() {
+        let metrics = L10nMetrics::default();
+        metrics.record_request("GET", "/locales");
+        metrics.record_request("GET", "/locales");
+        metrics.record_request("PATCH", "/config");
+        metrics.record_failure("unknown_locale");
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.requests.get("GET /locales"), Some(&2));
+        assert_eq!(snapshot.requests.get("PATCH /config"), Some(&1));
+        assert_eq!(snapshot.failures.get("unknown_locale"), Some(&1));
+        assert_eq!(snapshot.failures.get("unknown_timezone"), None);
+    }
+}
@@ -0,0 +1,201 @@
+//! This module builds previews of the translations for a user interface locale.
+//!
+//! The strings are taken from the gettext catalog of the service (the keymap descriptions from
+//! xkeyboard-config), which is read directly from its `.mo` file. This way, the service locale is
+//! not changed.
+
+use agama_locale_data::LocaleId;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs, io,
+    path::Path,
+};
+
+/// Directory containing the gettext catalogs.
+pub const LOCALE_DIR: &str = "/usr/share/locale";
+// Text domain of the translated strings.
+const TEXT_DOMAIN: &str = "xkeyboard-config";
+// Magic number of the .mo files (as read in little-endian order).
+const MO_MAGIC: u32 = 0x950412de;
+// Keymaps whose descriptions are included in the preview.
+const PREVIEW_KEYMAPS: [(&str, &str); 4] = [
+    ("us", "English (US)"),
+    ("de", "German"),
+    ("es", "Spanish"),
+    ("fr", "French"),
+];
+
+/// Messages of a gettext catalog.
+#[derive(Debug, Default)]
+pub struct Catalog {
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    /// Reads the catalog of the given locale from the given directory.
+    ///
+    /// It looks for the catalog of the language and territory first (e.g., "pt_BR") and then for
+    /// the catalog of the language (e.g., "pt"). It returns `None` if there is no catalog.
+    ///
+    /// * `dir`: directory containing the catalogs (see [LOCALE_DIR]).
+    /// * `locale`: locale to read the catalog for.
+    pub fn for_locale(dir: &Path, locale: &LocaleId) -> io::Result<Option<Self>> {
+        let names = [
+            format!("{}_{}", locale.language, locale.territory),
+            locale.language.clone(),
+        ];
+        for name in names {
+            let path = dir
+                .join(name)
+                .join("LC_MESSAGES")
+                .join(format!("{TEXT_DOMAIN}.mo"));
+            match fs::read(&path) {
+                Ok(content) => return Self::parse(&content).map(Some),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Parses the content of a `.mo` file.
+    ///
+    /// * `content`: content of the file.
+    pub fn parse(content: &[u8]) -> io::Result<Self> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "Not a valid .mo file");
+        let read_u32 = |offset: usize, big_endian: bool| {
+            let bytes: [u8; 4] = content.get(offset..offset + 4)?.try_into().ok()?;
+            Some(if big_endian {
+                u32::from_be_bytes(bytes)
+            } else {
+                u32::from_le_bytes(bytes)
+            })
+        };
+        let big_endian = match read_u32(0, false) {
+            Some(MO_MAGIC) => false,
+            Some(magic) if magic.swap_bytes() == MO_MAGIC => true,
+            _ => return Err(invalid()),
+        };
+        let read_u32 = |offset: usize| read_u32(offset, big_endian).map(|n| n as usize);
+        // the table entries are the length and the offset of each string
+        let read_str = |table: usize, index: usize| {
+            let entry = table + index * 8;
+            let (len, offset) = (read_u32(entry)?, read_u32(entry + 4)?);
+            let bytes = content.get(offset..offset.checked_add(len)?)?;
+            String::from_utf8(bytes.to_vec()).ok()
+        };
+
+        let count = read_u32(8).ok_or_else(invalid)?;
+        let originals = read_u32(12).ok_or_else(invalid)?;
+        let translations = read_u32(16).ok_or_else(invalid)?;
+        let mut messages = HashMap::with_capacity(count);
+        for index in 0..count {
+            let msgid = read_str(originals, index).ok_or_else(invalid)?;
+            let msgstr = read_str(translations, index).ok_or_else(invalid)?;
+            // skip the header (empty msgid) and the untranslated messages
+            if !msgid.is_empty() && !msgstr.is_empty() {
+                messages.insert(msgid, msgstr);
+            }
+        }
+        Ok(Self { messages })
+    }
+
+    /// Returns the translation of the given message or the message itself if there is none.
+    ///
+    /// * `msgid`: message to translate.
+    pub fn gettext<'a>(&'a self, msgid: &'a str) -> &'a str {
+        self.messages.get(msgid).map_or(msgid, String::as_str)
+    }
+}
+
+/// Translates a fixed set of strings to the given locale.
+///
+/// The keys are "keymap.<id>" (e.g., "keymap.de") and the values are the keymap descriptions
+/// translated using the gettext catalog of the locale. When there is no translation (or no
+/// catalog at all), the English text is used. It does not change the service locale.
+///
+/// * `dir`: directory containing the gettext catalogs (see [LOCALE_DIR]).
+/// * `locale`: locale to translate the strings to.
+pub fn translation_preview(dir: &Path, locale: &LocaleId) -> BTreeMap<String, String> {
+    let catalog = match Catalog::for_locale(dir, locale) {
+        Ok(catalog) => catalog.unwrap_or_default(),
+        Err(e) => {
+            log::warn!("Could not read the translations for {locale}: {e}");
+            Catalog::default()
+        }
+    };
+
+    PREVIEW_KEYMAPS
+        .iter()
+        .map(|(id, description)| {
+            (
+                format!("keymap.{id}"),
+                catalog.gettext(description).to_string(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{translation_preview, Catalog};
+    use agama_locale_data::LocaleId;
+    use std::fs;
+
+    // builds a little-endian .mo file containing the given messages (sorted by msgid)
+    fn mo_file(messages: &[(&str, &str)]) -> Vec<u8> {
+        let count = messages.len() as u32;
+        let originals = 28;
+        let translations = originals + count * 8;
+        let mut strings_offset = translations + count * 8;
+        let mut tables = [vec![], vec![]];
+        let mut strings = vec![];
+        for (msgid, msgstr) in messages {
+            for (table, string) in tables.iter_mut().zip([msgid, msgstr]) {
+                table.extend((string.len() as u32).to_le_bytes());
+                table.extend(strings_offset.to_le_bytes());
+                strings.extend(string.as_bytes());
+                strings.push(0);
+                strings_offset += string.len() as u32 + 1;
+            }
+        }
+
+        let mut content = vec![];
+        for n in [0x950412de, 0, count, originals, translations, 0, 0] {
+            content.extend(u32::to_le_bytes(n));
+        }
+        content.extend(tables.concat());
+        content.extend(strings);
+        content
+    }
+
+    #[test]
+    fn test_parse_catalog() {
+        let content = mo_file(&[("", "Content-Type: text/plain"), ("German", "Alemán")]);
+        let catalog = Catalog::parse(&content).unwrap();
+        assert_eq!(catalog.gettext("German"), "Alemán");
+        assert_eq!(catalog.gettext("French"), "French");
+        assert_eq!(catalog.gettext(""), "");
+
+        assert!(Catalog::parse(b"not a catalog").is_err());
+    }
+
+    #[test]
+    fn test_translation_preview() {
+        let dir = tempfile::tempdir().unwrap();
+        let messages_dir = dir.path().join("es/LC_MESSAGES");
+        fs::create_dir_all(&messages_dir).unwrap();
+        let content = mo_file(&[("German", "Alemán"), ("Spanish", "Español")]);
+        fs::write(messages_dir.join("xkeyboard-config.mo"), content).unwrap();
+
+        let locale = LocaleId::try_from("es_ES").unwrap();
+        let strings = translation_preview(dir.path(), &locale);
+        assert_eq!(strings.get("keymap.de").unwrap(), "Alemán");
+        assert_eq!(strings.get("keymap.es").unwrap(), "Español");
+        assert_eq!(strings.get("keymap.fr").unwrap(), "French");
+
+        let locale = LocaleId::try_from("cs_CZ").unwrap();
+        let strings = translation_preview(dir.path(), &locale);
+        assert_eq!(strings.get("keymap.de").unwrap(), "German");
+    }
+}
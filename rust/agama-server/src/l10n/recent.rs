@@ -0,0 +1,89 @@
+//! This module implements the list of recently used locales.
+
+use std::collections::VecDeque;
+
+/// Default number of locales to keep in the list of recently used ones.
+pub const DEFAULT_RECENT_LOCALES: usize = 10;
+
+/// Keeps the recently used locales, the most recent one first.
+///
+/// A locale appears only once: using it again moves it to the front. When the list is full, the
+/// least recently used locale is discarded.
+#[derive(Debug)]
+pub struct RecentLocales {
+    locales: VecDeque<String>,
+    size: usize,
+}
+
+impl RecentLocales {
+    /// Creates an empty list.
+    ///
+    /// * `size`: maximum number of locales to keep.
+    pub fn new(size: usize) -> Self {
+        Self {
+            locales: VecDeque::with_capacity(size),
+            size,
+        }
+    }
+
+    /// Adds the given locales to the front of the list, keeping their order.
+    ///
+    /// * `locales`: locale IDs (e.g., "es_ES.UTF-8").
+    pub fn push(&mut self, locales: &[String]) {
+        for locale in locales.iter().rev() {
+            self.locales.retain(|l| l != locale);
+            self.locales.push_front(locale.clone());
+        }
+        self.locales.truncate(self.size);
+    }
+
+    /// Returns the locales, the most recent one first.
+    pub fn locales(&self) -> Vec<String> {
+        self.locales.iter().cloned().collect()
+    }
+}
+
+impl Default for RecentLocales {
+    fn default() -> Self {
+        Self::new(DEFAULT_RECENT_LOCALES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RecentLocales;
+
+    fn locales(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_push() {
+        let mut recent = RecentLocales::new(3);
+        recent.push(&locales(&["en_US.UTF-8"]));
+        recent.push(&locales(&["es_ES.UTF-8", "de_DE.UTF-8"]));
+        assert_eq!(
+            recent.locales(),
+            locales(&["es_ES.UTF-8", "de_DE.UTF-8", "en_US.UTF-8"])
+        );
+
+        recent.push(&locales(&["en_US.UTF-8"]));
+        assert_eq!(
+            recent.locales(),
+            locales(&["en_US.UTF-8", "es_ES.UTF-8", "de_DE.UTF-8"])
+        );
+
+        recent.push(&locales(&["cs_CZ.UTF-8"]));
+        assert_eq!(
+            recent.locales(),
+            locales(&["cs_CZ.UTF-8", "en_US.UTF-8", "es_ES.UTF-8"])
+        );
+    }
+
+    #[test]
+    fn test_no_recent_locales() {
+        let mut recent = RecentLocales::new(0);
+        recent.push(&locales(&["en_US.UTF-8"]));
+        assert!(recent.locales().is_empty());
+    }
+}
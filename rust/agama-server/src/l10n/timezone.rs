@@ -1,10 +1,13 @@
 //! This module provides support for reading the timezones database.
 
+use super::helpers::normalize_text;
 use crate::error::Error;
 use agama_locale_data::territory::Territories;
 use agama_locale_data::timezone_part::TimezoneIdParts;
+use chrono::{DateTime, Offset, Utc};
+use chrono_tz::{OffsetComponents, Tz};
 use serde::Serialize;
-use std::collections::HashMap;
+use std::{collections::HashMap, fs, path::Path};
 
 /// Represents a timezone, including each part as localized.
 #[derive(Clone, Debug, Serialize, utoipa::ToSchema)]
@@ -15,6 +18,68 @@ pub struct TimezoneEntry {
     pub parts: Vec<String>,
     /// Localized name of the territory this timezone is associated to
     pub country: Option<String>,
+    /// ISO 3166 alpha-2 code of the territory this timezone is associated to (e.g., "ES")
+    #[serde(skip)]
+    pub country_code: Option<String>,
+    /// Current offset from UTC in minutes (e.g., 345 for "Asia/Kathmandu")
+    pub utc_offset: i32,
+    /// Whether the daylight saving time is currently in effect
+    pub dst: bool,
+    /// Importance of the timezone within its territory (bigger is more important)
+    #[serde(skip)]
+    pub rank: Option<u16>,
+}
+
+impl TimezoneEntry {
+    /// Updates the UTC offset and the DST flag according to the given time.
+    ///
+    /// * `time`: time to calculate the offset for.
+    pub fn update_offset(&mut self, time: &DateTime<Utc>) {
+        let Ok(tz) = self.code.parse::<Tz>() else {
+            log::warn!("Could not calculate the offset for timezone {}", &self.code);
+            return;
+        };
+        let offset = *time.with_timezone(&tz).offset();
+        self.utc_offset = offset.fix().local_minus_utc() / 60;
+        self.dst = offset.dst_offset().num_seconds() != 0;
+    }
+
+    /// Ranks how well the timezone matches the given text, if it matches at all.
+    ///
+    /// The text is compared with the city (the last part of the code, like "New York" for
+    /// "America/New_York", or its localized name) and with the rest of the localized names (e.g.,
+    /// the region or the country), ignoring the case and the accents. The lower the rank, the
+    /// better the match:
+    ///
+    /// * 0: the city is equal to the text.
+    /// * 1: the city starts with the text.
+    /// * 2: the city contains the text.
+    /// * 3: another localized name contains the text.
+    ///
+    /// * `search`: text to search for (e.g., "madrid").
+    pub fn search_rank(&self, search: &str) -> Option<u8> {
+        let search = normalize_text(search.trim()).replace('_', " ");
+        let city = self.code.rsplit('/').next().unwrap_or_default();
+        let mut cities = vec![normalize_text(city).replace('_', " ")];
+        if let Some(localized) = self.parts.last() {
+            cities.push(normalize_text(localized));
+        }
+
+        if cities.contains(&search) {
+            return Some(0);
+        }
+        if cities.iter().any(|c| c.starts_with(&search)) {
+            return Some(1);
+        }
+        if cities.iter().any(|c| c.contains(&search)) {
+            return Some(2);
+        }
+        self.parts
+            .iter()
+            .chain(self.country.iter())
+            .any(|n| normalize_text(n).contains(&search))
+            .then_some(3)
+    }
 }
 
 #[derive(Default)]
@@ -40,11 +105,83 @@ impl TimezonesDatabase {
         self.timezones.iter().any(|t| &t.code == timezone)
     }
 
+    /// Returns the canonical name of the given timezone, if it is known.
+    ///
+    /// The comparison is case insensitive and the deprecated names (e.g., "Asia/Calcutta") are
+    /// resolved to the current ones (e.g., "Asia/Kolkata").
+    ///
+    /// * `timezone`: timezone name.
+    pub fn canonical_name(&self, timezone: &str) -> Option<&str> {
+        let find = |name: &str| {
+            self.timezones
+                .iter()
+                .find(|t| t.code.eq_ignore_ascii_case(name))
+                .map(|t| t.code.as_str())
+        };
+
+        find(timezone).or_else(|| agama_locale_data::timezone_alias_target(timezone).and_then(find))
+    }
+
     /// Returns the list of timezones.
     pub fn entries(&self) -> &Vec<TimezoneEntry> {
         &self.timezones
     }
 
+    /// Returns the timezones, optionally filtered by country, with their current UTC offset.
+    ///
+    /// * `country`: ISO 3166 alpha-2 code of the country to filter by (e.g., "ES"), if any.
+    pub fn entries_with_offset(&self, country: Option<&str>) -> Vec<TimezoneEntry> {
+        let now = Utc::now();
+        let mut timezones: Vec<_> = self
+            .timezones
+            .iter()
+            .filter(|t| {
+                country.map_or(true, |country| {
+                    t.country_code
+                        .as_ref()
+                        .is_some_and(|c| c.eq_ignore_ascii_case(country))
+                })
+            })
+            .cloned()
+            .collect();
+        timezones.iter_mut().for_each(|t| t.update_offset(&now));
+        timezones
+    }
+
+    /// Returns the primary timezone of the given country, if any.
+    ///
+    /// When the country has a single timezone, it is the primary one. Otherwise, the primary
+    /// timezone is the one with the highest rank (according to the langtable data). If several
+    /// timezones share the highest rank (or there is no rank at all), there is no clear primary
+    /// timezone and it returns `None`.
+    ///
+    /// * `country`: ISO 3166 alpha-2 code of the country (e.g., "ES").
+    pub fn suggest(&self, country: &str) -> Option<&TimezoneEntry> {
+        let timezones: Vec<_> = self
+            .timezones
+            .iter()
+            .filter(|t| {
+                t.country_code
+                    .as_ref()
+                    .is_some_and(|c| c.eq_ignore_ascii_case(country))
+            })
+            .collect();
+        if let [timezone] = timezones.as_slice() {
+            return Some(timezone);
+        }
+
+        let top_rank = timezones.iter().filter_map(|t| t.rank).max()?;
+        match timezones
+            .iter()
+            .filter(|t| t.rank == Some(top_rank))
+            .collect::<Vec<_>>()
+            .as_slice()
+        {
+            [timezone] => Some(timezone),
+            _ => None,
+        }
+    }
+
     /// Returns a list of the supported timezones.
     ///
     /// Each element of the list contains a timezone identifier and a vector
@@ -62,13 +199,22 @@ impl TimezonesDatabase {
             .into_iter()
             .filter_map(|tz| {
                 let parts = translate_parts(&tz, ui_language, &tz_parts);
-                let country = translate_country(&tz, ui_language, &tz_countries, &territories);
+                let country_code = find_country_code(&tz, &tz_countries);
+                let country =
+                    country_code.and_then(|c| translate_country(c, ui_language, &territories));
+                let rank = country_code
+                    .and_then(|c| territories.find_by_id(c))
+                    .and_then(|t| t.timezones.rank_of(&tz));
                 match country {
                     None if !COUNTRYLESS.contains(&tz.as_str()) => None,
                     _ => Some(TimezoneEntry {
                         code: tz,
                         parts,
                         country,
+                        country_code: country_code.map(|c| c.to_string()),
+                        utc_offset: 0,
+                        dst: false,
+                        rank,
                     }),
                 }
             })
@@ -78,6 +224,33 @@ impl TimezonesDatabase {
     }
 }
 
+/// Returns the timezone configured in the running system, if it can be determined.
+///
+/// It reads the target of the `/etc/localtime` symbolic link (e.g.,
+/// "/usr/share/zoneinfo/Europe/Berlin") and falls back to the content of `/etc/timezone`.
+pub fn detect_system_timezone() -> Option<String> {
+    fs::read_link("/etc/localtime")
+        .ok()
+        .and_then(|path| timezone_from_path(&path))
+        .or_else(|| {
+            let content = fs::read_to_string("/etc/timezone").ok()?;
+            let timezone = content.trim();
+            (!timezone.is_empty()).then(|| timezone.to_string())
+        })
+}
+
+// extracts the timezone name from a path to the timezones database
+fn timezone_from_path(path: &Path) -> Option<String> {
+    let path = path.to_str()?;
+    let (_, timezone) = path.split_once("zoneinfo/")?;
+    // skip the "posix/" and "right/" variants of the database
+    let timezone = timezone
+        .strip_prefix("posix/")
+        .or_else(|| timezone.strip_prefix("right/"))
+        .unwrap_or(timezone);
+    (!timezone.is_empty()).then(|| timezone.to_string())
+}
+
 fn translate_parts(timezone: &str, ui_language: &str, tz_parts: &TimezoneIdParts) -> Vec<String> {
     timezone
         .split('/')
@@ -89,26 +262,44 @@ fn translate_parts(timezone: &str, ui_language: &str, tz_parts: &TimezoneIdParts
         .collect()
 }
 
-fn translate_country(
+fn find_country_code<'a>(
     timezone: &str,
-    lang: &str,
-    countries: &HashMap<String, String>,
-    territories: &Territories,
-) -> Option<String> {
+    countries: &'a HashMap<String, String>,
+) -> Option<&'a str> {
     let tz = match timezone {
         "Asia/Rangoon" => "Asia/Yangon",
         "Europe/Kiev" => "Europe/Kyiv",
         _ => timezone,
     };
-    let country_id = countries.get(tz)?;
+    countries.get(tz).map(|c| c.as_str())
+}
+
+// translates the country name, falling back to English if there is no translation
+fn translate_country(country_id: &str, lang: &str, territories: &Territories) -> Option<String> {
     let territory = territories.find_by_id(country_id)?;
-    let name = territory.names.name_for(lang)?;
-    Some(name)
+    territory
+        .names
+        .name_for(lang)
+        .or_else(|| territory.names.name_for("en"))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::TimezonesDatabase;
+    use super::{timezone_from_path, TimezoneEntry, TimezonesDatabase};
+    use chrono::{TimeZone, Utc};
+    use std::path::Path;
+
+    fn timezone(code: &str, parts: &[&str], country: Option<&str>) -> TimezoneEntry {
+        TimezoneEntry {
+            code: code.to_string(),
+            parts: parts.iter().map(|p| p.to_string()).collect(),
+            country: country.map(str::to_string),
+            country_code: None,
+            utc_offset: 0,
+            dst: false,
+            rank: None,
+        }
+    }
 
     #[test]
     fn test_read_timezones() {
@@ -125,6 +316,7 @@ mod tests {
             vec!["Europa".to_string(), "Berlín".to_string()]
         );
         assert_eq!(found.country, Some("Alemania".to_string()));
+        assert_eq!(found.country_code, Some("DE".to_string()));
     }
 
     #[test]
@@ -154,4 +346,110 @@ mod tests {
         assert!(db.exists(&"Atlantic/Canary".to_string()));
         assert!(!db.exists(&"Unknown/Unknown".to_string()));
     }
+
+    #[test]
+    fn test_search_rank() {
+        let madrid = timezone("Europe/Madrid", &["Europa", "Madrid"], Some("España"));
+        assert_eq!(madrid.search_rank("madrid"), Some(0));
+        assert_eq!(madrid.search_rank("Mad"), Some(1));
+        assert_eq!(madrid.search_rank("drid"), Some(2));
+        assert_eq!(madrid.search_rank("espana"), Some(3));
+        assert_eq!(madrid.search_rank("berlin"), None);
+
+        let new_york = timezone("America/New_York", &["América", "Nueva York"], None);
+        assert_eq!(new_york.search_rank("new york"), Some(0));
+        assert_eq!(new_york.search_rank("New_York"), Some(0));
+        assert_eq!(new_york.search_rank("nueva"), Some(1));
+        assert_eq!(new_york.search_rank("america"), Some(3));
+    }
+
+    #[test]
+    fn test_update_offset() {
+        let mut timezone = TimezoneEntry {
+            code: "Asia/Kathmandu".to_string(),
+            parts: vec![],
+            country: None,
+            country_code: None,
+            utc_offset: 0,
+            dst: false,
+            rank: None,
+        };
+        let summer = Utc.with_ymd_and_hms(2024, 7, 1, 12, 0, 0).unwrap();
+        timezone.update_offset(&summer);
+        assert_eq!(timezone.utc_offset, 345);
+        assert!(!timezone.dst);
+
+        timezone.code = "Europe/Berlin".to_string();
+        timezone.update_offset(&summer);
+        assert_eq!(timezone.utc_offset, 120);
+        assert!(timezone.dst);
+
+        let winter = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        timezone.update_offset(&winter);
+        assert_eq!(timezone.utc_offset, 60);
+        assert!(!timezone.dst);
+    }
+
+    #[test]
+    fn test_suggest() {
+        let timezone = |code: &str, country: &str, rank: Option<u16>| TimezoneEntry {
+            code: code.to_string(),
+            parts: vec![],
+            country: None,
+            country_code: Some(country.to_string()),
+            utc_offset: 0,
+            dst: false,
+            rank,
+        };
+        let db = TimezonesDatabase {
+            timezones: vec![
+                timezone("Europe/Madrid", "ES", Some(1000)),
+                timezone("Atlantic/Canary", "ES", Some(0)),
+                timezone("Europe/Berlin", "DE", None),
+                timezone("America/New_York", "US", None),
+                timezone("America/Chicago", "US", None),
+            ],
+        };
+        assert_eq!(db.suggest("ES").unwrap().code, "Europe/Madrid");
+        assert_eq!(db.suggest("de").unwrap().code, "Europe/Berlin");
+        assert!(db.suggest("US").is_none());
+        assert!(db.suggest("FR").is_none());
+    }
+
+    #[test]
+    fn test_canonical_name() {
+        let timezone = |code: &str| TimezoneEntry {
+            code: code.to_string(),
+            parts: vec![],
+            country: None,
+            country_code: None,
+            utc_offset: 0,
+            dst: false,
+            rank: None,
+        };
+        let db = TimezonesDatabase {
+            timezones: vec![timezone("Asia/Kolkata"), timezone("Europe/Berlin")],
+        };
+        assert_eq!(db.canonical_name("Europe/Berlin"), Some("Europe/Berlin"));
+        assert_eq!(db.canonical_name("europe/berlin"), Some("Europe/Berlin"));
+        assert_eq!(db.canonical_name("Asia/Calcutta"), Some("Asia/Kolkata"));
+        assert_eq!(db.canonical_name("asia/calcutta"), Some("Asia/Kolkata"));
+        assert_eq!(db.canonical_name("US/Eastern"), None);
+        assert_eq!(db.canonical_name("Unknown/Unknown"), None);
+    }
+
+    #[test]
+    fn test_timezone_from_path() {
+        let timezone = |path: &str| timezone_from_path(Path::new(path));
+        assert_eq!(
+            timezone("/usr/share/zoneinfo/Europe/Berlin"),
+            Some("Europe/Berlin".to_string())
+        );
+        assert_eq!(
+            timezone("../usr/share/zoneinfo/posix/America/New_York"),
+            Some("America/New_York".to_string())
+        );
+        assert_eq!(timezone("/usr/share/zoneinfo/UTC"), Some("UTC".to_string()));
+        assert_eq!(timezone("/etc/localtime.bak"), None);
+    }
 }
@@ -0,0 +1,114 @@
+//! Availability of the external tools used to apply the keymap.
+//!
+//! Changing the keymap of the running system relies on `localectl` and `setxkbmap`. They might
+//! be missing in some environments (e.g., containers), so the service checks them at startup
+//! and refuses to change the UI keymap instead of failing with an obscure error.
+
+use std::{os::unix::fs::PermissionsExt, path::Path};
+
+use super::LocaleError;
+
+/// Path to the `localectl` binary.
+pub const LOCALECTL: &str = "/usr/bin/localectl";
+/// Path to the `setxkbmap` binary.
+pub const SETXKBMAP: &str = "/usr/bin/setxkbmap";
+
+/// Result of checking the external tools.
+#[derive(Clone, Debug, Default)]
+pub struct ToolsCheck {
+    /// Tools which are missing or not executable.
+    pub missing: Vec<String>,
+}
+
+impl ToolsCheck {
+    /// Checks whether `localectl` and `setxkbmap` are available.
+    pub fn run() -> Self {
+        Self::run_for(&[LOCALECTL, SETXKBMAP])
+    }
+
+    /// Checks whether the given tools are available.
+    ///
+    /// * `tools`: paths to the tools.
+    pub fn run_for(tools: &[&str]) -> Self {
+        let missing = tools
+            .iter()
+            .filter(|t| !is_executable(Path::new(t)))
+            .map(|t| t.to_string())
+            .collect();
+        Self { missing }
+    }
+
+    /// Whether all the tools are available.
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty()
+    }
+
+    /// Makes sure that the given tools are available.
+    ///
+    /// It returns a [LocaleError::MissingTools] error listing the missing ones.
+    ///
+    /// * `tools`: paths to the required tools.
+    pub fn require(&self, tools: &[&str]) -> Result<(), LocaleError> {
+        let missing: Vec<_> = tools
+            .iter()
+            .filter(|t| self.missing.iter().any(|m| m == *t))
+            .map(|t| t.to_string())
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(LocaleError::MissingTools(missing))
+        }
+    }
+}
+
+fn is_executable(path: &Path) -> bool {
+    path.metadata()
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_run_for() {
+        let dir = std::env::temp_dir().join(format!("agama-tools-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let executable = dir.join("executable");
+        let regular = dir.join("regular");
+        fs::write(&executable, "").unwrap();
+        fs::write(&regular, "").unwrap();
+        fs::set_permissions(&executable, fs::Permissions::from_mode(0o755)).unwrap();
+        fs::set_permissions(&regular, fs::Permissions::from_mode(0o644)).unwrap();
+        let missing = dir.join("missing");
+
+        let check = ToolsCheck::run_for(&[
+            executable.to_str().unwrap(),
+            regular.to_str().unwrap(),
+            missing.to_str().unwrap(),
+            dir.to_str().unwrap(),
+        ]);
+        assert!(!check.is_ok());
+        assert_eq!(
+            check.missing,
+            vec![
+                regular.to_str().unwrap(),
+                missing.to_str().unwrap(),
+                dir.to_str().unwrap()
+            ]
+        );
+
+        assert!(check.require(&[executable.to_str().unwrap()]).is_ok());
+        let error = check
+            .require(&[executable.to_str().unwrap(), missing.to_str().unwrap()])
+            .unwrap_err();
+        assert!(
+            matches!(error, LocaleError::MissingTools(tools) if tools == [missing.to_str().unwrap()])
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,26 +1,59 @@
 //! This module implements the web API for the localization module.
 
 use super::{
-    error::LocaleError, keyboard::Keymap, locale::LocaleEntry, timezone::TimezoneEntry, L10n,
+    collation::Collation,
+    error::{LocaleError, LocaleErrorBody},
+    helpers,
+    history::{ConfigHistory, FieldTimestamps, DEFAULT_HISTORY_DEPTH},
+    keyboard::{console_font, keymap_language, Keymap, KeymapMatch},
+    l10n::{display, x_display_available},
+    locale::{LanguageEntry, LocaleEntry},
+    metrics::L10nMetrics,
+    preview::{translation_preview, LOCALE_DIR},
+    recent::{RecentLocales, DEFAULT_RECENT_LOCALES},
+    timezone::{detect_system_timezone, TimezoneEntry, TimezonesDatabase},
+    tools::ToolsCheck,
+    L10n,
 };
 use crate::{
     error::Error,
-    web::{Event, EventsSender},
+    web::{
+        negotiation::{Format, Negotiated},
+        EventsSender,
+    },
 };
 use agama_lib::{
-    error::ServiceError, localization::model::LocaleConfig, localization::LocaleProxy,
+    error::ServiceError,
+    localization::{model::LocaleConfig, LocaleProxy},
     proxies::LocaleProxy as ManagerLocaleProxy,
 };
-use agama_locale_data::LocaleId;
+use agama_locale_data::{KeymapId, LocaleId};
 use axum::{
-    extract::State,
-    http::StatusCode,
-    response::IntoResponse,
-    routing::{get, patch},
+    extract::{Path, Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, patch, post},
     Json, Router,
 };
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    path::PathBuf,
+    sync::Arc,
+};
+use tokio::sync::{Mutex, RwLock};
+
+pub mod config;
+pub mod events;
+pub mod metrics;
+
+use config::{
+    config_schema, current_config, get_config, import_config, load_config, replace_config,
+    reset_config, selected_locales, set_config, undo_config, validate_config,
+};
 
 #[derive(Clone)]
 struct LocaleState<'a> {
@@ -28,6 +61,82 @@ struct LocaleState<'a> {
     proxy: LocaleProxy<'a>,
     manager_proxy: ManagerLocaleProxy<'a>,
     events: EventsSender,
+    /// X display to apply the UI keymap to.
+    display: String,
+    /// Whether to apply the UI keymap to the running system.
+    apply_ui_keymap: bool,
+    /// Whether to reject the configurations containing unknown attributes.
+    strict_config: bool,
+    /// File to persist the configuration to.
+    state_file: Option<PathBuf>,
+    /// Directory containing the web UI.
+    ui_dir: Option<PathBuf>,
+    /// Previous configurations, to undo the changes.
+    history: Arc<RwLock<ConfigHistory>>,
+    /// Time each configuration attribute was last changed.
+    timestamps: Arc<RwLock<FieldTimestamps>>,
+    /// Locales recently set through `PATCH /config`.
+    recent_locales: Arc<RwLock<RecentLocales>>,
+    /// Serializes the configuration updates.
+    updates: Arc<Mutex<()>>,
+    /// Translation previews indexed by locale (e.g., "pt_BR.UTF-8").
+    previews: Arc<RwLock<HashMap<String, BTreeMap<String, String>>>>,
+    /// External tools checked when the service started.
+    tools: ToolsCheck,
+    /// Error loading the localization data, if any.
+    load_error: Option<String>,
+    /// Usage counters.
+    metrics: Arc<L10nMetrics>,
+}
+
+/// Default file to persist the localization configuration across restarts.
+pub const DEFAULT_STATE_FILE: &str = "/run/agama/l10n.json";
+
+/// Options to set up the localization service.
+#[derive(Clone, Debug)]
+pub struct L10nServiceOptions {
+    /// X display to apply the UI keymap to (e.g., ":1").
+    pub display: String,
+    /// File to persist the configuration across restarts. If it is `None`, the configuration is
+    /// not persisted.
+    pub state_file: Option<PathBuf>,
+    /// Directory containing the web UI, used to find out the available translations.
+    pub ui_dir: Option<PathBuf>,
+    /// Number of configuration changes that can be undone.
+    pub history_depth: usize,
+    /// Number of locales to keep in the list of recently used ones.
+    pub recent_locales: usize,
+    /// Whether to enable the `/debug` endpoint.
+    pub debug: bool,
+    /// Whether to reject the configurations containing unknown attributes (e.g., "timzone")
+    /// instead of ignoring them. It is disabled by default, so the clients written for newer
+    /// versions of the API keep working.
+    pub strict_config: bool,
+    /// Whether to apply the UI keymap to the running system (using `localectl` and `setxkbmap`).
+    ///
+    /// When it is disabled, the UI keymap is still stored and the `KeymapChanged` event is still
+    /// emitted, but no command is run. It is meant for the deployments where the front-end manages
+    /// the keyboard by itself.
+    pub apply_ui_keymap: bool,
+    /// Keymap to install by default. If it is `None`, the keymap is the one suggested for the
+    /// default locale (e.g., "de" for "de_DE.UTF-8").
+    pub default_keymap: Option<KeymapId>,
+}
+
+impl Default for L10nServiceOptions {
+    fn default() -> Self {
+        Self {
+            display: display(),
+            state_file: None,
+            ui_dir: None,
+            history_depth: DEFAULT_HISTORY_DEPTH,
+            recent_locales: DEFAULT_RECENT_LOCALES,
+            debug: false,
+            strict_config: false,
+            apply_ui_keymap: true,
+            default_keymap: None,
+        }
+    }
 }
 
 /// Sets up and returns the axum service for the localization module.
@@ -36,166 +145,1233 @@ struct LocaleState<'a> {
 pub async fn l10n_service(
     dbus: zbus::Connection,
     events: EventsSender,
+) -> Result<Router, ServiceError> {
+    l10n_service_with_options(dbus, events, L10nServiceOptions::default()).await
+}
+
+/// Sets up and returns the axum service for the localization module using the given X display.
+///
+/// * `events`: channel to send the events to the main service.
+/// * `display`: X display to apply the UI keymap to (e.g., ":1").
+pub async fn l10n_service_with_display(
+    dbus: zbus::Connection,
+    events: EventsSender,
+    display: String,
+) -> Result<Router, ServiceError> {
+    let options = L10nServiceOptions {
+        display,
+        ..Default::default()
+    };
+    l10n_service_with_options(dbus, events, options).await
+}
+
+/// Sets up and returns the axum service for the localization module using the given options.
+///
+/// If a state file is given and it contains a valid configuration, the configuration is restored
+/// from it. Otherwise, it starts with the default configuration.
+///
+/// If the localization data cannot be loaded, the service starts anyway: `/health` reports the
+/// problem and the rest of the endpoints return a "503 Service Unavailable" error.
+///
+/// * `events`: channel to send the events to the main service.
+/// * `options`: service options.
+pub async fn l10n_service_with_options(
+    dbus: zbus::Connection,
+    events: EventsSender,
+    options: L10nServiceOptions,
 ) -> Result<Router, ServiceError> {
     let id = LocaleId::default();
-    let locale = L10n::new_with_locale(&id).unwrap();
+    let default_keymap = options.default_keymap.as_ref();
+    let (mut l10n, load_error) = load_l10n(&id, default_keymap);
+    if load_error.is_none() {
+        if let Some(config) = options.state_file.as_deref().and_then(load_config) {
+            if let Err(error) = l10n.restore_config(&config) {
+                log::warn!("Ignoring the saved localization configuration: {error}");
+                l10n = load_l10n(&id, default_keymap).0;
+            }
+        }
+    }
+    let tools = ToolsCheck::run();
+    if !options.apply_ui_keymap {
+        log::info!("The UI keymap is not applied to the system");
+    } else if !tools.is_ok() {
+        log::warn!(
+            "Missing tools, the UI keymap cannot be changed: {}",
+            tools.missing.join(", ")
+        );
+    }
+    let debug = options.debug;
     let proxy = LocaleProxy::new(&dbus).await?;
     let manager_proxy = ManagerLocaleProxy::new(&dbus).await?;
     let state = LocaleState {
-        locale: Arc::new(RwLock::new(locale)),
+        locale: Arc::new(RwLock::new(l10n)),
         proxy,
         manager_proxy,
         events,
+        display: options.display,
+        apply_ui_keymap: options.apply_ui_keymap,
+        strict_config: options.strict_config,
+        state_file: options.state_file,
+        ui_dir: options.ui_dir,
+        history: Arc::new(RwLock::new(ConfigHistory::new(options.history_depth))),
+        timestamps: Arc::new(RwLock::new(FieldTimestamps::default())),
+        recent_locales: Arc::new(RwLock::new(RecentLocales::new(options.recent_locales))),
+        updates: Arc::new(Mutex::new(())),
+        previews: Arc::new(RwLock::new(HashMap::new())),
+        tools,
+        load_error: load_error.clone(),
+        metrics: Arc::new(L10nMetrics::default()),
     };
+    let metrics = Arc::clone(&state.metrics);
 
-    let router = Router::new()
-        .route("/keymaps", get(keymaps))
+    let mut router = Router::new()
+        .route("/", get(summary))
+        .route("/keymaps", get(keymaps).post(add_keymap))
+        .route("/languages", get(languages))
+        .route("/keymaps/search", get(search_keymaps))
+        .route("/keymaps/suggested", get(suggested_keymap))
+        .route("/keymaps/:id", get(keymap))
+        .route("/keymaps/:id/console-font", get(keymap_console_font))
         .route("/locales", get(locales))
+        .route("/locales/default", get(default_locale))
+        .route("/locales/recent", get(recent_locales))
+        .route("/locales/:id", get(locale))
         .route("/timezones", get(timezones))
-        .route("/config", patch(set_config).get(get_config))
-        .with_state(state);
-    Ok(router)
+        .route("/timezones/detected", get(detected_timezone))
+        .route("/timezones/suggested", get(suggested_timezone))
+        .route("/ui-locales", get(ui_locales))
+        .route("/ui_locale/negotiate", get(negotiate_ui_locale))
+        .route("/ui_locale/preview", get(preview_ui_locale))
+        .route(
+            "/config",
+            patch(set_config).put(replace_config).get(get_config),
+        )
+        .route("/config/locales", get(selected_locales))
+        .route("/config/schema", get(config_schema))
+        .route("/config/validate", post(validate_config))
+        .route("/config/import", post(import_config))
+        .route("/config/reset", post(reset_config))
+        .route("/config/undo", post(undo_config))
+        .route("/events", get(events::events));
+    if let Some(error) = load_error {
+        router = router.route_layer(middleware::from_fn_with_state(error, data_unavailable));
+    }
+    router = router.route("/health", get(health));
+    if debug {
+        router = router.route("/debug", get(self::debug));
+    }
+    router = router
+        .route_layer(middleware::from_fn_with_state(
+            metrics,
+            metrics::count_requests,
+        ))
+        .route("/metrics", get(metrics::metrics));
+    Ok(router.with_state(state))
+}
+
+// Reads the localization databases, setting the default keymap if given. If they cannot be read,
+// it returns an instance with empty databases and the error.
+fn load_l10n(id: &LocaleId, default_keymap: Option<&KeymapId>) -> (L10n, Option<String>) {
+    match L10n::new_with_locale(id) {
+        Ok(mut l10n) => {
+            if let Some(keymap) = default_keymap {
+                if let Err(error) = l10n.set_default_keymap(keymap.clone()) {
+                    log::warn!("Ignoring the default keymap: {error}");
+                }
+            }
+            (l10n, None)
+        }
+        Err(error) => {
+            log::error!("Could not load the localization data: {error}");
+            (L10n::new_empty(id), Some(error.to_string()))
+        }
+    }
+}
+
+// Rejects the requests to the endpoints that need the localization data, which could not be
+// loaded.
+async fn data_unavailable(State(error): State<String>, _request: Request, _next: Next) -> Response {
+    Error::from(LocaleError::DataUnavailable(error)).into_response()
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+struct SummaryQuery {
+    /// Comma-separated list of parts to include ("locales", "keymaps", "timezones" and
+    /// "config"). All of them are included by default.
+    include: Option<String>,
 }
 
-#[utoipa::path(get, path = "/l10n/locales", responses(
-  (status = 200, description = "List of known locales", body = Vec<LocaleEntry>)
+impl SummaryQuery {
+    fn includes(&self, part: &str) -> bool {
+        self.include
+            .as_ref()
+            .map_or(true, |i| i.split(',').any(|p| p.trim() == part))
+    }
+}
+
+/// Localization data and configuration.
+///
+/// Only the parts requested through the `include` parameter are present.
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct L10nSummary {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    locales: Option<Vec<LocaleEntry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keymaps: Option<Vec<Keymap>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timezones: Option<Vec<TimezoneEntry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    config: Option<LocaleConfig>,
+}
+
+/// Returns the known locales, keymaps and timezones together with the current configuration.
+///
+/// It allows the clients to fetch all the localization data in a single request.
+#[utoipa::path(
+    get,
+    path = "",
+    context_path = "/api/l10n",
+    params(SummaryQuery),
+    responses(
+      (status = 200, description = "Localization data and configuration", body = L10nSummary)
+    )
+)]
+async fn summary(
+    State(state): State<LocaleState<'_>>,
+    Query(query): Query<SummaryQuery>,
+) -> Json<L10nSummary> {
+    let data = state.locale.read().await;
+    let summary = L10nSummary {
+        locales: query
+            .includes("locales")
+            .then(|| data.locale_entries().to_vec()),
+        keymaps: query
+            .includes("keymaps")
+            .then(|| data.keymap_entries().to_vec()),
+        timezones: query
+            .includes("timezones")
+            .then(|| data.timezone_entries(None)),
+        config: query.includes("config").then(|| current_config(&data)),
+    };
+    Json(summary)
+}
+
+/// Returns the languages of the known locales.
+///
+/// Each language appears only once, even if it is used in several territories. The list is
+/// sorted by the endonym, unless a `collation` is given. In that case, it is sorted by the
+/// localized name using the rules of that language.
+#[utoipa::path(get, path = "/l10n/languages", params(CollationQuery), responses(
+  (status = 200, description = "List of known languages", body = Vec<LanguageEntry>)
 ))]
-async fn locales(State(state): State<LocaleState<'_>>) -> Json<Vec<LocaleEntry>> {
+async fn languages(
+    State(state): State<LocaleState<'_>>,
+    format: Format,
+    Query(sort): Query<CollationQuery>,
+) -> Negotiated<Vec<LanguageEntry>> {
     let data = state.locale.read().await;
-    let locales = data.locales_db.entries().to_vec();
-    Json(locales)
+    let mut languages = data.language_entries();
+    if let Some(collation) = sort.collation(&data) {
+        collation.sort_by(&mut languages, |l| l.name.clone());
+    }
+    Negotiated(format, languages)
+}
+
+/// Criteria to group the locales by.
+#[derive(Clone, Copy, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+enum LocalesGroupBy {
+    /// Groups the locales by language code.
+    Language,
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+struct LocalesQuery {
+    /// Language code to filter by (e.g., "de").
+    language: Option<String>,
+    /// Text to search in the localized language and territory names. The search is case and
+    /// accent insensitive.
+    search: Option<String>,
+    /// When set to "language", returns a map from language code to the list of locales sharing
+    /// that language, sorted by the localized territory name.
+    #[param(inline)]
+    group_by: Option<LocalesGroupBy>,
+}
+
+/// Returns the known locales.
+///
+/// When a `collation` is given, they are sorted by the localized language and territory names
+/// using the rules of that language.
+#[utoipa::path(get, path = "/l10n/locales", params(LocalesQuery, CollationQuery), responses(
+  (status = 200, description = "List of known locales (or a map from language code to locales if `group_by=language` is given)", body = Vec<LocaleEntry>),
+  (status = 400, description = "The query parameters are not valid")
+))]
+async fn locales(
+    State(state): State<LocaleState<'_>>,
+    format: Format,
+    Query(query): Query<LocalesQuery>,
+    Query(sort): Query<CollationQuery>,
+) -> Response {
+    let data = state.locale.read().await;
+    let collation = sort.collation(&data);
+    let mut locales: Vec<_> = data
+        .locale_entries()
+        .iter()
+        .filter(|e| {
+            query
+                .language
+                .as_ref()
+                .map_or(true, |l| &e.id.language == l)
+        })
+        .filter(|e| query.search.as_ref().map_or(true, |s| e.matches(s)))
+        .cloned()
+        .collect();
+
+    match query.group_by {
+        Some(LocalesGroupBy::Language) => {
+            Negotiated(format, group_by_language(locales, collation)).into_response()
+        }
+        None => {
+            if let Some(collation) = collation {
+                collation.sort_by(&mut locales, |l| format!("{} {}", l.language, l.territory));
+            }
+            Negotiated(format, locales).into_response()
+        }
+    }
+}
+
+// Groups the locales by language code, sorting each group by the localized territory name
+// (using the given collation, if any).
+fn group_by_language(
+    locales: Vec<LocaleEntry>,
+    collation: Option<Collation>,
+) -> BTreeMap<String, Vec<LocaleEntry>> {
+    let mut groups: BTreeMap<String, Vec<LocaleEntry>> = BTreeMap::new();
+    for locale in locales {
+        groups
+            .entry(locale.id.language.clone())
+            .or_default()
+            .push(locale);
+    }
+    for group in groups.values_mut() {
+        match collation {
+            Some(collation) => collation.sort_by(group, |l| l.territory.clone()),
+            None => group.sort_by_cached_key(|l| helpers::normalize_text(&l.territory)),
+        }
+    }
+    groups
 }
 
+/// Maximum number of items per page.
+const MAX_PAGE_SIZE: usize = 500;
+
+#[derive(Deserialize, utoipa::IntoParams)]
+struct PaginationQuery {
+    /// Number of items to skip.
+    offset: Option<usize>,
+    /// Maximum number of items to return (up to 500).
+    limit: Option<usize>,
+}
+
+/// Page of a list of items.
+#[derive(Serialize, utoipa::ToSchema)]
+#[aliases(KeymapsPage = Page<Keymap>, TimezonesPage = Page<TimezoneEntry>)]
+pub struct Page<T> {
+    /// Items in the page.
+    items: Vec<T>,
+    /// Total number of items.
+    total: usize,
+    /// Number of skipped items.
+    offset: usize,
+    /// Maximum number of items in the page.
+    limit: usize,
+}
+
+impl<T: Clone> Page<T> {
+    pub fn new(entries: &[T], offset: usize, limit: usize) -> Self {
+        let limit = limit.min(MAX_PAGE_SIZE);
+        Self {
+            items: entries.iter().skip(offset).take(limit).cloned().collect(),
+            total: entries.len(),
+            offset,
+            limit,
+        }
+    }
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+struct CollationQuery {
+    /// Language whose sorting rules are used to sort the results (e.g., "sv" or "sv_SE"). For
+    /// instance, Swedish sorts "ö" after "z". If the language is unknown, the default order is
+    /// kept.
+    collation: Option<String>,
+}
+
+impl CollationQuery {
+    // Returns the collation for the requested language, if it is known.
+    fn collation(&self, data: &L10n) -> Option<Collation> {
+        let collation = self.collation.as_deref()?;
+        let language = helpers::parse_locale(collation)
+            .map(|l| l.language)
+            .unwrap_or_else(|_| collation.to_lowercase());
+        let known = data
+            .locales_db
+            .entries()
+            .iter()
+            .any(|l| l.id.language == language);
+        if !known {
+            tracing::debug!(collation, "Unknown collation, keeping the default order");
+            return None;
+        }
+        Some(Collation::for_language(&language))
+    }
+}
+
+/// Full list of keymaps or, if some pagination parameter is given, a page of it.
+//
+// It only describes the response in the documentation, as `paginate` builds the response.
+#[derive(Serialize, utoipa::ToSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+pub enum KeymapsList {
+    All(Vec<Keymap>),
+    Page(KeymapsPage),
+}
+
+/// Full list of timezones or, if some pagination parameter is given, a page of it.
+//
+// It only describes the response in the documentation, as `paginate` builds the response.
+#[derive(Serialize, utoipa::ToSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+pub enum TimezonesList {
+    All(Vec<TimezoneEntry>),
+    Page(TimezonesPage),
+}
+
+// Returns the full list of entries unless some pagination parameter is given. In that case, it
+// returns the corresponding page.
+fn paginate<T: Clone + Serialize>(
+    entries: &[T],
+    query: &PaginationQuery,
+    format: Format,
+) -> Response {
+    if query.offset.is_none() && query.limit.is_none() {
+        return Negotiated(format, entries.to_vec()).into_response();
+    }
+
+    let page = Page::new(
+        entries,
+        query.offset.unwrap_or(0),
+        query.limit.unwrap_or(MAX_PAGE_SIZE),
+    );
+    Negotiated(format, page).into_response()
+}
+
+/// Readiness of the localization service.
+#[derive(Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct L10nHealth {
+    /// Number of known locales.
+    locales: usize,
+    /// Number of known keymaps.
+    keymaps: usize,
+    /// Number of known timezones.
+    timezones: usize,
+    /// Databases which could not be loaded (they are empty).
+    failed: Vec<&'static str>,
+    /// External tools which are missing. The UI keymap cannot be changed without them, but
+    /// the rest of the settings can.
+    missing_tools: Vec<String>,
+    /// Error loading the localization data, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Checks whether the localization databases are loaded.
+///
+/// It reports the number of entries in each database instead of the full lists. The missing
+/// tools do not make the service unavailable, as only the UI keymap depends on them.
 #[utoipa::path(
     get,
-    path = "/timezones",
+    path = "/health",
     context_path = "/api/l10n",
     responses(
-      (status = 200, description = "List of known timezones", body = Vec<TimezoneEntry>)
-  )
+      (status = 200, description = "The service is ready", body = L10nHealth),
+      (status = 503, description = "Some database could not be loaded", body = L10nHealth)
+    )
 )]
-async fn timezones(State(state): State<LocaleState<'_>>) -> Json<Vec<TimezoneEntry>> {
+async fn health(State(state): State<LocaleState<'_>>) -> Response {
     let data = state.locale.read().await;
-    let timezones = data.timezones_db.entries().to_vec();
-    Json(timezones)
+    let health = L10nHealth {
+        locales: data.locales_db.entries().len(),
+        keymaps: data.keymaps_db.entries().len(),
+        timezones: data.timezones_db.entries().len(),
+        failed: vec![],
+        missing_tools: state.tools.missing.clone(),
+        error: state.load_error.clone(),
+    };
+    let failed = [
+        ("locales", health.locales),
+        ("keymaps", health.keymaps),
+        ("timezones", health.timezones),
+    ]
+    .into_iter()
+    .filter_map(|(name, count)| (count == 0).then_some(name))
+    .collect();
+    let health = L10nHealth { failed, ..health };
+
+    let status = if health.failed.is_empty() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(health)).into_response()
+}
+
+/// Diagnostic information about the localization service.
+#[derive(Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct L10nDebugInfo {
+    /// Current configuration.
+    config: LocaleConfig,
+    /// Primary locale of the target system.
+    primary_locale: Option<String>,
+    /// UI keymap detected when the service started.
+    default_ui_keymap: String,
+    /// Languages whose translations are loaded.
+    translations: Vec<String>,
+    /// Number of known locales.
+    locales: usize,
+    /// Number of known keymaps.
+    keymaps: usize,
+    /// Number of known timezones.
+    timezones: usize,
+    /// X display to apply the UI keymap to.
+    display: String,
+    /// Whether there is an X server running on `display`.
+    x_display_available: bool,
+    /// External tools which are missing.
+    missing_tools: Vec<String>,
+    /// Number of changes that can be undone.
+    history: usize,
+    /// Version of the IANA timezone database (e.g., "2024a").
+    tzdb_version: &'static str,
+    /// Data files the databases are built from.
+    data_files: Vec<DataFileInfo>,
+}
+
+/// Details of a localization data file.
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct DataFileInfo {
+    /// Path to the file.
+    path: &'static str,
+    /// Last modification time (RFC 3339). It is `None` if the file does not exist.
+    modified: Option<String>,
 }
 
+/// Returns a dump of the localization service state for debugging purposes.
+///
+/// It includes the configuration, the size of the databases, the loaded translations, the X
+/// display and the versions of the underlying data. The langtable data does not carry a version,
+/// so the modification time of each file is reported instead.
+///
+/// It is only available when the `debug` option of the web server is enabled.
 #[utoipa::path(
     get,
-    path = "/keymaps",
+    path = "/debug",
     context_path = "/api/l10n",
     responses(
-      (status = 200, description = "List of known keymaps", body = Vec<Keymap>)
+      (status = 200, description = "Localization service state", body = L10nDebugInfo),
+      (status = 404, description = "The debugging endpoints are disabled")
     )
 )]
-async fn keymaps(State(state): State<LocaleState<'_>>) -> Json<Vec<Keymap>> {
+async fn debug(State(state): State<LocaleState<'_>>) -> Json<L10nDebugInfo> {
+    let history = state.history.read().await.len();
     let data = state.locale.read().await;
-    let keymaps = data.keymaps_db.entries().to_vec();
-    Json(keymaps)
+    let data_files = agama_locale_data::DATA_FILES
+        .into_iter()
+        .map(|path| DataFileInfo {
+            path,
+            modified: fs::metadata(path)
+                .and_then(|m| m.modified())
+                .ok()
+                .map(|t| chrono::DateTime::<Utc>::from(t).to_rfc3339()),
+        })
+        .collect();
+
+    Json(L10nDebugInfo {
+        config: current_config(&data),
+        primary_locale: data.primary_locale().map(str::to_string),
+        default_ui_keymap: data.default_ui_keymap.to_string(),
+        translations: data.translated_languages(),
+        locales: data.locales_db.entries().len(),
+        keymaps: data.keymaps_db.entries().len(),
+        timezones: data.timezones_db.entries().len(),
+        display: state.display.clone(),
+        x_display_available: x_display_available(&state.display),
+        missing_tools: state.tools.missing.clone(),
+        history,
+        tzdb_version: agama_locale_data::TZDB_VERSION,
+        data_files,
+    })
 }
 
-// TODO: update all or nothing
-// TODO: send only the attributes that have changed
+/// Returns the locale with the given ID.
+///
+/// When the ID is malformed, the error message includes the parsing problem. Otherwise, it
+/// reports the locale as unknown.
 #[utoipa::path(
-    patch,
-    path = "/config",
+    get,
+    path = "/locales/:id",
     context_path = "/api/l10n",
-    operation_id = "set_l10n_config",
+    params(("id" = String, Path, description = "Locale ID (e.g., \"es_ES.UTF-8\")")),
     responses(
-      (status = 204, description = "Set the locale configuration", body = LocaleConfig)
+      (status = 200, description = "Locale", body = LocaleEntry),
+      (status = 404, description = "The locale ID is not valid or the locale is unknown")
     )
 )]
-async fn set_config(
+async fn locale(
     State(state): State<LocaleState<'_>>,
-    Json(value): Json<LocaleConfig>,
-) -> Result<impl IntoResponse, Error> {
-    let mut data = state.locale.write().await;
-    let mut changes = LocaleConfig::default();
+    Path(id): Path<String>,
+) -> Result<Json<LocaleEntry>, Response> {
+    let data = state.locale.read().await;
+    let locale = LocaleId::try_from(id.as_str())
+        .map_err(LocaleError::InvalidLocale)
+        .and_then(|locale_id| {
+            data.locales_db
+                .find(&locale_id)
+                .ok_or(LocaleError::UnknownLocale(id))
+        });
 
-    if let Some(locales) = &value.locales {
-        data.set_locales(locales)?;
-        changes.locales.clone_from(&value.locales);
+    match locale {
+        Ok(locale) => Ok(Json(locale.clone())),
+        Err(error) => Err(not_found(error)),
     }
+}
 
-    if let Some(timezone) = &value.timezone {
-        data.set_timezone(timezone)?;
-        changes.timezone.clone_from(&value.timezone);
+/// Returns the locale to install by default.
+#[utoipa::path(
+    get,
+    path = "/locales/default",
+    context_path = "/api/l10n",
+    responses(
+      (status = 200, description = "Default locale", body = LocaleEntry),
+      (status = 404, description = "There are no known locales")
+    )
+)]
+async fn default_locale(State(state): State<LocaleState<'_>>) -> Response {
+    let data = state.locale.read().await;
+    match data.default_locale_entry() {
+        Some(locale) => Json(locale.clone()).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
     }
+}
+
+/// Returns the locales recently set through `PATCH /config`, the most recent one first.
+///
+/// Each locale is listed only once and the list is capped (see
+/// [L10nServiceOptions::recent_locales]). It is kept in memory, so it is lost when the service
+/// restarts.
+#[utoipa::path(
+    get,
+    path = "/locales/recent",
+    context_path = "/api/l10n",
+    responses(
+      (status = 200, description = "Recently used locales", body = Vec<LocaleEntry>)
+    )
+)]
+async fn recent_locales(State(state): State<LocaleState<'_>>) -> Json<Vec<LocaleEntry>> {
+    let recent = state.recent_locales.read().await.locales();
+    let data = state.locale.read().await;
+    let locales = recent
+        .iter()
+        .filter_map(|id| LocaleId::try_from(id.as_str()).ok())
+        .filter_map(|id| data.locales_db.find(&id).cloned())
+        .collect();
+    Json(locales)
+}
+
+/// Returns the locales the installer user interface is translated to.
+///
+/// Unlike `/locales`, which lists the locales that can be installed in the target system, it
+/// only includes the locales that can be used as `uiLocale` without showing untranslated texts.
+#[utoipa::path(
+    get,
+    path = "/ui-locales",
+    context_path = "/api/l10n",
+    responses(
+      (status = 200, description = "Locales the user interface is translated to", body = Vec<LocaleEntry>)
+    )
+)]
+async fn ui_locales(State(state): State<LocaleState<'_>>) -> Json<Vec<LocaleEntry>> {
+    let translations = state
+        .ui_dir
+        .as_deref()
+        .map(helpers::ui_translations)
+        .unwrap_or_default();
+    let data = state.locale.read().await;
+    let locales = data
+        .locales_db
+        .entries()
+        .iter()
+        .filter(|l| helpers::has_ui_translation(&l.id, &translations))
+        .cloned()
+        .collect();
+    Json(locales)
+}
 
-    if let Some(keymap_id) = &value.keymap {
-        let keymap_id = keymap_id.parse().map_err(LocaleError::InvalidKeymap)?;
-        data.set_keymap(keymap_id)?;
-        changes.keymap.clone_from(&value.keymap);
+#[derive(Deserialize, utoipa::IntoParams)]
+struct PreviewQuery {
+    /// Locale to preview (e.g., "fr_FR.UTF-8").
+    locale: String,
+}
+
+/// Preview of the translations for a UI locale.
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct TranslationPreview {
+    /// Previewed locale (e.g., "fr_FR.UTF-8").
+    locale: String,
+    /// Translated strings indexed by a fixed set of keys (e.g., "keymap.de").
+    strings: BTreeMap<String, String>,
+}
+
+/// Returns a sample of strings translated to the given locale.
+///
+/// The strings (the descriptions of some keymaps) are translated using the gettext catalog of the
+/// locale. It does not change the UI locale. The translations are cached, so previewing a locale
+/// again is fast.
+#[utoipa::path(
+    get,
+    path = "/ui_locale/preview",
+    context_path = "/api/l10n",
+    params(PreviewQuery),
+    responses(
+      (status = 200, description = "Translated strings", body = TranslationPreview),
+      (status = 400, description = "The locale is not valid")
+    )
+)]
+async fn preview_ui_locale(
+    State(state): State<LocaleState<'_>>,
+    Query(query): Query<PreviewQuery>,
+) -> Result<Json<TranslationPreview>, Error> {
+    let locale = helpers::parse_locale(&query.locale).map_err(LocaleError::from)?;
+    let key = locale.to_string();
+    let cached = state.previews.read().await.get(&key).cloned();
+    let strings = match cached {
+        Some(strings) => strings,
+        None => {
+            let id = locale.clone();
+            let strings = tokio::task::spawn_blocking(move || {
+                translation_preview(std::path::Path::new(LOCALE_DIR), &id)
+            })
+            .await
+            .map_err(|e| Error::Anyhow(e.to_string()))?;
+            state.previews.write().await.insert(key, strings.clone());
+            strings
+        }
+    };
+
+    Ok(Json(TranslationPreview {
+        locale: locale.to_string(),
+        strings,
+    }))
+}
+
+/// Result of negotiating the UI locale.
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct LocaleNegotiation {
+    /// Best matching locale (e.g., "es_ES.UTF-8"), if any.
+    locale: Option<String>,
+    /// Matching locales sorted by preference.
+    candidates: Vec<String>,
+}
+
+/// Negotiates the UI locale according to the `Accept-Language` header.
+///
+/// The language ranges are sorted by their quality value and matched against the known locales
+/// using the RFC 4647 basic filtering.
+#[utoipa::path(
+    get,
+    path = "/ui_locale/negotiate",
+    context_path = "/api/l10n",
+    responses(
+      (status = 200, description = "Best matching locale and the list of candidates", body = LocaleNegotiation)
+    )
+)]
+async fn negotiate_ui_locale(
+    State(state): State<LocaleState<'_>>,
+    headers: HeaderMap,
+) -> Json<LocaleNegotiation> {
+    let ranges = headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|h| h.to_str().ok())
+        .map(helpers::parse_accept_language)
+        .unwrap_or_default();
+
+    let data = state.locale.read().await;
+    let candidates: Vec<_> = data
+        .locales_db
+        .negotiate(&ranges)
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+    Json(LocaleNegotiation {
+        locale: candidates.first().cloned(),
+        candidates,
+    })
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+struct TimezonesQuery {
+    /// ISO 3166 alpha-2 code of the country to filter by (e.g., "ES").
+    country: Option<String>,
+    /// Locale to translate the names to (e.g., "fr_FR"). It defaults to the UI locale.
+    locale: Option<String>,
+    /// Text to search for in the city and the localized names (e.g., "madrid"). The search is
+    /// case and accent insensitive and the results are sorted by relevance.
+    search: Option<String>,
+}
+
+/// Returns the known timezones.
+///
+/// When `search` is given, the timezones whose city (e.g., "Madrid" for "Europe/Madrid") matches
+/// the text come first: the exact matches, then the cities starting with the text and then the
+/// ones containing it. The timezones whose localized region or country contain the text come
+/// last. Within each group, they are sorted by code.
+///
+/// Otherwise, when a `collation` is given, they are sorted by the localized names using the rules
+/// of that language.
+#[utoipa::path(
+    get,
+    path = "/timezones",
+    context_path = "/api/l10n",
+    params(TimezonesQuery, CollationQuery, PaginationQuery),
+    responses(
+      (status = 200, description = "List of known timezones (or a page if `offset` or `limit` are given)", body = TimezonesList),
+      (status = 400, description = "The pagination parameters or the locale are not valid")
+  )
+)]
+async fn timezones(
+    State(state): State<LocaleState<'_>>,
+    format: Format,
+    Query(filter): Query<TimezonesQuery>,
+    Query(sort): Query<CollationQuery>,
+    Query(query): Query<PaginationQuery>,
+) -> Result<Response, Error> {
+    let country = filter.country.as_deref();
+    let search = filter.search.as_deref().filter(|s| !s.trim().is_empty());
+    let language = match &filter.locale {
+        Some(locale) => Some(
+            helpers::parse_locale(locale)
+                .map_err(LocaleError::from)?
+                .language,
+        ),
+        None => None,
+    };
+
+    let collation = sort.collation(&*state.locale.read().await);
+    {
+        let data = state.locale.read().await;
+        let db = match &language {
+            Some(language) => data.timezones_for(language),
+            None => Some(&data.timezones_db),
+        };
+        if let Some(db) = db {
+            let timezones = search_timezones(db.entries_with_offset(country), search, collation);
+            return Ok(paginate(&timezones, &query, format));
+        }
     }
 
-    if let Some(ui_locale) = &value.ui_locale {
-        let locale: LocaleId = ui_locale
-            .as_str()
-            .try_into()
-            .map_err(|_e| LocaleError::UnknownLocale(ui_locale.to_string()))?;
-        data.translate(&locale)?;
-        let locale_string = locale.to_string();
-        state.manager_proxy.set_locale(&locale_string).await?;
-        changes.ui_locale = Some(locale_string);
+    // the translations are not available, so read them without touching the shared state
+    let language = language.unwrap_or_default();
+    let db = tokio::task::spawn_blocking(move || {
+        let mut db = TimezonesDatabase::new();
+        db.read(&language).map(|_| db)
+    })
+    .await
+    .map_err(|e| Error::Anyhow(e.to_string()))??;
+    let timezones = search_timezones(db.entries_with_offset(country), search, collation);
+    Ok(paginate(&timezones, &query, format))
+}
 
-        _ = state.events.send(Event::LocaleChanged {
-            locale: locale.to_string(),
-        });
+// Keeps the timezones matching the search text, sorted by relevance and code (see
+// [TimezoneEntry::search_rank]). Without a search text, it sorts the timezones using the given
+// collation or, if there is none, it returns them untouched.
+fn search_timezones(
+    mut timezones: Vec<TimezoneEntry>,
+    search: Option<&str>,
+    collation: Option<Collation>,
+) -> Vec<TimezoneEntry> {
+    let Some(search) = search else {
+        if let Some(collation) = collation {
+            collation.sort_by(&mut timezones, |t| t.parts.join("/"));
+        }
+        return timezones;
+    };
+    let mut ranked: Vec<_> = timezones
+        .into_iter()
+        .filter_map(|t| t.search_rank(search).map(|rank| (rank, t)))
+        .collect();
+    ranked.sort_by(|(a_rank, a), (b_rank, b)| a_rank.cmp(b_rank).then_with(|| a.code.cmp(&b.code)));
+    ranked.into_iter().map(|(_, t)| t).collect()
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+struct SuggestedTimezoneQuery {
+    /// ISO 3166 alpha-2 code of the country (e.g., "ES").
+    country: Option<String>,
+    /// Locale whose territory is used when no country is given (e.g., "es_ES").
+    locale: Option<String>,
+}
+
+/// Returns the timezone configured in the running system.
+///
+/// It is meant to pre-select a timezone, so it does not change anything. Deprecated names are
+/// resolved to the current ones (e.g., "Asia/Calcutta" to "Asia/Kolkata").
+#[utoipa::path(
+    get,
+    path = "/timezones/detected",
+    context_path = "/api/l10n",
+    responses(
+      (status = 200, description = "Timezone of the running system", body = TimezoneEntry),
+      (status = 204, description = "The timezone could not be determined or it is unknown")
+    )
+)]
+async fn detected_timezone(State(state): State<LocaleState<'_>>) -> Response {
+    let Some(timezone) = detect_system_timezone() else {
+        return StatusCode::NO_CONTENT.into_response();
+    };
+
+    let data = state.locale.read().await;
+    let entry = data
+        .timezones_db
+        .canonical_name(&timezone)
+        .and_then(|name| data.timezones_db.entries().iter().find(|t| t.code == name));
+    match entry {
+        Some(entry) => {
+            let mut entry = entry.clone();
+            entry.update_offset(&Utc::now());
+            Json(entry).into_response()
+        }
+        None => StatusCode::NO_CONTENT.into_response(),
     }
+}
+
+/// Returns the primary timezone of a country.
+///
+/// When the country has a single timezone, it is the primary one. Otherwise, it is the
+/// timezone with the highest rank in the langtable data. If several timezones share the
+/// highest rank, there is no suggestion.
+#[utoipa::path(
+    get,
+    path = "/timezones/suggested",
+    context_path = "/api/l10n",
+    params(SuggestedTimezoneQuery),
+    responses(
+      (status = 200, description = "Suggested timezone", body = TimezoneEntry),
+      (status = 204, description = "There is no clear primary timezone for the country"),
+      (status = 400, description = "Neither a country nor a valid locale were given")
+    )
+)]
+async fn suggested_timezone(
+    State(state): State<LocaleState<'_>>,
+    Query(query): Query<SuggestedTimezoneQuery>,
+) -> Result<Response, Error> {
+    let country = match (query.country, query.locale) {
+        (Some(country), _) => country,
+        (None, Some(locale)) => {
+            LocaleId::try_from(locale.as_str())
+                .map_err(LocaleError::InvalidLocale)?
+                .territory
+        }
+        (None, None) => return Err(LocaleError::MissingField("country".to_string()).into()),
+    };
+
+    let data = state.locale.read().await;
+    let response = match data.timezones_db.suggest(&country) {
+        Some(timezone) => {
+            let mut timezone = timezone.clone();
+            timezone.update_offset(&Utc::now());
+            Json(timezone).into_response()
+        }
+        None => StatusCode::NO_CONTENT.into_response(),
+    };
+    Ok(response)
+}
+
+/// Criteria to group the keymaps by.
+#[derive(Clone, Copy, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+enum KeymapsGroupBy {
+    /// Groups the keymaps by the language they are meant for.
+    Language,
+}
+
+/// Group of the keymaps whose language cannot be derived.
+const OTHER_KEYMAPS_GROUP: &str = "Other";
 
-    if let Some(ui_keymap) = &value.ui_keymap {
-        let ui_keymap = ui_keymap.parse().map_err(LocaleError::InvalidKeymap)?;
-        data.set_ui_keymap(ui_keymap)?;
+#[derive(Deserialize, utoipa::IntoParams)]
+struct KeymapsQuery {
+    /// Keyboard layout to filter by (e.g., "us").
+    layout: Option<String>,
+    /// Keyboard layout variant to filter by (e.g., "dvorak").
+    variant: Option<String>,
+    /// When set to "language", returns a map from language code to the list of keymaps for that
+    /// language, sorted by description. The keymaps whose language cannot be derived are grouped
+    /// under "Other". The pagination parameters are ignored.
+    #[param(inline)]
+    group_by: Option<KeymapsGroupBy>,
+}
+
+/// Returns the known keymaps.
+///
+/// When a `collation` is given, they are sorted by description using the rules of that language.
+#[utoipa::path(
+    get,
+    path = "/keymaps",
+    context_path = "/api/l10n",
+    params(KeymapsQuery, CollationQuery, PaginationQuery),
+    responses(
+      (status = 200, description = "List of known keymaps (or a page if `offset` or `limit` are given, or a map from language code to keymaps if `group_by=language` is given)", body = KeymapsList),
+      (status = 400, description = "The pagination parameters are not valid")
+    )
+)]
+async fn keymaps(
+    State(state): State<LocaleState<'_>>,
+    format: Format,
+    Query(filter): Query<KeymapsQuery>,
+    Query(sort): Query<CollationQuery>,
+    Query(query): Query<PaginationQuery>,
+) -> Response {
+    let data = state.locale.read().await;
+    let mut keymaps: Vec<_> = data
+        .keymap_entries()
+        .iter()
+        .filter(|k| k.matches(filter.layout.as_deref(), filter.variant.as_deref()))
+        .cloned()
+        .collect();
+    let collation = sort.collation(&data);
+    if let Some(KeymapsGroupBy::Language) = filter.group_by {
+        let languages: HashSet<String> = data
+            .language_entries()
+            .into_iter()
+            .map(|l| l.code)
+            .collect();
+        let groups = group_keymaps_by_language(keymaps, &languages, collation);
+        return Negotiated(format, groups).into_response();
     }
+    if let Some(collation) = collation {
+        collation.sort_by(&mut keymaps, |k| k.description().to_string());
+    }
+    paginate(&keymaps, &query, format)
+}
 
-    if let Err(e) = update_dbus(&state.proxy, &changes).await {
-        log::warn!("Could not synchronize settings in the localization D-Bus service: {e}");
+// Groups the keymaps by language code (see [keymap_language]), sorting each group by description
+// (using the given collation, if any).
+fn group_keymaps_by_language(
+    keymaps: Vec<Keymap>,
+    languages: &HashSet<String>,
+    collation: Option<Collation>,
+) -> BTreeMap<String, Vec<Keymap>> {
+    let mut groups: BTreeMap<String, Vec<Keymap>> = BTreeMap::new();
+    for keymap in keymaps {
+        let language = keymap_language(&keymap.id, |l| languages.contains(l))
+            .unwrap_or_else(|| OTHER_KEYMAPS_GROUP.to_string());
+        groups.entry(language).or_default().push(keymap);
     }
-    _ = state.events.send(Event::L10nConfigChanged(changes));
+    for group in groups.values_mut() {
+        match collation {
+            Some(collation) => collation.sort_by(group, |k| k.description().to_string()),
+            None => group.sort_by_cached_key(|k| helpers::normalize_text(k.description())),
+        }
+    }
+    groups
+}
+
+/// Default number of results of a keymaps search.
+const DEFAULT_SEARCH_LIMIT: usize = 10;
+/// Maximum number of results of a keymaps search.
+const MAX_SEARCH_LIMIT: usize = 50;
 
-    Ok(StatusCode::NO_CONTENT)
+#[derive(Deserialize, utoipa::IntoParams)]
+struct KeymapsSearchQuery {
+    /// Text to search for (e.g., "german quertz").
+    q: String,
+    /// Maximum number of results (10 by default, up to 50).
+    limit: Option<usize>,
 }
 
+/// Searches for keymaps, tolerating typos.
+///
+/// The words of the query are compared with the keymap descriptions and IDs, allowing up to two
+/// typos per word (one for each three characters). The results are sorted by the number of typos.
 #[utoipa::path(
     get,
-    path = "/config",
+    path = "/keymaps/search",
     context_path = "/api/l10n",
-    operation_id = "get_l10n_config",
+    params(KeymapsSearchQuery),
     responses(
-        (status = 200, description = "Localization configuration", body = LocaleConfig)
+      (status = 200, description = "Matching keymaps, best matches first", body = Vec<KeymapMatch>),
+      (status = 400, description = "The query parameters are not valid")
     )
 )]
-async fn get_config(State(state): State<LocaleState<'_>>) -> Json<LocaleConfig> {
+async fn search_keymaps(
+    State(state): State<LocaleState<'_>>,
+    Query(query): Query<KeymapsSearchQuery>,
+) -> Json<Vec<KeymapMatch>> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .min(MAX_SEARCH_LIMIT);
     let data = state.locale.read().await;
-    Json(LocaleConfig {
-        locales: Some(data.locales.clone()),
-        keymap: Some(data.keymap.to_string()),
-        timezone: Some(data.timezone.to_string()),
-        ui_locale: Some(data.ui_locale.to_string()),
-        ui_keymap: Some(data.ui_keymap.to_string()),
-    })
+    Json(data.search_keymaps(&query.q, limit))
+}
+
+/// Definition of a custom keymap.
+#[derive(Deserialize, utoipa::ToSchema)]
+pub struct CustomKeymap {
+    /// Keymap ID (e.g., "oem" or "oem(special)").
+    id: String,
+    /// Keymap description (e.g., "OEM (Special)").
+    description: String,
 }
 
-pub async fn update_dbus(
-    client: &LocaleProxy<'_>,
-    config: &LocaleConfig,
-) -> Result<(), ServiceError> {
-    if let Some(locales) = &config.locales {
-        let locales: Vec<_> = locales.iter().map(|l| l.as_ref()).collect();
-        client.set_locales(&locales).await?;
+/// Registers a keymap which is not included in the system database (e.g., a custom XKB layout).
+///
+/// Once registered, it can be selected as any other keymap. It is kept only until the service is
+/// restarted.
+#[utoipa::path(
+    post,
+    path = "/keymaps",
+    context_path = "/api/l10n",
+    request_body = CustomKeymap,
+    responses(
+      (status = 201, description = "The keymap was registered", body = Keymap),
+      (status = 400, description = "The keymap ID is not valid"),
+      (status = 409, description = "The keymap already exists")
+    )
+)]
+async fn add_keymap(
+    State(state): State<LocaleState<'_>>,
+    Json(custom): Json<CustomKeymap>,
+) -> Result<impl IntoResponse, Error> {
+    let id: KeymapId = custom.id.parse().map_err(LocaleError::InvalidKeymap)?;
+    let keymap = Keymap::new(id.clone(), &custom.description);
+
+    let _update = state.updates.lock().await;
+    let mut data = state.locale.write().await;
+    if !data.keymaps_db.add(keymap.clone()) {
+        return Err(LocaleError::DuplicatedKeymap(id).into());
     }
+    Ok((StatusCode::CREATED, Json(keymap)))
+}
 
-    if let Some(keymap) = &config.keymap {
-        client.set_keymap(keymap.as_str()).await?;
+/// Returns the keymap with the given ID.
+///
+/// It is useful to validate a keymap ID without fetching the whole list.
+#[utoipa::path(
+    get,
+    path = "/keymaps/:id",
+    context_path = "/api/l10n",
+    params(("id" = String, Path, description = "Keymap ID (e.g., \"us\" or \"cz(qwerty)\")")),
+    responses(
+      (status = 200, description = "Keymap", body = Keymap),
+      (status = 404, description = "The keymap ID is not valid or the keymap is unknown")
+    )
+)]
+async fn keymap(
+    State(state): State<LocaleState<'_>>,
+    Path(id): Path<String>,
+) -> Result<Json<Keymap>, Response> {
+    let data = state.locale.read().await;
+    let keymap = id
+        .parse()
+        .map_err(LocaleError::InvalidKeymap)
+        .and_then(|id| {
+            data.keymaps_db
+                .find(&id)
+                .ok_or(LocaleError::UnknownKeymap(id))
+        });
+
+    match keymap {
+        Ok(keymap) => Ok(Json(keymap.clone())),
+        Err(error) => Err(not_found(error)),
     }
+}
+
+/// Console font needed by a keymap.
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct ConsoleFont {
+    /// Font name (e.g., "cyr-sun16").
+    font: &'static str,
+}
 
-    if let Some(timezone) = &config.timezone {
-        client.set_timezone(timezone).await?;
+/// Returns the console font to use with the given keymap.
+///
+/// Some keymaps (e.g., Cyrillic or Greek layouts) produce characters which are not included in
+/// the default console font. When the default font suffices, it returns "204 No Content".
+#[utoipa::path(
+    get,
+    path = "/keymaps/:id/console-font",
+    context_path = "/api/l10n",
+    params(("id" = String, Path, description = "Keymap ID (e.g., \"ru\")")),
+    responses(
+      (status = 200, description = "Console font for the keymap", body = ConsoleFont),
+      (status = 204, description = "The default console font suffices"),
+      (status = 404, description = "The keymap ID is not valid or the keymap is unknown")
+    )
+)]
+async fn keymap_console_font(
+    State(state): State<LocaleState<'_>>,
+    Path(id): Path<String>,
+) -> Response {
+    let keymap_id = match id.parse() {
+        Ok(keymap_id) => keymap_id,
+        Err(error) => return not_found(LocaleError::InvalidKeymap(error)),
+    };
+    if !state.locale.read().await.keymaps_db.exists(&keymap_id) {
+        return not_found(LocaleError::UnknownKeymap(keymap_id));
     }
 
-    if let Some(ui_locale) = &config.ui_locale {
-        client.set_uilocale(ui_locale).await?;
+    match console_font(&keymap_id) {
+        Some(font) => Json(ConsoleFont { font }).into_response(),
+        None => StatusCode::NO_CONTENT.into_response(),
     }
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+struct SuggestedKeymapQuery {
+    /// Locale to get the keymap for (e.g., "de_DE").
+    locale: String,
+}
+
+/// Returns the suggested keymap for a locale.
+#[utoipa::path(
+    get,
+    path = "/keymaps/suggested",
+    context_path = "/api/l10n",
+    params(SuggestedKeymapQuery),
+    responses(
+      (status = 200, description = "Suggested keymap", body = Keymap),
+      (status = 204, description = "There is no suggestion for the locale"),
+      (status = 400, description = "The locale is not valid")
+    )
+)]
+async fn suggested_keymap(
+    State(state): State<LocaleState<'_>>,
+    Query(query): Query<SuggestedKeymapQuery>,
+) -> Result<Response, Error> {
+    let locale = LocaleId::try_from(query.locale.as_str()).map_err(LocaleError::InvalidLocale)?;
+    let data = state.locale.read().await;
+    let response = match data.keymaps_db.suggest(&locale) {
+        Some(keymap) => Json(keymap.clone()).into_response(),
+        None => StatusCode::NO_CONTENT.into_response(),
+    };
+    Ok(response)
+}
 
-    Ok(())
+// Builds a "404 Not Found" response including the error code and message.
+fn not_found(error: LocaleError) -> Response {
+    (StatusCode::NOT_FOUND, Json(LocaleErrorBody::from(&error))).into_response()
 }
@@ -0,0 +1,1047 @@
+//! This module implements the endpoints to read and change the localization configuration.
+
+use super::LocaleState;
+use crate::{
+    error::Error,
+    l10n::{
+        error::LocaleError,
+        helpers,
+        keyboard::UiKeymapMethod,
+        l10n::{rollback_on_error, ValidatedConfig},
+        locale::{LocalesDatabase, SelectedLocale},
+        timezone::TimezonesDatabase,
+        L10n,
+    },
+    web::{
+        negotiation::{Format, JsonOrYaml, Negotiated},
+        Event,
+    },
+};
+use agama_lib::{
+    error::ServiceError,
+    localization::model::LocaleConfig,
+    localization::{LocaleProxy, LocalizationSettings},
+};
+use agama_locale_data::{KeymapId, LocaleId};
+use async_trait::async_trait;
+use axum::{
+    extract::{FromRequest, Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap},
+    fs,
+    hash::{Hash, Hasher},
+    io,
+};
+use utoipa::{
+    openapi::{RefOr, Schema},
+    ToSchema,
+};
+
+/// Returns the schema of the localization configuration.
+///
+/// It is the same schema included in the OpenAPI document, so the clients can generate forms
+/// without processing the whole document. All the attributes are optional.
+#[utoipa::path(
+    get,
+    path = "/config/schema",
+    context_path = "/api/l10n",
+    responses(
+      (status = 200, description = "Schema of the localization configuration")
+    )
+)]
+pub(super) async fn config_schema() -> Json<RefOr<Schema>> {
+    let (_, schema) = LocaleConfig::schema();
+    Json(schema)
+}
+
+/// Returns the details of the locales to install.
+///
+/// The locales which are not known anymore are included with `valid` set to `false`.
+#[utoipa::path(
+    get,
+    path = "/config/locales",
+    context_path = "/api/l10n",
+    responses(
+      (status = 200, description = "Locales to install", body = Vec<SelectedLocale>)
+    )
+)]
+pub(super) async fn selected_locales(
+    State(state): State<LocaleState<'_>>,
+) -> Json<Vec<SelectedLocale>> {
+    let data = state.locale.read().await;
+    Json(data.selected_locales())
+}
+
+/// Extractor for a [LocaleConfig] request body, written in JSON or YAML.
+///
+/// In strict mode (see [super::L10nServiceOptions::strict_config]), the unknown attributes are
+/// rejected with a "400 Bad Request" error listing them. Otherwise, they are ignored.
+pub(super) struct ConfigBody(LocaleConfig);
+
+#[async_trait]
+impl<'a> FromRequest<LocaleState<'a>> for ConfigBody {
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &LocaleState<'a>) -> Result<Self, Self::Rejection> {
+        if !state.strict_config {
+            let JsonOrYaml(config) = JsonOrYaml::from_request(req, state).await?;
+            return Ok(Self(config));
+        }
+
+        let JsonOrYaml(value) = JsonOrYaml::<serde_json::Value>::from_request(req, state).await?;
+        let unknown: Vec<_> = value
+            .as_object()
+            .map(|o| {
+                o.keys()
+                    .filter(|k| !LocaleConfig::FIELDS.contains(&k.as_str()))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !unknown.is_empty() {
+            let error = LocaleError::UnknownFields(unknown);
+            state.metrics.record_failure(error.code());
+            return Err(Error::from(error).into_response());
+        }
+
+        serde_json::from_value(value).map(Self).map_err(|error| {
+            let body = json!({ "error": format!("Failed to deserialize the body: {error}") });
+            (StatusCode::UNPROCESSABLE_ENTITY, Json(body)).into_response()
+        })
+    }
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub(super) struct SetConfigQuery {
+    /// Whether to switch to a UI locale the user interface is not translated to.
+    #[serde(default)]
+    allow_untranslated: bool,
+}
+
+/// Updates the localization configuration.
+///
+/// Only the given attributes are modified. Only the attributes whose value changed are included
+/// in the emitted `L10nConfigChanged` event.
+///
+/// When the UI keymap changes, the method used to apply it is reported in the
+/// `x-agama-ui-keymap-method` header ("x11" or "console" when there is no X server). The header
+/// is not included if the service is set up to not apply the UI keymap (see
+/// [super::L10nServiceOptions::apply_ui_keymap]).
+///
+/// To avoid overwriting someone else's changes, the client can send the `ETag` returned by
+/// `GET /config` in the `If-Match` header. The ETag is derived from a hash of the current
+/// configuration, so it changes whenever any attribute changes. The strong comparison is used, so
+/// weak validators (`W/"..."`) never match. If it does not match, nothing is modified and it
+/// returns a "412 Precondition Failed" error including the current ETag.
+///
+/// The body can be written in YAML, using the `application/yaml` content type. Unknown attributes
+/// are ignored unless the service runs in strict mode (see
+/// [super::L10nServiceOptions::strict_config]).
+///
+/// Switching to a `uiLocale` the user interface is not translated to (see `/ui-locales`) fails
+/// with a `translation_unavailable` error, unless `allow_untranslated` is set.
+#[utoipa::path(
+    patch,
+    path = "/config",
+    context_path = "/api/l10n",
+    operation_id = "set_l10n_config",
+    request_body = LocaleConfig,
+    params(
+      SetConfigQuery,
+      ("If-Match" = Option<String>, Header, description = "ETag of the configuration to modify (as returned by GET /config)")
+    ),
+    responses(
+      (status = 204, description = "Set the locale configuration", body = LocaleConfig,
+        headers(("x-agama-ui-keymap-method" = String, description = "Method used to apply the UI keymap (\"x11\" or \"console\"), if it changed"))),
+      (status = 400, description = "Some value is malformed or some attribute is unknown"),
+      (status = 412, description = "The configuration does not match the If-Match header", body = LocaleErrorBody),
+      (status = 422, description = "Some value is unknown or not acceptable"),
+      (status = 500, description = "The changes could not be applied")
+    )
+)]
+pub(super) async fn set_config(
+    State(state): State<LocaleState<'_>>,
+    headers: HeaderMap,
+    Query(query): Query<SetConfigQuery>,
+    ConfigBody(value): ConfigBody,
+) -> Result<impl IntoResponse, Error> {
+    if !query.allow_untranslated {
+        check_ui_translation(&state, &value).await?;
+    }
+    let if_match = if_match_header(&headers);
+    let set_locales = value.locales.is_some();
+    let (changes, method) = update_config(&state, value, true, if_match, false).await?;
+    if set_locales {
+        let locales = state.locale.read().await.locales.clone();
+        state.recent_locales.write().await.push(&locales);
+    }
+    _ = state.events.send(Event::L10nConfigChanged(changes));
+    Ok(config_updated_response(method))
+}
+
+/// Replaces the localization configuration.
+///
+/// The `locales`, `keymap` and `timezone` attributes are mandatory. When `uiLocale` or
+/// `uiKeymap` are omitted, they are reset to their default values (`en_US.UTF-8` and `us`).
+/// When `uiKeyboardOptions` is omitted, the keyboard options are removed. When `rtcUtc` is
+/// omitted, the hardware clock mode is reset to the one detected when the service started. When
+/// `ntpServers` is omitted, the list of NTP servers is emptied.
+///
+/// Like `PATCH /config`, it supports the `If-Match` header, the `allow_untranslated` parameter
+/// and YAML bodies, and it rejects the unknown attributes.
+#[utoipa::path(
+    put,
+    path = "/config",
+    context_path = "/api/l10n",
+    operation_id = "replace_l10n_config",
+    request_body = LocaleConfig,
+    params(
+      SetConfigQuery,
+      ("If-Match" = Option<String>, Header, description = "ETag of the configuration to replace (as returned by GET /config)")
+    ),
+    responses(
+      (status = 204, description = "Replace the locale configuration", body = LocaleConfig),
+      (status = 400, description = "Some mandatory attribute is missing, some value is malformed or some attribute is unknown"),
+      (status = 412, description = "The configuration does not match the If-Match header", body = LocaleErrorBody),
+      (status = 422, description = "Some value is unknown or not acceptable"),
+      (status = 500, description = "The changes could not be applied")
+    )
+)]
+pub(super) async fn replace_config(
+    State(state): State<LocaleState<'_>>,
+    headers: HeaderMap,
+    Query(query): Query<SetConfigQuery>,
+    ConfigBody(mut value): ConfigBody,
+) -> Result<impl IntoResponse, Error> {
+    if value.locales.is_none() {
+        return Err(LocaleError::MissingField("locales".to_string()).into());
+    }
+    if value.keymap.is_none() {
+        return Err(LocaleError::MissingField("keymap".to_string()).into());
+    }
+    if value.timezone.is_none() {
+        return Err(LocaleError::MissingField("timezone".to_string()).into());
+    }
+    // the omitted attributes get the same values as in `/config/reset`
+    let (default_ui_keymap, default_rtc_utc) = {
+        let data = state.locale.read().await;
+        (data.default_ui_keymap.to_string(), data.default_rtc_utc)
+    };
+    value
+        .ui_locale
+        .get_or_insert_with(|| LocaleId::default().to_string());
+    value.ui_keymap.get_or_insert(default_ui_keymap);
+    value.ui_keyboard_options.get_or_insert_with(Vec::new);
+    value.rtc_utc.get_or_insert(default_rtc_utc);
+    value.ntp_servers.get_or_insert_with(Vec::new);
+    if !query.allow_untranslated {
+        check_ui_translation(&state, &value).await?;
+    }
+
+    let if_match = if_match_header(&headers);
+    let (changes, method) = update_config(&state, value, true, if_match, false).await?;
+    _ = state.events.send(Event::L10nConfigChanged(changes));
+    Ok(config_updated_response(method))
+}
+
+// Makes sure that the user interface is translated to the UI locale of the configuration, if it
+// changes.
+//
+// The check is skipped when the directory of the web UI is unknown. Malformed locales are
+// reported later, when the configuration is validated.
+async fn check_ui_translation(
+    state: &LocaleState<'_>,
+    config: &LocaleConfig,
+) -> Result<(), LocaleError> {
+    let Some(ui_dir) = &state.ui_dir else {
+        return Ok(());
+    };
+    let Some(locale) = config
+        .ui_locale
+        .as_deref()
+        .and_then(|l| helpers::parse_locale(l).ok())
+    else {
+        return Ok(());
+    };
+    if state.locale.read().await.ui_locale == locale {
+        return Ok(());
+    }
+
+    if helpers::has_ui_translation(&locale, &helpers::ui_translations(ui_dir)) {
+        return Ok(());
+    }
+    let error = LocaleError::TranslationUnavailable(locale.to_string());
+    state.metrics.record_failure(error.code());
+    Err(error)
+}
+
+/// Resets the localization configuration to the default values.
+///
+/// The emitted `L10nConfigChanged` event includes all the attributes.
+#[utoipa::path(
+    post,
+    path = "/config/reset",
+    context_path = "/api/l10n",
+    operation_id = "reset_l10n_config",
+    responses(
+      (status = 204, description = "The locale configuration was reset"),
+      (status = 500, description = "The default configuration could not be applied")
+    )
+)]
+pub(super) async fn reset_config(
+    State(state): State<LocaleState<'_>>,
+) -> Result<impl IntoResponse, Error> {
+    let defaults = state.locale.read().await.default_config();
+    let (_, method) = update_config(&state, defaults, true, None, false).await?;
+
+    let config = current_config(&*state.locale.read().await);
+    _ = state.events.send(Event::L10nConfigChanged(config));
+    Ok(config_updated_response(method))
+}
+
+/// Reverts the last change of the localization configuration.
+///
+/// It applies the previous configuration again, emitting an `L10nConfigChanged` event with the
+/// attributes that changed.
+#[utoipa::path(
+    post,
+    path = "/config/undo",
+    context_path = "/api/l10n",
+    operation_id = "undo_l10n_config",
+    responses(
+      (status = 204, description = "The last change was reverted"),
+      (status = 500, description = "The previous configuration could not be applied"),
+      (status = 409, description = "There is nothing to undo")
+    )
+)]
+pub(super) async fn undo_config(State(state): State<LocaleState<'_>>) -> Result<Response, Error> {
+    // hold the `updates` lock from taking the configuration out of the history until it is
+    // applied (or put back), so no other change can be recorded in between
+    let _update = state.updates.lock().await;
+    let Some(previous) = state.history.write().await.pop() else {
+        let body = json!({ "error": "There is nothing to undo" });
+        return Ok((StatusCode::CONFLICT, Json(body)).into_response());
+    };
+
+    match update_config(&state, previous.clone(), false, None, true).await {
+        Ok((changes, method)) => {
+            _ = state.events.send(Event::L10nConfigChanged(changes));
+            Ok(config_updated_response(method))
+        }
+        // the previous configuration was applied anyway, so it is not kept in the history
+        Err(error @ Error::Locale(LocaleError::PartialCommit(_))) => Err(error),
+        Err(error) => {
+            // keep the configuration, so it is possible to try again
+            state.history.write().await.push(previous);
+            Err(error)
+        }
+    }
+}
+
+/// Header to report the method used to apply the UI keymap ("x11" or "console").
+pub const UI_KEYMAP_METHOD_HEADER: &str = "x-agama-ui-keymap-method";
+
+// Builds the response for a configuration update, reporting the method used to apply the UI
+// keymap when it changed.
+fn config_updated_response(method: Option<UiKeymapMethod>) -> Response {
+    match method {
+        Some(method) => (
+            StatusCode::NO_CONTENT,
+            [(UI_KEYMAP_METHOD_HEADER, method.as_str())],
+        )
+            .into_response(),
+        None => StatusCode::NO_CONTENT.into_response(),
+    }
+}
+
+// Applies the given configuration, updating only the given attributes.
+//
+// It returns the attributes that changed and, if the UI keymap changed, the method used to apply
+// it. When `record` is true, the previous configuration is added to the history. When `if_match`
+// is given, the changes are only applied if it matches the ETag of the current configuration.
+// When `locked` is true, the caller already holds the `updates` mutex.
+//
+// The updates are serialized using the `updates` mutex. The slow operations (reading the
+// translations, running `localectl`/`setxkbmap` and the D-Bus calls) run without holding the
+// `locale` lock, which is only write-locked to change the state. This way, the read endpoints
+// (e.g., `/locales`) keep responding while a configuration change is being applied, waiting at
+// most for the in-memory update.
+//
+// If the UI locale cannot be set in the manager, the state has already changed, so the changes
+// are still recorded, saved and announced (including the `L10nConfigChanged` event) and it returns
+// a `PartialCommit` error.
+//
+// The failures are counted in the metrics.
+async fn update_config(
+    state: &LocaleState<'_>,
+    value: LocaleConfig,
+    record: bool,
+    if_match: Option<&str>,
+    locked: bool,
+) -> Result<(LocaleConfig, Option<UiKeymapMethod>), Error> {
+    let result = apply_config(state, value, record, if_match, locked).await;
+    if let Err(error) = &result {
+        let code = match error {
+            Error::Locale(error) => error.code(),
+            _ => "other",
+        };
+        state.metrics.record_failure(code);
+    }
+    result
+}
+
+// Applies the given configuration (see [update_config]).
+//
+// It goes through the following stages: validating the values ([prepare_update]), applying the
+// changes to the system ([apply_system_changes]), updating the state ([commit_update]) and
+// announcing the changes ([publish_update]).
+#[tracing::instrument(
+    level = "debug",
+    skip(state, value),
+    fields(fields = ?config_fields(&value))
+)]
+async fn apply_config(
+    state: &LocaleState<'_>,
+    value: LocaleConfig,
+    record: bool,
+    if_match: Option<&str>,
+    locked: bool,
+) -> Result<(LocaleConfig, Option<UiKeymapMethod>), Error> {
+    let _update = if locked {
+        None
+    } else {
+        Some(state.updates.lock().await)
+    };
+
+    // the check must happen while holding the `updates` lock, so no other change can sneak in
+    if let Some(if_match) = if_match {
+        let etag = config_etag(&current_config(&*state.locale.read().await));
+        if !if_match_matches(if_match, &etag) {
+            tracing::debug!(if_match, etag, "The configuration was modified");
+            return Err(LocaleError::ConfigChanged(etag).into());
+        }
+    }
+
+    let update = prepare_update(state, &value).await?;
+    let ui_keymap_method = apply_system_changes(state, &update).await?;
+    let (changes, config) = commit_update(state, update, record).await?;
+    publish_update(state, &changes, &config).await?;
+    Ok((changes, ui_keymap_method))
+}
+
+// Configuration update which was validated and is ready to be applied.
+struct ConfigUpdate {
+    // Validated values.
+    config: ValidatedConfig,
+    // Configuration before the update.
+    previous: LocaleConfig,
+    // Current UI keymap, keyboard options and hardware clock mode, to restore them if needed.
+    ui_keymap: KeymapId,
+    ui_keyboard_options: Vec<String>,
+    rtc_utc: bool,
+    // Translations of the new UI locale, if they were not loaded yet.
+    translations: Option<(LocalesDatabase, TimezonesDatabase)>,
+}
+
+// Validates all the values before applying any change, so the configuration is updated all or
+// nothing. It also reads the translations the new UI locale needs, if any.
+async fn prepare_update(
+    state: &LocaleState<'_>,
+    value: &LocaleConfig,
+) -> Result<ConfigUpdate, Error> {
+    let (config, previous, ui_keymap, ui_keyboard_options, rtc_utc, translations_needed) = {
+        let data = state.locale.read().await;
+        let config = data.validate_config(value).map_err(|mut errors| {
+            for (field, error) in &errors {
+                tracing::debug!(field, %error, "Invalid value");
+            }
+            errors.swap_remove(0).1
+        })?;
+        tracing::debug!("The configuration is valid");
+        let translations_needed = config
+            .ui_locale
+            .as_ref()
+            .is_some_and(|l| data.needs_translations(l));
+        (
+            config,
+            current_config(&data),
+            data.ui_keymap.clone(),
+            data.ui_keyboard_options.clone(),
+            data.rtc_utc,
+            translations_needed,
+        )
+    };
+
+    let translations = match &config.ui_locale {
+        Some(locale) if translations_needed => {
+            let language = locale.language.clone();
+            let translations =
+                tokio::task::spawn_blocking(move || L10n::read_translations(&language))
+                    .await
+                    .map_err(|e| Error::Anyhow(e.to_string()))??;
+            Some(translations)
+        }
+        _ => None,
+    };
+
+    Ok(ConfigUpdate {
+        config,
+        previous,
+        ui_keymap,
+        ui_keyboard_options,
+        rtc_utc,
+        translations,
+    })
+}
+
+// Applies the hardware clock mode and the UI keymap to the system.
+//
+// The hardware clock mode goes first, so it can be restored if the UI keymap cannot be applied.
+// It returns the method used to apply the UI keymap, if it changed.
+async fn apply_system_changes(
+    state: &LocaleState<'_>,
+    update: &ConfigUpdate,
+) -> Result<Option<UiKeymapMethod>, Error> {
+    let config = &update.config;
+    let new_rtc_utc = config.rtc_utc.filter(|r| *r != update.rtc_utc);
+    if let Some(rtc_utc) = new_rtc_utc {
+        L10n::apply_rtc_utc(rtc_utc).await?;
+    }
+
+    if config.ui_keymap.is_none() && config.ui_keyboard_options.is_none() {
+        return Ok(None);
+    }
+    let ui_keymap = config.ui_keymap.as_ref().unwrap_or(&update.ui_keymap);
+    let ui_options = config
+        .ui_keyboard_options
+        .as_ref()
+        .unwrap_or(&update.ui_keyboard_options);
+    if !state.apply_ui_keymap {
+        tracing::debug!(%ui_keymap, ?ui_options, "Not applying the UI keymap to the system");
+        return Ok(None);
+    }
+    if *ui_keymap == update.ui_keymap && *ui_options == update.ui_keyboard_options {
+        return Ok(None);
+    }
+
+    let method = L10n::apply_ui_keymap(
+        ui_keymap,
+        ui_options,
+        &update.ui_keymap,
+        &update.ui_keyboard_options,
+        &state.display,
+    );
+    let restore_rtc_utc = async {
+        if new_rtc_utc.is_some() {
+            L10n::apply_rtc_utc(update.rtc_utc).await?;
+        }
+        Ok(())
+    };
+    let method = rollback_on_error(method, restore_rtc_utc, "the previous hardware clock mode");
+    Ok(Some(method.await?))
+}
+
+// Updates the state, returning the attributes that changed and the resulting configuration.
+//
+// It also updates the time each attribute changed and, when `record` is true and something
+// changed, it adds the previous configuration to the history.
+async fn commit_update(
+    state: &LocaleState<'_>,
+    update: ConfigUpdate,
+    record: bool,
+) -> Result<(LocaleConfig, LocaleConfig), Error> {
+    let ConfigUpdate {
+        config,
+        previous,
+        translations,
+        ..
+    } = update;
+    let mut changes = LocaleConfig::default();
+    let config = {
+        let mut data = state.locale.write().await;
+
+        // the translations were read in advance, so translating does not read any file; it goes
+        // first anyway, so the state is left untouched if it fails
+        if let Some(locale) = config.ui_locale {
+            if data.ui_locale != locale {
+                if let Some(translations) = translations {
+                    data.add_translations(&locale.language, translations);
+                }
+                data.translate(&locale)?;
+                changes.ui_locale = Some(locale.to_string());
+            }
+        }
+
+        if let Some(locales) = config.locales {
+            if data.locales != locales {
+                data.locales.clone_from(&locales);
+                changes.locales = Some(locales);
+            }
+        }
+
+        if let Some(timezone) = config.timezone {
+            if data.timezone != timezone {
+                data.timezone.clone_from(&timezone);
+                changes.timezone = Some(timezone);
+            }
+        }
+
+        if let Some(keymap_id) = config.keymap {
+            if data.keymap != keymap_id {
+                changes.keymap = Some(keymap_id.to_string());
+                data.keymap = keymap_id;
+            }
+        }
+
+        if let Some(ui_keymap) = config.ui_keymap {
+            if data.ui_keymap != ui_keymap {
+                changes.ui_keymap = Some(ui_keymap.to_string());
+                data.ui_keymap = ui_keymap;
+            }
+        }
+
+        if let Some(options) = config.ui_keyboard_options {
+            if data.ui_keyboard_options != options {
+                data.ui_keyboard_options.clone_from(&options);
+                changes.ui_keyboard_options = Some(options);
+            }
+        }
+
+        if let Some(rtc_utc) = config.rtc_utc {
+            if data.rtc_utc != rtc_utc {
+                data.rtc_utc = rtc_utc;
+                changes.rtc_utc = Some(rtc_utc);
+            }
+        }
+
+        if let Some(ntp_servers) = config.ntp_servers {
+            if data.ntp_servers != ntp_servers {
+                data.ntp_servers.clone_from(&ntp_servers);
+                changes.ntp_servers = Some(ntp_servers);
+            }
+        }
+
+        current_config(&data)
+    };
+
+    let changed = config_fields(&changes);
+    tracing::debug!(?changed, "Configuration applied");
+    state.timestamps.write().await.touch(&changed, Utc::now());
+
+    if record && config != previous {
+        state.history.write().await.push(previous);
+    }
+
+    Ok((changes, config))
+}
+
+// Announces the changes: it sets the UI locale in the manager, emits the `LocaleChanged` and
+// `KeymapChanged` events, synchronizes the D-Bus service and saves the configuration.
+//
+// The state already changed, so a failure to set the UI locale in the manager is reported only
+// once everything is published (see [update_config]).
+async fn publish_update(
+    state: &LocaleState<'_>,
+    changes: &LocaleConfig,
+    config: &LocaleConfig,
+) -> Result<(), Error> {
+    let mut manager_error = None;
+    if let Some(locale) = &changes.ui_locale {
+        if let Err(e) = state.manager_proxy.set_locale(locale).await {
+            manager_error = Some(e);
+        }
+        _ = state.events.send(Event::LocaleChanged {
+            locale: locale.to_string(),
+        });
+    }
+
+    if let Some(keymap) = &changes.ui_keymap {
+        _ = state.events.send(Event::KeymapChanged {
+            keymap: keymap.to_string(),
+        });
+    }
+
+    if let Err(e) = update_dbus(&state.proxy, changes).await {
+        log::warn!("Could not synchronize settings in the localization D-Bus service: {e}");
+    }
+
+    if let Some(path) = &state.state_file {
+        if let Err(e) = save_config(path, config) {
+            log::warn!("Could not save the localization configuration: {e}");
+        }
+    }
+
+    if let Some(error) = manager_error {
+        // the callers only emit the event on success
+        _ = state.events.send(Event::L10nConfigChanged(changes.clone()));
+        let message = format!("the UI locale of the manager could not be set: {error}");
+        return Err(LocaleError::PartialCommit(message).into());
+    }
+
+    Ok(())
+}
+
+// Returns the names of the attributes included in the configuration.
+fn config_fields(config: &LocaleConfig) -> Vec<&'static str> {
+    [
+        ("locales", config.locales.is_some()),
+        ("keymap", config.keymap.is_some()),
+        ("timezone", config.timezone.is_some()),
+        ("uiLocale", config.ui_locale.is_some()),
+        ("uiKeymap", config.ui_keymap.is_some()),
+        ("uiKeyboardOptions", config.ui_keyboard_options.is_some()),
+        ("rtcUtc", config.rtc_utc.is_some()),
+        ("ntpServers", config.ntp_servers.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(name, present)| present.then_some(name))
+    .collect()
+}
+
+// Reads the configuration from the given file, returning `None` if it does not exist or it is not
+// valid JSON.
+pub(super) fn load_config(path: &std::path::Path) -> Option<LocaleConfig> {
+    let content = fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(config) => Some(config),
+        Err(error) => {
+            log::warn!("Could not read the localization configuration: {error}");
+            None
+        }
+    }
+}
+
+// Writes the configuration to the given file. It writes to a temporary file first, so a crash does
+// not leave a truncated file behind.
+fn save_config(path: &std::path::Path, config: &LocaleConfig) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, serde_json::to_string(config)?)?;
+    fs::rename(&tmp_path, path)
+}
+
+/// Problem found when validating a localization configuration.
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct ConfigValidationError {
+    /// Name of the invalid field (e.g., "timezone").
+    field: String,
+    /// Reason why the value is not valid.
+    reason: String,
+    /// Stable error code (e.g., "unknown_timezone").
+    code: &'static str,
+    /// Offending value, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Object)]
+    value: Option<serde_json::Value>,
+}
+
+impl ConfigValidationError {
+    fn new(field: &str, error: &LocaleError) -> Self {
+        Self {
+            field: field.to_string(),
+            reason: error.to_string(),
+            code: error.code(),
+            value: error.value(),
+        }
+    }
+}
+
+/// Validates the localization configuration without applying it.
+///
+/// All the problems are reported at once. Like `PATCH /config`, it rejects the unknown
+/// attributes with a "400 Bad Request" error.
+#[utoipa::path(
+    post,
+    path = "/config/validate",
+    context_path = "/api/l10n",
+    operation_id = "validate_l10n_config",
+    responses(
+      (status = 200, description = "The configuration is valid", body = Vec<ConfigValidationError>),
+      (status = 400, description = "Some attribute is unknown", body = LocaleErrorBody),
+      (status = 422, description = "The configuration is not valid", body = Vec<ConfigValidationError>)
+    )
+)]
+pub(super) async fn validate_config(
+    State(state): State<LocaleState<'_>>,
+    ConfigBody(value): ConfigBody,
+) -> impl IntoResponse {
+    let data = state.locale.read().await;
+    match data.validate_config(&value) {
+        Ok(_) => (StatusCode::OK, Json(vec![])),
+        Err(errors) => {
+            let errors: Vec<_> = errors
+                .iter()
+                .map(|(field, error)| {
+                    state.metrics.record_failure(error.code());
+                    ConfigValidationError::new(field, error)
+                })
+                .collect();
+            (StatusCode::UNPROCESSABLE_ENTITY, Json(errors))
+        }
+    }
+}
+
+// Keys of the localization section of a profile and the configuration attributes they are
+// mapped to.
+const PROFILE_FIELDS: [(&str, &str); 3] = [
+    ("language", "locales"),
+    ("keyboard", "keymap"),
+    ("timezone", "timezone"),
+];
+
+/// Result of importing a field of the localization section of a profile.
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct ImportedField {
+    /// Profile key (e.g., "keyboard").
+    field: &'static str,
+    /// Configuration attribute it was mapped to (e.g., "keymap").
+    attribute: &'static str,
+    /// Whether the value changed. It is `false` if the attribute already had that value.
+    changed: bool,
+}
+
+/// Imports the localization section of a profile.
+///
+/// The section uses the profile keys (`language`, `keyboard` and `timezone`), which are mapped to
+/// the configuration attributes (`locales`, `keymap` and `timezone`). The whole section is
+/// validated before applying any change, reporting all the problems at once using the profile
+/// keys. Like `PATCH /config`, the body can be written in JSON or YAML.
+///
+/// It returns a report including each imported field.
+#[utoipa::path(
+    post,
+    path = "/config/import",
+    context_path = "/api/l10n",
+    operation_id = "import_l10n_config",
+    request_body = LocalizationSettings,
+    responses(
+      (status = 200, description = "The section was imported", body = Vec<ImportedField>),
+      (status = 422, description = "The section is not valid", body = Vec<ConfigValidationError>),
+      (status = 500, description = "The changes could not be applied")
+    )
+)]
+pub(super) async fn import_config(
+    State(state): State<LocaleState<'_>>,
+    JsonOrYaml(settings): JsonOrYaml<LocalizationSettings>,
+) -> Result<Response, Error> {
+    let present = [
+        settings.language.is_some(),
+        settings.keyboard.is_some(),
+        settings.timezone.is_some(),
+    ];
+    let value = LocaleConfig::from(settings);
+
+    if let Err(errors) = state.locale.read().await.validate_config(&value) {
+        let errors: Vec<_> = errors
+            .iter()
+            .map(|(attribute, error)| {
+                let field = PROFILE_FIELDS
+                    .iter()
+                    .find(|(_, a)| a == attribute)
+                    .map_or(*attribute, |(f, _)| *f);
+                state.metrics.record_failure(error.code());
+                ConfigValidationError::new(field, error)
+            })
+            .collect();
+        return Ok((StatusCode::UNPROCESSABLE_ENTITY, Json(errors)).into_response());
+    }
+
+    let (changes, _) = update_config(&state, value, true, None, false).await?;
+    let changed = config_fields(&changes);
+    let report: Vec<_> = PROFILE_FIELDS
+        .into_iter()
+        .zip(present)
+        .filter(|(_, present)| *present)
+        .map(|((field, attribute), _)| ImportedField {
+            field,
+            attribute,
+            changed: changed.contains(&attribute),
+        })
+        .collect();
+    _ = state.events.send(Event::L10nConfigChanged(changes));
+    Ok(Json(report).into_response())
+}
+
+/// Localization configuration, including the primary locale.
+#[derive(Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct L10nConfig {
+    #[serde(flatten)]
+    config: LocaleConfig,
+    /// Primary locale of the target system (the first one of `locales`).
+    primary_locale: Option<String>,
+    /// Time each attribute was last changed, in RFC 3339 format (only if `with_timestamps` is
+    /// set). The attributes which did not change since the service started are not included.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timestamps: Option<BTreeMap<&'static str, String>>,
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub(super) struct ConfigQuery {
+    /// Whether to include the time each attribute was last changed.
+    #[serde(default)]
+    with_timestamps: bool,
+}
+
+/// Returns the localization configuration.
+///
+/// The response includes the `primaryLocale`, which is the first element of `locales`.
+///
+/// The response includes a strong `ETag` header. When the `If-None-Match` header matches the
+/// current configuration, it returns a "304 Not Modified" response with no body.
+///
+/// The configuration is returned as YAML if the `Accept` header asks for it.
+///
+/// When `with_timestamps` is set, the response includes the time each attribute was last changed
+/// (by any endpoint), so the clients can find out which values were modified recently.
+#[utoipa::path(
+    get,
+    path = "/config",
+    context_path = "/api/l10n",
+    operation_id = "get_l10n_config",
+    params(ConfigQuery),
+    responses(
+        (status = 200, description = "Localization configuration", body = L10nConfig),
+        (status = 304, description = "The localization configuration did not change")
+    )
+)]
+pub(super) async fn get_config(
+    State(state): State<LocaleState<'_>>,
+    format: Format,
+    headers: HeaderMap,
+    Query(query): Query<ConfigQuery>,
+) -> Response {
+    let data = state.locale.read().await;
+    let config = current_config(&data);
+    let etag = config_etag(&config);
+
+    let matches = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|h| etag_matches(h, &etag));
+    if matches {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response();
+    }
+
+    let mut config = L10nConfig {
+        config,
+        primary_locale: data.primary_locale().map(str::to_string),
+        timestamps: None,
+    };
+    if query.with_timestamps {
+        config.timestamps = Some(state.timestamps.read().await.to_rfc3339());
+    }
+    ([(header::ETAG, etag)], Negotiated(format, config)).into_response()
+}
+
+// Computes a strong ETag for the given configuration.
+//
+// The hash covers every attribute of the configuration, so two representations with the same
+// ETag are identical.
+fn config_etag(config: &LocaleConfig) -> String {
+    let mut hasher = DefaultHasher::new();
+    config.locales.hash(&mut hasher);
+    config.keymap.hash(&mut hasher);
+    config.timezone.hash(&mut hasher);
+    config.ui_locale.hash(&mut hasher);
+    config.ui_keymap.hash(&mut hasher);
+    config.ui_keyboard_options.hash(&mut hasher);
+    config.rtc_utc.hash(&mut hasher);
+    config.ntp_servers.hash(&mut hasher);
+    format!("\"{:x}\"", hasher.finish())
+}
+
+// Determines whether the If-None-Match header value matches the ETag (using the weak
+// comparison).
+fn etag_matches(header: &str, etag: &str) -> bool {
+    let etag = etag.trim_start_matches("W/");
+    header
+        .split(',')
+        .map(|t| t.trim())
+        .any(|t| t == "*" || t.trim_start_matches("W/") == etag)
+}
+
+// Determines whether the If-Match header value matches the ETag (using the strong comparison, as
+// required by RFC 9110). Weak validators never match.
+fn if_match_matches(header: &str, etag: &str) -> bool {
+    !etag.starts_with("W/")
+        && header
+            .split(',')
+            .map(|t| t.trim())
+            .any(|t| t == "*" || t == etag)
+}
+
+// Returns the value of the If-Match header, if any.
+fn if_match_header(headers: &HeaderMap) -> Option<&str> {
+    headers.get(header::IF_MATCH).and_then(|h| h.to_str().ok())
+}
+
+// Returns the current localization configuration.
+pub(super) fn current_config(data: &L10n) -> LocaleConfig {
+    LocaleConfig {
+        locales: Some(data.locales.clone()),
+        keymap: Some(data.keymap.to_string()),
+        timezone: Some(data.timezone.to_string()),
+        ui_locale: Some(data.ui_locale.to_string()),
+        ui_keymap: Some(data.ui_keymap.to_string()),
+        ui_keyboard_options: Some(data.ui_keyboard_options.clone()),
+        rtc_utc: Some(data.rtc_utc),
+        ntp_servers: Some(data.ntp_servers.clone()),
+    }
+}
+
+pub async fn update_dbus(
+    client: &LocaleProxy<'_>,
+    config: &LocaleConfig,
+) -> Result<(), ServiceError> {
+    if let Some(locales) = &config.locales {
+        let locales: Vec<_> = locales.iter().map(|l| l.as_ref()).collect();
+        client.set_locales(&locales).await?;
+    }
+
+    if let Some(keymap) = &config.keymap {
+        client.set_keymap(keymap.as_str()).await?;
+    }
+
+    if let Some(timezone) = &config.timezone {
+        client.set_timezone(timezone).await?;
+    }
+
+    if let Some(ui_locale) = &config.ui_locale {
+        client.set_uilocale(ui_locale).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{etag_matches, if_match_matches};
+
+    #[test]
+    fn test_etag_matches() {
+        assert!(etag_matches("\"1a\"", "\"1a\""));
+        assert!(etag_matches("W/\"1a\"", "\"1a\""));
+        assert!(etag_matches("\"0\", W/\"1a\"", "\"1a\""));
+        assert!(etag_matches("*", "\"1a\""));
+        assert!(!etag_matches("\"0\"", "\"1a\""));
+    }
+
+    #[test]
+    fn test_if_match_matches() {
+        assert!(if_match_matches("\"1a\"", "\"1a\""));
+        assert!(if_match_matches("\"0\", \"1a\"", "\"1a\""));
+        assert!(if_match_matches("*", "\"1a\""));
+        assert!(!if_match_matches("W/\"1a\"", "\"1a\""));
+        assert!(!if_match_matches("\"0\"", "\"1a\""));
+    }
+}
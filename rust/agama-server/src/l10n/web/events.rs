@@ -0,0 +1,110 @@
+//! This module implements the WebSocket to follow the localization events.
+
+use super::LocaleState;
+use crate::web::{Event, EventsReceiver};
+use axum::{
+    extract::{
+        ws::{Message, WebSocket},
+        State, WebSocketUpgrade,
+    },
+    response::Response,
+};
+use tokio::sync::broadcast::error::RecvError;
+
+/// Sends the localization events to the client through a WebSocket.
+///
+/// Only the `L10nConfigChanged`, `LocaleChanged` and `KeymapChanged` events are sent, as JSON text
+/// messages. The `L10nConfigChanged` events only include the attributes that changed (e.g.,
+/// `{"type":"L10nConfigChanged","timezone":"Europe/Madrid"}`), so the clients can keep up to date
+/// without polling `/config`.
+#[utoipa::path(
+    get,
+    path = "/events",
+    context_path = "/api/l10n",
+    responses(
+      (status = 101, description = "Switching to the WebSocket protocol")
+    )
+)]
+pub(super) async fn events(State(state): State<LocaleState<'_>>, ws: WebSocketUpgrade) -> Response {
+    // subscribe before the upgrade, so no event is lost in the meantime
+    let events = state.events.subscribe();
+    ws.on_upgrade(move |socket| forward_events(socket, events))
+}
+
+// Forwards the localization events to the WebSocket until the client disconnects.
+//
+// The socket is read too, so a closed connection is noticed right away instead of on the next
+// event. The messages from the client are ignored.
+async fn forward_events(mut socket: WebSocket, mut events: EventsReceiver) {
+    loop {
+        let event = tokio::select! {
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+            event = events.recv() => match event {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    log::warn!("Skipped {skipped} events for a localization events client");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            },
+        };
+        let Some(json) = l10n_event_json(&event) else {
+            continue;
+        };
+        if socket.send(Message::Text(json.to_string())).await.is_err() {
+            break;
+        }
+    }
+}
+
+// Returns the JSON representation of a localization event, without the attributes that did not
+// change. It returns `None` for the rest of events.
+fn l10n_event_json(event: &Event) -> Option<serde_json::Value> {
+    if !matches!(
+        event,
+        Event::L10nConfigChanged(_) | Event::LocaleChanged { .. } | Event::KeymapChanged { .. }
+    ) {
+        return None;
+    }
+    let mut json = serde_json::to_value(event).ok()?;
+    if let Some(object) = json.as_object_mut() {
+        object.retain(|_, value| !value.is_null());
+    }
+    Some(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::l10n_event_json;
+    use crate::web::Event;
+    use agama_lib::localization::model::LocaleConfig;
+    use serde_json::json;
+
+    #[test]
+    fn test_l10n_event_json() {
+        let changes = LocaleConfig {
+            timezone: Some("Europe/Madrid".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            l10n_event_json(&Event::L10nConfigChanged(changes)),
+            Some(json!({"type": "L10nConfigChanged", "timezone": "Europe/Madrid"}))
+        );
+
+        let event = Event::KeymapChanged {
+            keymap: "es".to_string(),
+        };
+        assert_eq!(
+            l10n_event_json(&event),
+            Some(json!({"type": "KeymapChanged", "keymap": "es"}))
+        );
+
+        let event = Event::ProductChanged {
+            id: "Tumbleweed".to_string(),
+        };
+        assert_eq!(l10n_event_json(&event), None);
+    }
+}
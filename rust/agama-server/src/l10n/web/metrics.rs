@@ -0,0 +1,56 @@
+//! This module implements the usage counters of the localization web API.
+
+use super::LocaleState;
+use crate::l10n::metrics::{L10nMetrics, L10nMetricsSnapshot};
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+    Json,
+};
+use serde::Serialize;
+use std::sync::Arc;
+
+// Counts the requests to each endpoint.
+pub(super) async fn count_requests(
+    State(metrics): State<Arc<L10nMetrics>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if let Some(path) = request.extensions().get::<MatchedPath>() {
+        metrics.record_request(request.method().as_str(), path.as_str());
+    }
+    next.run(request).await
+}
+
+/// Usage counters of the localization API.
+#[derive(Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct L10nMetricsInfo {
+    #[serde(flatten)]
+    counters: L10nMetricsSnapshot,
+    /// Number of locales in the current configuration.
+    selected_locales: usize,
+}
+
+/// Returns the usage counters of the localization API.
+///
+/// It includes the number of requests to each endpoint and the number of failures by error code
+/// (e.g., "unknown_locale"). The failures include the changes that could not be applied and the
+/// problems found when validating a configuration. The requests to this endpoint are not
+/// counted.
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    context_path = "/api/l10n",
+    responses(
+      (status = 200, description = "Usage counters", body = L10nMetricsInfo)
+    )
+)]
+pub(super) async fn metrics(State(state): State<LocaleState<'_>>) -> Json<L10nMetricsInfo> {
+    let selected_locales = state.locale.read().await.locales.len();
+    Json(L10nMetricsInfo {
+        counters: state.metrics.snapshot(),
+        selected_locales,
+    })
+}
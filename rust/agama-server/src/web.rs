@@ -6,7 +6,7 @@
 
 use crate::{
     error::Error,
-    l10n::web::l10n_service,
+    l10n::web::{l10n_service_with_options, L10nServiceOptions, DEFAULT_STATE_FILE},
     manager::web::{manager_service, manager_stream},
     network::{web::network_service, NetworkManagerAdapter},
     questions::web::{questions_service, questions_stream},
@@ -23,6 +23,7 @@ mod config;
 mod docs;
 mod event;
 mod http;
+pub mod negotiation;
 mod service;
 mod state;
 mod ws;
@@ -54,8 +55,18 @@ where
         .await
         .expect("Could not connect to NetworkManager to read the configuration");
 
+    let l10n_options = L10nServiceOptions {
+        state_file: Some(DEFAULT_STATE_FILE.into()),
+        ui_dir: Some(web_ui_dir.as_ref().to_path_buf()),
+        debug: config.debug,
+        strict_config: true,
+        ..Default::default()
+    };
     let router = MainServiceBuilder::new(events.clone(), web_ui_dir)
-        .add_service("/l10n", l10n_service(dbus.clone(), events.clone()).await?)
+        .add_service(
+            "/l10n",
+            l10n_service_with_options(dbus.clone(), events.clone(), l10n_options).await?,
+        )
         .add_service("/manager", manager_service(dbus.clone()).await?)
         .add_service("/software", software_service(dbus.clone()).await?)
         .add_service("/storage", storage_service(dbus.clone()).await?)
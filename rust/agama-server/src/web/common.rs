@@ -4,6 +4,7 @@ use std::{pin::Pin, task::Poll};
 
 use agama_lib::{
     error::ServiceError,
+    issues::Issue,
     progress::Progress,
     proxies::{IssuesProxy, ProgressProxy, ServiceStatusProxy},
 };
@@ -287,7 +288,7 @@ pub async fn issues_router<T>(
 
 async fn issues(State(state): State<IssuesState<'_>>) -> Result<Json<Vec<Issue>>, Error> {
     let issues = state.proxy.all().await?;
-    let issues: Vec<Issue> = issues.into_iter().map(Issue::from_tuple).collect();
+    let issues: Vec<Issue> = issues.into_iter().map(Issue::from).collect();
     Ok(Json(issues))
 }
 
@@ -296,33 +297,6 @@ struct IssuesState<'a> {
     proxy: IssuesProxy<'a>,
 }
 
-#[derive(Clone, Debug, Serialize)]
-pub struct Issue {
-    description: String,
-    details: Option<String>,
-    source: u32,
-    severity: u32,
-}
-
-impl Issue {
-    pub fn from_tuple(
-        (description, details, source, severity): (String, String, u32, u32),
-    ) -> Self {
-        let details = if details.is_empty() {
-            None
-        } else {
-            Some(details)
-        };
-
-        Self {
-            description,
-            details,
-            source,
-            severity,
-        }
-    }
-}
-
 /// Builds a stream of the changes in the the `org.opensuse.Agama1.Issues`
 /// interface of the given D-Bus object.
 ///
@@ -340,7 +314,7 @@ pub async fn issues_stream(
         .await
         .then(move |change| async move {
             if let Ok(issues) = change.get().await {
-                let issues = issues.into_iter().map(Issue::from_tuple).collect();
+                let issues = issues.into_iter().map(Issue::from).collect();
                 Some(Event::IssuesChanged {
                     service: destination.to_string(),
                     path: path.to_string(),
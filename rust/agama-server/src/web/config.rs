@@ -19,6 +19,8 @@ use serde::Deserialize;
 pub struct ServiceConfig {
     /// Key to sign the JSON Web Tokens.
     pub jwt_secret: String,
+    /// Whether to enable the debugging endpoints (e.g., `/api/l10n/debug`).
+    pub debug: bool,
 }
 
 impl ServiceConfig {
@@ -29,6 +31,7 @@ impl ServiceConfig {
 
         let config = Config::builder()
             .set_default("jwt_secret", jwt_secret)?
+            .set_default("debug", false)?
             .add_source(File::with_name("/usr/etc/agama.d/server").required(false))
             .add_source(File::with_name("/etc/agama.d/server").required(false))
             .add_source(File::with_name("etc/agama.d/server").required(false))
@@ -41,6 +44,7 @@ impl Default for ServiceConfig {
     fn default() -> Self {
         Self {
             jwt_secret: "".to_string(),
+            debug: false,
         }
     }
 }
@@ -3,11 +3,37 @@ use utoipa::OpenApi;
 #[openapi(
     info(description = "Agama web API description"),
     paths(
-        crate::l10n::web::get_config,
+        crate::l10n::web::add_keymap,
+        crate::l10n::web::config::config_schema,
+        crate::l10n::web::config::get_config,
+        crate::l10n::web::config::import_config,
+        crate::l10n::web::config::replace_config,
+        crate::l10n::web::config::reset_config,
+        crate::l10n::web::config::selected_locales,
+        crate::l10n::web::config::set_config,
+        crate::l10n::web::config::undo_config,
+        crate::l10n::web::config::validate_config,
+        crate::l10n::web::debug,
+        crate::l10n::web::default_locale,
+        crate::l10n::web::detected_timezone,
+        crate::l10n::web::events::events,
+        crate::l10n::web::health,
+        crate::l10n::web::keymap,
+        crate::l10n::web::keymap_console_font,
         crate::l10n::web::keymaps,
+        crate::l10n::web::languages,
+        crate::l10n::web::locale,
         crate::l10n::web::locales,
-        crate::l10n::web::set_config,
+        crate::l10n::web::metrics::metrics,
+        crate::l10n::web::negotiate_ui_locale,
+        crate::l10n::web::preview_ui_locale,
+        crate::l10n::web::recent_locales,
+        crate::l10n::web::search_keymaps,
+        crate::l10n::web::suggested_keymap,
+        crate::l10n::web::suggested_timezone,
+        crate::l10n::web::summary,
         crate::l10n::web::timezones,
+        crate::l10n::web::ui_locales,
         crate::manager::web::finish_action,
         crate::manager::web::install_action,
         crate::manager::web::installer_status,
@@ -94,9 +120,32 @@ use utoipa::OpenApi;
         schemas(agama_lib::storage::client::iscsi::LoginResult),
         schemas(agama_lib::users::FirstUser),
         schemas(crate::l10n::Keymap),
+        schemas(crate::l10n::KeymapMatch),
+        schemas(crate::l10n::LanguageEntry),
         schemas(crate::l10n::LocaleEntry),
+        schemas(crate::l10n::LocaleErrorBody),
+        schemas(crate::l10n::SelectedLocale),
+        schemas(crate::l10n::TextDirection),
         schemas(crate::l10n::TimezoneEntry),
+        schemas(crate::l10n::web::config::ConfigValidationError),
+        schemas(crate::l10n::web::config::ImportedField),
+        schemas(crate::l10n::web::ConsoleFont),
+        schemas(crate::l10n::web::metrics::L10nMetricsInfo),
+        schemas(crate::l10n::L10nMetricsSnapshot),
+        schemas(crate::l10n::web::CustomKeymap),
+        schemas(crate::l10n::web::KeymapsList),
+        schemas(crate::l10n::web::KeymapsPage),
+        schemas(crate::l10n::web::TimezonesList),
+        schemas(crate::l10n::web::TimezonesPage),
+        schemas(crate::l10n::web::LocaleNegotiation),
+        schemas(crate::l10n::web::config::L10nConfig),
+        schemas(crate::l10n::web::L10nHealth),
+        schemas(crate::l10n::web::L10nDebugInfo),
+        schemas(crate::l10n::web::DataFileInfo),
+        schemas(crate::l10n::web::L10nSummary),
+        schemas(crate::l10n::web::TranslationPreview),
         schemas(agama_lib::localization::model::LocaleConfig),
+        schemas(agama_lib::localization::LocalizationSettings),
         schemas(crate::manager::web::InstallerStatus),
         schemas(crate::network::model::Connection),
         schemas(crate::network::model::Device),
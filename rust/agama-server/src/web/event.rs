@@ -1,6 +1,6 @@
 use crate::network::model::NetworkChange;
 use agama_lib::{
-    localization::model::LocaleConfig, manager::InstallationPhase,
+    issues::Issue, localization::model::LocaleConfig, manager::InstallationPhase,
     product::RegistrationRequirement, progress::Progress, software::SelectedBy, storage::ISCSINode,
     users::FirstUser,
 };
@@ -8,15 +8,19 @@ use serde::Serialize;
 use std::collections::HashMap;
 use tokio::sync::broadcast::{Receiver, Sender};
 
-use super::common::Issue;
-
 #[derive(Clone, Debug, Serialize)]
 #[serde(tag = "type")]
 pub enum Event {
+    /// The localization configuration changed. Only the attributes that changed are set (`Some`),
+    /// so subscribers can rely on `None` meaning "this attribute did not change".
     L10nConfigChanged(LocaleConfig),
     LocaleChanged {
         locale: String,
     },
+    /// The UI keymap changed.
+    KeymapChanged {
+        keymap: String,
+    },
     DevicesDirty {
         dirty: bool,
     },
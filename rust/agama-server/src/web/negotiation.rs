@@ -0,0 +1,222 @@
+//! Content negotiation for the web API.
+//!
+//! The responses are serialized as JSON unless the client asks for YAML through the `Accept`
+//! header (e.g., `Accept: application/yaml`). Similarly, the request bodies are read as YAML when
+//! the `Content-Type` header says so.
+//!
+//! * [Format]: extractor to find out the format of the response.
+//! * [Negotiated]: response serialized in the given format.
+//! * [JsonOrYaml]: extractor to read a JSON or YAML request body.
+
+use async_trait::async_trait;
+use axum::{
+    body::Bytes,
+    extract::{FromRequest, FromRequestParts, Request},
+    http::{header, request, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::json;
+use std::convert::Infallible;
+
+/// Media types recognized as YAML.
+pub const YAML_MEDIA_TYPES: [&str; 3] = ["application/yaml", "application/x-yaml", "text/yaml"];
+
+/// Serialization format.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Format {
+    #[default]
+    Json,
+    Yaml,
+}
+
+impl Format {
+    /// Determines the format of the response according to the `Accept` header.
+    ///
+    /// It picks the preferred media type (the one with the highest quality value) among JSON and
+    /// YAML. It defaults to JSON when the header is missing or it does not mention any of them.
+    ///
+    /// * `headers`: request headers.
+    pub fn from_accept(headers: &HeaderMap) -> Self {
+        let Some(accept) = headers.get(header::ACCEPT).and_then(|h| h.to_str().ok()) else {
+            return Self::Json;
+        };
+
+        let mut ranges: Vec<(Self, f32)> = accept
+            .split(',')
+            .filter_map(|item| {
+                let mut parts = item.split(';');
+                let format = Self::from_media_type(parts.next()?)?;
+                let quality = parts
+                    .filter_map(|p| p.trim().strip_prefix("q="))
+                    .find_map(|q| q.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                Some((format, quality))
+            })
+            .filter(|(_, quality)| *quality > 0.0)
+            .collect();
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranges.first().map(|(f, _)| *f).unwrap_or_default()
+    }
+
+    /// Determines the format of the body according to the `Content-Type` header.
+    ///
+    /// It defaults to JSON when the header is missing or it is not a YAML media type.
+    ///
+    /// * `headers`: request headers.
+    pub fn from_content_type(headers: &HeaderMap) -> Self {
+        headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.split(';').next())
+            .and_then(Self::from_media_type)
+            .unwrap_or_default()
+    }
+
+    /// Returns the media type of the format.
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Yaml => "application/yaml",
+        }
+    }
+
+    // Returns the format for the given media type, if it is JSON or YAML
+    fn from_media_type(media_type: &str) -> Option<Self> {
+        let media_type = media_type.trim().to_lowercase();
+        if media_type == "application/json" {
+            Some(Self::Json)
+        } else if YAML_MEDIA_TYPES.contains(&media_type.as_str()) {
+            Some(Self::Yaml)
+        } else {
+            None
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Format {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut request::Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_accept(&parts.headers))
+    }
+}
+
+/// Response serialized as JSON or YAML.
+///
+/// ```no_run
+/// # use agama_server::web::negotiation::{Format, Negotiated};
+/// async fn handler(format: Format) -> Negotiated<Vec<String>> {
+///     Negotiated(format, vec!["en_US.UTF-8".to_string()])
+/// }
+/// ```
+pub struct Negotiated<T>(pub Format, pub T);
+
+impl<T: Serialize> IntoResponse for Negotiated<T> {
+    fn into_response(self) -> Response {
+        let Self(format, value) = self;
+        match format {
+            Format::Json => Json(value).into_response(),
+            Format::Yaml => match serde_yaml::to_string(&value) {
+                Ok(yaml) => (
+                    [(
+                        header::CONTENT_TYPE,
+                        HeaderValue::from_static(format.media_type()),
+                    )],
+                    yaml,
+                )
+                    .into_response(),
+                Err(error) => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "error": error.to_string() })),
+                )
+                    .into_response(),
+            },
+        }
+    }
+}
+
+/// Extractor for a JSON or YAML request body.
+///
+/// The body is read as YAML if the `Content-Type` is a YAML media type (see [YAML_MEDIA_TYPES]).
+/// Otherwise, it behaves like [Json].
+pub struct JsonOrYaml<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for JsonOrYaml<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if Format::from_content_type(req.headers()) == Format::Json {
+            let Json(value) = Json::<T>::from_request(req, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+            return Ok(Self(value));
+        }
+
+        let bytes = Bytes::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        serde_yaml::from_slice(&bytes).map(Self).map_err(|error| {
+            let body = json!({ "error": format!("Failed to parse the YAML body: {error}") });
+            (StatusCode::UNPROCESSABLE_ENTITY, Json(body)).into_response()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Format;
+    use axum::http::{header, HeaderMap, HeaderValue};
+
+    fn headers(name: header::HeaderName, value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn test_from_accept() {
+        assert_eq!(Format::from_accept(&HeaderMap::new()), Format::Json);
+        for (accept, format) in [
+            ("application/yaml", Format::Yaml),
+            ("text/yaml", Format::Yaml),
+            ("application/json", Format::Json),
+            ("*/*", Format::Json),
+            ("application/json;q=0.5, application/yaml", Format::Yaml),
+            ("application/yaml;q=0.5, application/json", Format::Json),
+            ("application/yaml;q=0, text/html", Format::Json),
+        ] {
+            assert_eq!(
+                Format::from_accept(&headers(header::ACCEPT, accept)),
+                format,
+                "{accept}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_content_type() {
+        assert_eq!(Format::from_content_type(&HeaderMap::new()), Format::Json);
+        assert_eq!(
+            Format::from_content_type(&headers(
+                header::CONTENT_TYPE,
+                "application/x-yaml; charset=utf-8"
+            )),
+            Format::Yaml
+        );
+        assert_eq!(
+            Format::from_content_type(&headers(header::CONTENT_TYPE, "application/json")),
+            Format::Json
+        );
+    }
+}
@@ -1,16 +1,24 @@
 pub mod common;
 
-use std::error::Error;
+use std::{error::Error, sync::Arc, time::Duration};
 
-use agama_server::l10n::web::l10n_service;
+use agama_server::{
+    l10n::web::{l10n_service, l10n_service_with_options, L10nServiceOptions},
+    web::Event,
+};
 use axum::{
     body::Body,
     http::{Request, StatusCode},
     Router,
 };
 use common::{body_to_string, DBusServer};
-use tokio::{sync::broadcast::channel, test};
+use tokio::{
+    sync::{broadcast::channel, Notify},
+    test,
+    time::timeout,
+};
 use tower::ServiceExt;
+use zbus::dbus_interface;
 
 async fn build_service(dbus: zbus::Connection) -> Router {
     let (tx, _) = channel(16);
@@ -27,6 +35,24 @@ async fn test_get_config() -> Result<(), Box<dyn Error>> {
         .unwrap();
     let response = service.oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""rtcUtc":"#));
+    Ok(())
+}
+
+#[test]
+async fn test_health() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::builder()
+        .uri("/health")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""failed":[]"#));
+    assert!(body.contains(r#""missingTools":"#));
     Ok(())
 }
 
@@ -45,6 +71,44 @@ async fn test_locales() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+async fn test_locales_search() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::builder()
+        .uri("/locales?language=es&search=spain")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""id":"es_ES.UTF-8""#));
+    assert!(!body.contains(r#""language":"English""#));
+    Ok(())
+}
+
+#[test]
+async fn test_locales_group_by_language() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::builder()
+        .uri("/locales?group_by=language")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""es":[{"#));
+
+    let request = Request::builder()
+        .uri("/locales?group_by=unknown")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    Ok(())
+}
+
 #[test]
 async fn test_keymaps() -> Result<(), Box<dyn Error>> {
     let dbus_server = DBusServer::new().start().await?;
@@ -60,6 +124,72 @@ async fn test_keymaps() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+async fn test_keymaps_by_language() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::builder()
+        .uri("/keymaps?group_by=language")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""en":[{"id":"#));
+    assert!(body.contains(r#""Other":["#));
+    Ok(())
+}
+
+#[test]
+async fn test_keymaps_by_layout() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::builder()
+        .uri("/keymaps?layout=us&variant=dvorak")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""layout":"us","variant":"dvorak""#));
+    assert!(!body.contains(r#""variant":null"#));
+
+    let request = Request::builder()
+        .uri("/keymaps?layout=us&variant=unknown")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    let body = body_to_string(response.into_body()).await;
+    assert_eq!(body, "[]");
+    Ok(())
+}
+
+#[test]
+async fn test_add_keymap() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    let content = "{\"id\":\"oem(special)\",\"description\":\"OEM (Special)\"}";
+    let request = Request::post("/keymaps")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let request = Request::post("/keymaps")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from("{\"keymap\":\"oem(special)\"}"))?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    Ok(())
+}
+
 #[test]
 async fn test_timezones() -> Result<(), Box<dyn Error>> {
     let dbus_server = DBusServer::new().start().await?;
@@ -101,3 +231,1285 @@ async fn test_set_config_locales() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+async fn test_set_config_timezone_changes() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let (tx, mut rx) = channel(16);
+    let service = l10n_service(dbus_server.connection(), tx).await?;
+
+    let content = "{\"timezone\":\"Atlantic/Canary\"}";
+    let body = Body::from(content);
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(body)?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let Event::L10nConfigChanged(changes) = rx.recv().await? else {
+        panic!("Expected an L10nConfigChanged event");
+    };
+    assert_eq!(changes.timezone, Some("Atlantic/Canary".to_string()));
+    assert_eq!(changes.locales, None);
+    assert_eq!(changes.keymap, None);
+    assert_eq!(changes.ui_locale, None);
+    assert_eq!(changes.ui_keymap, None);
+    Ok(())
+}
+
+#[test]
+async fn test_set_config_timezone_alias() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let (tx, mut rx) = channel(16);
+    let service = l10n_service(dbus_server.connection(), tx).await?;
+
+    let content = "{\"timezone\":\"asia/calcutta\"}";
+    let body = Body::from(content);
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(body)?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let Event::L10nConfigChanged(changes) = rx.recv().await? else {
+        panic!("Expected an L10nConfigChanged event");
+    };
+    assert_eq!(changes.timezone, Some("Asia/Kolkata".to_string()));
+    Ok(())
+}
+
+#[test]
+async fn test_set_config_unknown_locales() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    let content = "{\"locales\":[\"es_ES.UTF-8\",\"xx_XX\",\"yy_YY.UTF-8\"]}";
+    let body = Body::from(content);
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(body)?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains("xx_XX, yy_YY.UTF-8"));
+    assert!(body.contains(r#""error":"unknown_locales""#));
+    assert!(body.contains(r#""value":["xx_XX","yy_YY.UTF-8"]"#));
+    Ok(())
+}
+
+#[test]
+async fn test_set_config_all_or_nothing() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let (tx, mut rx) = channel(16);
+    let service = l10n_service(dbus_server.connection(), tx).await?;
+
+    let content = r#"{"locales":["es_ES.UTF-8"],"timezone":"Europe/Madrid","uiKeymap":"unknown"}"#;
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(rx.try_recv().is_err());
+
+    let request = Request::get("/config").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    let body = body_to_string(response.into_body()).await;
+    assert!(!body.contains("es_ES.UTF-8"));
+    assert!(!body.contains("Europe/Madrid"));
+    Ok(())
+}
+
+#[test]
+async fn test_set_config_malformed_locales() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    for content in [r#"{"locales":["es-"]}"#, r#"{"uiLocale":"es-"}"#] {
+        let request = Request::patch("/config")
+            .header("Content-Type", "application/json")
+            .body(Body::from(content))?;
+        let response = service.clone().oneshot(request).await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = body_to_string(response.into_body()).await;
+        assert!(body.contains(r#""error":"invalid_locale""#));
+    }
+    Ok(())
+}
+
+#[test]
+async fn test_set_config_invalid_locales_list() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    for (content, message) in [
+        ("{\"locales\":[]}", "At least one locale is required"),
+        (
+            "{\"locales\":[\"es_ES\",\"de_DE\",\"es-ES.UTF-8\"]}",
+            "Duplicated locale codes: es_ES.UTF-8",
+        ),
+    ] {
+        let request = Request::patch("/config")
+            .header("Content-Type", "application/json")
+            .body(Body::from(content))?;
+        let response = service.clone().oneshot(request).await?;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = body_to_string(response.into_body()).await;
+        assert!(body.contains(message));
+    }
+    Ok(())
+}
+
+#[test]
+async fn test_get_config_primary_locale() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    let content = "{\"locales\":[\"de_DE\",\"es_ES\"]}";
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = Request::builder()
+        .uri("/config")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.oneshot(request).await?;
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""primaryLocale":"de_DE.UTF-8""#));
+    Ok(())
+}
+
+#[test]
+async fn test_set_config_canonical_locales() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let (tx, mut rx) = channel(16);
+    let service = l10n_service(dbus_server.connection(), tx).await?;
+
+    let content = "{\"locales\":[\"es-ES\",\"de_DE\",\"pt_BR.utf8\"]}";
+    let body = Body::from(content);
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(body)?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let Event::L10nConfigChanged(changes) = rx.recv().await? else {
+        panic!("Expected an L10nConfigChanged event");
+    };
+    assert_eq!(
+        changes.locales,
+        Some(vec![
+            "es_ES.UTF-8".to_string(),
+            "de_DE.UTF-8".to_string(),
+            "pt_BR.UTF-8".to_string()
+        ])
+    );
+    Ok(())
+}
+
+#[test]
+async fn test_persist_config() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let state_file = std::env::temp_dir().join(format!("agama-l10n-{}.json", std::process::id()));
+    let options = L10nServiceOptions {
+        state_file: Some(state_file.clone()),
+        ..Default::default()
+    };
+    let (tx, _) = channel(16);
+    let service = l10n_service_with_options(dbus_server.connection(), tx, options.clone()).await?;
+
+    let content = "{\"timezone\":\"Atlantic/Canary\"}";
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(state_file.exists());
+
+    let (tx, _) = channel(16);
+    let service = l10n_service_with_options(dbus_server.connection(), tx, options).await?;
+    let request = Request::builder()
+        .uri("/config")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.oneshot(request).await?;
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""timezone":"Atlantic/Canary""#));
+
+    std::fs::remove_file(state_file)?;
+    Ok(())
+}
+
+#[test]
+async fn test_selected_locales() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::builder()
+        .uri("/config/locales")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""id":"en_US.UTF-8""#));
+    assert!(body.contains(r#""valid":true"#));
+    Ok(())
+}
+
+#[test]
+async fn test_undo_config() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let (tx, mut rx) = channel(16);
+    let service = l10n_service(dbus_server.connection(), tx).await?;
+
+    let request = Request::post("/config/undo").body(Body::empty())?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let content = "{\"timezone\":\"Atlantic/Canary\"}";
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    _ = rx.recv().await?;
+
+    let request = Request::post("/config/undo").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let Event::L10nConfigChanged(changes) = rx.recv().await? else {
+        panic!("Expected an L10nConfigChanged event");
+    };
+    assert_eq!(changes.timezone, Some("Europe/Berlin".to_string()));
+    Ok(())
+}
+
+#[test]
+async fn test_config_schema() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::builder()
+        .uri("/config/schema")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""uiLocale":{"#));
+    assert!(!body.contains(r#""required""#));
+    Ok(())
+}
+
+#[test]
+async fn test_validate_config() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    let content = "{\"timezone\":\"Unknown/Unknown\",\"keymap\":\"unknown\"}";
+    let body = Body::from(content);
+    let request = Request::post("/config/validate")
+        .header("Content-Type", "application/json")
+        .body(body)?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""field":"timezone""#));
+    assert!(body.contains(r#""field":"keymap""#));
+    Ok(())
+}
+
+#[test]
+async fn test_replace_config_missing_field() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    let content = "{\"locales\":[\"es_ES.UTF-8\"],\"keymap\":\"es\"}";
+    let body = Body::from(content);
+    let request = Request::put("/config")
+        .header("Content-Type", "application/json")
+        .body(body)?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains("timezone"));
+    Ok(())
+}
+
+#[test]
+async fn test_replace_config_default_ui_keymap() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let options = L10nServiceOptions {
+        apply_ui_keymap: false,
+        ..Default::default()
+    };
+    let (tx, _rx) = channel(16);
+    let service = l10n_service_with_options(dbus_server.connection(), tx, options).await?;
+
+    let request = Request::post("/config/reset").body(Body::empty())?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let request = Request::get("/config").body(Body::empty())?;
+    let response = service.clone().oneshot(request).await?;
+    let body = body_to_string(response.into_body()).await;
+    let config: serde_json::Value = serde_json::from_str(&body)?;
+    let default_ui_keymap = config["uiKeymap"].clone();
+
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(r#"{"uiKeymap":"cz"}"#))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let content = r#"{"locales":["es_ES.UTF-8"],"keymap":"es","timezone":"Europe/Madrid"}"#;
+    let request = Request::put("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = Request::get("/config").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    let body = body_to_string(response.into_body()).await;
+    let config: serde_json::Value = serde_json::from_str(&body)?;
+    assert_eq!(config["uiKeymap"], default_ui_keymap);
+    Ok(())
+}
+
+#[test]
+async fn test_keymaps_page() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::builder()
+        .uri("/keymaps?offset=0&limit=1")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""offset":0,"limit":1"#));
+    Ok(())
+}
+
+#[test]
+async fn test_keymaps_invalid_page() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::builder()
+        .uri("/keymaps?limit=many")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    Ok(())
+}
+
+#[test]
+async fn test_timezones_in_locale() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::builder()
+        .uri("/timezones?country=DE&locale=fr_FR")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""country":"Allemagne""#));
+
+    let request = Request::builder()
+        .uri("/timezones?locale=wrong")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    Ok(())
+}
+
+#[test]
+async fn test_timezones_by_country() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::builder()
+        .uri("/timezones?country=ES")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""code":"Atlantic/Canary""#));
+    assert!(!body.contains(r#""code":"Europe/Berlin""#));
+    Ok(())
+}
+
+#[test]
+async fn test_keymap() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::builder()
+        .uri("/keymaps/us")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""id":"us""#));
+
+    let request = Request::builder()
+        .uri("/keymaps/unknown")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    Ok(())
+}
+
+#[test]
+async fn test_summary() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+    let response = service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""locales":["#));
+    assert!(body.contains(r#""keymaps":["#));
+    assert!(body.contains(r#""timezones":["#));
+    assert!(body.contains(r#""config":{"#));
+
+    let request = Request::builder()
+        .uri("/?include=config,keymaps")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""keymaps":["#));
+    assert!(body.contains(r#""config":{"#));
+    assert!(!body.contains(r#""locales""#));
+    assert!(!body.contains(r#""timezones""#));
+    Ok(())
+}
+
+#[test]
+async fn test_suggested_keymap() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::builder()
+        .uri("/keymaps/suggested?locale=de_DE")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""id":"de""#));
+
+    let request = Request::builder()
+        .uri("/keymaps/suggested?locale=invalid")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    Ok(())
+}
+
+#[test]
+async fn test_suggested_timezone() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::builder()
+        .uri("/timezones/suggested?country=DE")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""code":"Europe/Berlin""#));
+
+    let request = Request::builder()
+        .uri("/timezones/suggested")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    Ok(())
+}
+
+#[test]
+async fn test_default_locale() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::builder()
+        .uri("/locales/default")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""id":"en_US.UTF-8""#));
+    Ok(())
+}
+
+#[test]
+async fn test_locale() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::builder()
+        .uri("/locales/es_ES.UTF-8")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::builder()
+        .uri("/locales/spanish")
+        .body(Body::empty())
+        .unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains("Not a valid locale string"));
+    Ok(())
+}
+
+#[test]
+async fn test_reset_config() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let (tx, mut rx) = channel(16);
+    let service = l10n_service(dbus_server.connection(), tx).await?;
+
+    let request = Request::post("/config/reset").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let Event::L10nConfigChanged(config) = rx.recv().await? else {
+        panic!("Expected an L10nConfigChanged event");
+    };
+    assert_eq!(config.timezone, Some("Europe/Berlin".to_string()));
+    assert_eq!(config.keymap, Some("us".to_string()));
+    assert_eq!(config.ui_locale, Some("en_US.UTF-8".to_string()));
+    assert!(config.locales.is_some());
+    assert!(config.ui_keymap.is_some());
+    Ok(())
+}
+
+#[test]
+async fn test_set_config_ui_keymap() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    // do not change the keyboard of the machine running the tests
+    let options = L10nServiceOptions {
+        apply_ui_keymap: false,
+        ..Default::default()
+    };
+    let (tx, mut rx) = channel(16);
+    let service = l10n_service_with_options(dbus_server.connection(), tx, options).await?;
+
+    let content = "{\"uiKeymap\":\"es\"}";
+    let body = Body::from(content);
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(body)?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let Event::KeymapChanged { keymap } = rx.recv().await? else {
+        panic!("Expected a KeymapChanged event");
+    };
+    assert_eq!(keymap, "es".to_string());
+    Ok(())
+}
+
+#[test]
+async fn test_get_config_not_modified() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::get("/config").body(Body::empty())?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response
+        .headers()
+        .get("ETag")
+        .expect("ETag header not found")
+        .clone();
+
+    let request = Request::get("/config")
+        .header("If-None-Match", etag)
+        .body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    Ok(())
+}
+
+#[test]
+async fn test_set_config_if_match() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .header("If-Match", "W/\"0\"")
+        .body(Body::from("{\"timezone\":\"Europe/Berlin\"}"))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""error":"config_changed""#));
+
+    let request = Request::get("/config").body(Body::empty())?;
+    let response = service.clone().oneshot(request).await?;
+    let etag = response
+        .headers()
+        .get("ETag")
+        .expect("ETag header not found")
+        .clone();
+
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .header("If-Match", etag)
+        .body(Body::from("{\"timezone\":\"Europe/Berlin\"}"))?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    Ok(())
+}
+
+#[test]
+async fn test_set_config_if_match_weak() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    let request = Request::get("/config").body(Body::empty())?;
+    let response = service.clone().oneshot(request).await?;
+    let etag = response
+        .headers()
+        .get("ETag")
+        .expect("ETag header not found")
+        .to_str()?
+        .to_string();
+    assert!(!etag.starts_with("W/"));
+
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .header("If-Match", format!("W/{etag}"))
+        .body(Body::from("{\"timezone\":\"Europe/Berlin\"}"))?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+    Ok(())
+}
+
+#[test]
+async fn test_languages() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::get("/languages").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert_eq!(body.matches(r#""code":"es""#).count(), 1);
+    Ok(())
+}
+
+#[test]
+async fn test_locales_sorted() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::get("/locales").body(Body::empty())?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    let locales: Vec<serde_json::Value> = serde_json::from_str(&body)?;
+    let ids: Vec<_> = locales.iter().map(|l| l["id"].to_string()).collect();
+    let mut unique = ids.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), ids.len());
+
+    // the order is stable between requests
+    let request = Request::get("/locales").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(body_to_string(response.into_body()).await, body);
+    Ok(())
+}
+
+#[test]
+async fn test_search_keymaps() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::get("/keymaps/search?q=germn&limit=3").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    let found: Vec<serde_json::Value> = serde_json::from_str(&body)?;
+    assert!(!found.is_empty() && found.len() <= 3);
+    assert_eq!(found[0]["distance"], 1);
+    Ok(())
+}
+
+#[test]
+async fn test_set_config_ntp_servers() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    let content = r#"{"ntpServers":["pool.ntp.org","ntp..example.net"]}"#;
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""error":"invalid_ntp_servers""#));
+
+    let content = r#"{"ntpServers":["pool.ntp.org","192.168.1.1"]}"#;
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = Request::get("/config").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""ntpServers":["pool.ntp.org","192.168.1.1"]"#));
+    Ok(())
+}
+
+#[test]
+async fn test_debug() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+    let request = Request::get("/debug").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let options = L10nServiceOptions {
+        debug: true,
+        ..Default::default()
+    };
+    let (tx, _) = channel(16);
+    let service = l10n_service_with_options(dbus_server.connection(), tx, options).await?;
+    let request = Request::get("/debug").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""tzdbVersion":"#));
+    assert!(body.contains(r#""dataFiles":["#));
+    Ok(())
+}
+
+#[test]
+async fn test_set_config_locale_modifier() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    let content = r#"{"locales":["ca_ES@valencia"]}"#;
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = Request::get("/config").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""locales":["ca_ES.UTF-8@valencia"]"#));
+    Ok(())
+}
+
+#[test]
+async fn test_config_yaml() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    let content = "timezone: Atlantic/Canary\nlocales:\n  - es_ES.UTF-8\n";
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/yaml")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = Request::get("/config")
+        .header("Accept", "application/yaml")
+        .body(Body::empty())?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "application/yaml"
+    );
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains("timezone: Atlantic/Canary"));
+
+    let request = Request::get("/config").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""timezone":"Atlantic/Canary""#));
+    Ok(())
+}
+
+#[test]
+async fn test_import_config() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    let content = r#"{"language":"es_ES.UTF-8","keyboard":"xx","timezone":"Unknown/City"}"#;
+    let request = Request::post("/config/import")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""field":"keyboard""#));
+    assert!(body.contains(r#""field":"timezone""#));
+
+    let content = r#"{"language":"es_ES.UTF-8","keyboard":"es","timezone":"Europe/Madrid"}"#;
+    let request = Request::post("/config/import")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#"{"field":"keyboard","attribute":"keymap","changed":true}"#));
+
+    let request = Request::get("/config").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""keymap":"es""#));
+    assert!(body.contains(r#""locales":["es_ES.UTF-8"]"#));
+    Ok(())
+}
+
+#[test]
+async fn test_keymap_console_font() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    let request = Request::get("/keymaps/ru/console-font").body(Body::empty())?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert_eq!(body, r#"{"font":"cyr-sun16"}"#);
+
+    let request = Request::get("/keymaps/us/console-font").body(Body::empty())?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = Request::get("/keymaps/xx/console-font").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    Ok(())
+}
+
+#[test]
+async fn test_search_timezones() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    let request = Request::get("/timezones?search=madrid").body(Body::empty())?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.starts_with(r#"[{"code":"Europe/Madrid""#));
+
+    let request = Request::get("/timezones?search=xyzzy").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    let body = body_to_string(response.into_body()).await;
+    assert_eq!(body, "[]");
+    Ok(())
+}
+
+#[test]
+async fn test_metrics() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    let request = Request::get("/locales/es_ES").body(Body::empty())?;
+    service.clone().oneshot(request).await?;
+
+    let content = r#"{"locales":["xx_YY"]}"#;
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let request = Request::get("/metrics").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""GET /locales/:id":1"#));
+    assert!(body.contains(r#""PATCH /config":1"#));
+    assert!(body.contains(r#""failures":{"unknown_locales":1}"#));
+    assert!(body.contains(r#""selectedLocales":1"#));
+    Ok(())
+}
+
+#[test]
+async fn test_set_config_ui_keymap_not_applied() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let options = L10nServiceOptions {
+        apply_ui_keymap: false,
+        ..Default::default()
+    };
+    let (tx, mut rx) = channel(16);
+    let service = l10n_service_with_options(dbus_server.connection(), tx, options).await?;
+
+    let content = r#"{"uiKeymap":"es"}"#;
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(response.headers().get("x-agama-ui-keymap-method").is_none());
+
+    let mut keymap_changed = false;
+    while let Ok(event) = rx.try_recv() {
+        if let Event::KeymapChanged { keymap } = event {
+            assert_eq!(keymap, "es");
+            keymap_changed = true;
+        }
+    }
+    assert!(keymap_changed);
+
+    let request = Request::get("/config").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""uiKeymap":"es""#));
+    Ok(())
+}
+
+#[test]
+async fn test_recent_locales() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    for content in [
+        r#"{"locales":["es_ES.UTF-8"]}"#,
+        r#"{"locales":["en_US.UTF-8"]}"#,
+        r#"{"timezone":"Europe/Madrid"}"#,
+        r#"{"locales":["es_ES.UTF-8"]}"#,
+    ] {
+        let request = Request::patch("/config")
+            .header("Content-Type", "application/json")
+            .body(Body::from(content))?;
+        let response = service.clone().oneshot(request).await?;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    }
+
+    let request = Request::get("/locales/recent").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    let es = body.find(r#""id":"es_ES.UTF-8""#).unwrap();
+    let en = body.find(r#""id":"en_US.UTF-8""#).unwrap();
+    assert!(es < en);
+    assert_eq!(body.matches(r#""id":"es_ES.UTF-8""#).count(), 1);
+    Ok(())
+}
+
+#[test]
+async fn test_set_config_unknown_fields() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let options = L10nServiceOptions {
+        strict_config: true,
+        ..Default::default()
+    };
+    let (tx, _rx) = channel(16);
+    let service = l10n_service_with_options(dbus_server.connection(), tx, options).await?;
+
+    let content = r#"{"timzone":"Europe/Madrid"}"#;
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""error":"unknown_fields""#));
+    assert!(body.contains(r#""value":["timzone"]"#));
+
+    let request = Request::get("/config").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    let body = body_to_string(response.into_body()).await;
+    assert!(!body.contains("Europe/Madrid"));
+    Ok(())
+}
+
+#[test]
+async fn test_set_config_unknown_fields_lenient() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    let content = r#"{"timezone":"Europe/Madrid","timzone":"Europe/Prague"}"#;
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    Ok(())
+}
+
+#[test]
+async fn test_get_config_with_timestamps() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    let content = r#"{"timezone":"Europe/Madrid"}"#;
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = Request::get("/config").body(Body::empty())?;
+    let response = service.clone().oneshot(request).await?;
+    let body = body_to_string(response.into_body()).await;
+    assert!(!body.contains("timestamps"));
+
+    let request = Request::get("/config?with_timestamps=true").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value =
+        serde_json::from_str(&body_to_string(response.into_body()).await)?;
+    let timestamps = body["timestamps"].as_object().unwrap();
+    assert_eq!(timestamps.len(), 1);
+    assert!(timestamps["timezone"].is_string());
+    Ok(())
+}
+
+#[test]
+async fn test_keymaps_collation() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let service = build_service(dbus_server.connection()).await;
+
+    let request = Request::get("/keymaps").body(Body::empty())?;
+    let response = service.clone().oneshot(request).await?;
+    let default = body_to_string(response.into_body()).await;
+
+    let request = Request::get("/keymaps?collation=xx").body(Body::empty())?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_to_string(response.into_body()).await, default);
+
+    let request = Request::get("/keymaps?collation=sv_SE").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_to_string(response.into_body()).await;
+    let keymaps: Vec<serde_json::Value> = serde_json::from_str(&body)?;
+    let descriptions: Vec<_> = keymaps
+        .iter()
+        .map(|k| k["description"].as_str().unwrap().to_lowercase())
+        .collect();
+    let position = |text: &str| descriptions.iter().position(|d| d.starts_with(text));
+    assert!(position("czech").unwrap() < position("english").unwrap());
+    Ok(())
+}
+
+// Locale interface of the manager, which is told about the UI locale changes.
+struct ManagerLocale;
+
+#[dbus_interface(name = "org.opensuse.Agama1.Locale")]
+impl ManagerLocale {
+    fn set_locale(&self, _locale: &str) {}
+}
+
+// Serves the Locale interface of the manager on the given connection.
+async fn serve_manager_locale(connection: &zbus::Connection) -> Result<(), Box<dyn Error>> {
+    connection
+        .object_server()
+        .at("/org/opensuse/Agama/Manager1", ManagerLocale)
+        .await?;
+    connection
+        .request_name("org.opensuse.Agama.Manager1")
+        .await?;
+    Ok(())
+}
+
+#[test]
+async fn test_set_config_untranslated_ui_locale() -> Result<(), Box<dyn Error>> {
+    // removed when it is dropped, even if the test fails
+    let ui_dir = tempfile::tempdir()?;
+    std::fs::write(ui_dir.path().join("po.es.js"), "")?;
+
+    let dbus_server = DBusServer::new().start().await?;
+    serve_manager_locale(&dbus_server.connection()).await?;
+    let options = L10nServiceOptions {
+        ui_dir: Some(ui_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let (tx, _rx) = channel(16);
+    let service = l10n_service_with_options(dbus_server.connection(), tx, options).await?;
+
+    let content = r#"{"uiLocale":"fr_FR.UTF-8"}"#;
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""error":"translation_unavailable""#));
+
+    let content = r#"{"uiLocale":"es_ES.UTF-8"}"#;
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let content = r#"{"uiLocale":"fr_FR.UTF-8"}"#;
+    let request = Request::patch("/config?allow_untranslated=true")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    Ok(())
+}
+
+#[test]
+async fn test_default_keymap() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let options = L10nServiceOptions {
+        default_keymap: Some("de".parse()?),
+        ..Default::default()
+    };
+    let (tx, _rx) = channel(16);
+    let service = l10n_service_with_options(dbus_server.connection(), tx, options).await?;
+
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(r#"{"keymap":"es"}"#))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = Request::post("/config/reset").body(Body::empty())?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = Request::get("/config").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""keymap":"de""#));
+    Ok(())
+}
+
+#[test]
+async fn test_set_config_ui_keyboard_options() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let options = L10nServiceOptions {
+        apply_ui_keymap: false,
+        ..Default::default()
+    };
+    let (tx, _rx) = channel(16);
+    let service = l10n_service_with_options(dbus_server.connection(), tx, options).await?;
+
+    let content = r#"{"uiKeyboardOptions":["numpad:mac","grp:alt_shift_toggle"]}"#;
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""error":"unknown_keyboard_options""#));
+
+    let content = r#"{"uiKeyboardOptions":["numpad:mac"]}"#;
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = Request::get("/config").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""uiKeyboardOptions":["numpad:mac"]"#));
+    Ok(())
+}
+
+// Localization D-Bus service whose keymap setter does not return until it is released.
+struct SlowLocale {
+    keymap: String,
+    started: Arc<Notify>,
+    release: Arc<Notify>,
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Locale")]
+impl SlowLocale {
+    #[dbus_interface(property)]
+    fn keymap(&self) -> String {
+        self.keymap.clone()
+    }
+
+    #[dbus_interface(property)]
+    async fn set_keymap(&mut self, value: &str) {
+        self.started.notify_one();
+        self.release.notified().await;
+        self.keymap = value.to_string();
+    }
+}
+
+// An update keeps the `updates` lock until the D-Bus service is synchronized, but the read
+// endpoints must answer while it is still in progress.
+#[test]
+async fn test_read_during_slow_update() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let connection = dbus_server.connection();
+    let started = Arc::new(Notify::new());
+    let release = Arc::new(Notify::new());
+    let locale = SlowLocale {
+        keymap: "us".to_string(),
+        started: Arc::clone(&started),
+        release: Arc::clone(&release),
+    };
+    connection
+        .object_server()
+        .at("/org/opensuse/Agama1/Locale", locale)
+        .await?;
+    connection.request_name("org.opensuse.Agama1").await?;
+    let service = build_service(connection).await;
+
+    let request = Request::patch("/config")
+        .header("Content-Type", "application/json")
+        .body(Body::from(r#"{"keymap":"es"}"#))?;
+    let update = tokio::spawn(service.clone().oneshot(request));
+    timeout(Duration::from_secs(5), started.notified()).await?;
+
+    let request = Request::get("/locales").body(Body::empty())?;
+    let response = timeout(Duration::from_secs(1), service.oneshot(request)).await??;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!update.is_finished());
+
+    release.notify_one();
+    let response = timeout(Duration::from_secs(5), update).await???;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    Ok(())
+}
+
+#[test]
+async fn test_set_config_ui_locale_manager_failure() -> Result<(), Box<dyn Error>> {
+    // there is no manager service, so its UI locale cannot be set
+    let dbus_server = DBusServer::new().start().await?;
+    let (tx, mut rx) = channel(16);
+    let service = l10n_service(dbus_server.connection(), tx).await?;
+
+    let content = r#"{"uiLocale":"es_ES.UTF-8"}"#;
+    let request = Request::patch("/config?allow_untranslated=true")
+        .header("Content-Type", "application/json")
+        .body(Body::from(content))?;
+    let response = service.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""error":"partial_commit""#));
+
+    let mut config_changed = false;
+    while let Ok(event) = rx.try_recv() {
+        if let Event::L10nConfigChanged(changes) = event {
+            assert_eq!(changes.ui_locale, Some("es_ES.UTF-8".to_string()));
+            config_changed = true;
+        }
+    }
+    assert!(config_changed);
+
+    let request = Request::get("/config").body(Body::empty())?;
+    let response = service.oneshot(request).await?;
+    let body = body_to_string(response.into_body()).await;
+    assert!(body.contains(r#""uiLocale":"es_ES.UTF-8""#));
+    Ok(())
+}
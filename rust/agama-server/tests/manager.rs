@@ -0,0 +1,153 @@
+pub mod common;
+
+use agama_lib::{error::ServiceError, manager::ManagerClient};
+use common::DBusServer;
+use std::{
+    error::Error,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{test, time::timeout};
+use tokio_util::sync::CancellationToken;
+use zbus::{dbus_interface, Connection};
+
+const MANAGER_SERVICE: &str = "org.opensuse.Agama.Manager1";
+const MANAGER_PATH: &str = "/org/opensuse/Agama/Manager1";
+
+struct ServiceStatus {
+    current: u32,
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.ServiceStatus")]
+impl ServiceStatus {
+    #[dbus_interface(property)]
+    fn current(&self) -> u32 {
+        self.current
+    }
+}
+
+#[derive(Default)]
+struct Progress {
+    current_step: (u32, String),
+}
+
+#[dbus_interface(name = "org.opensuse.Agama1.Progress")]
+impl Progress {
+    #[dbus_interface(property)]
+    fn current_step(&self) -> (u32, String) {
+        self.current_step.clone()
+    }
+
+    #[dbus_interface(property)]
+    fn total_steps(&self) -> u32 {
+        3
+    }
+
+    #[dbus_interface(property)]
+    fn finished(&self) -> bool {
+        false
+    }
+}
+
+// Manager whose probing starts but never finishes.
+struct Manager {
+    probed: Arc<AtomicBool>,
+}
+
+#[dbus_interface(name = "org.opensuse.Agama.Manager1")]
+impl Manager {
+    async fn probe(&self, #[zbus(connection)] connection: &Connection) -> zbus::fdo::Result<()> {
+        self.probed.store(true, Ordering::SeqCst);
+        let server = connection.object_server();
+
+        let status = server.interface::<_, ServiceStatus>(MANAGER_PATH).await?;
+        status.get_mut().await.current = 1;
+        status
+            .get()
+            .await
+            .current_changed(status.signal_context())
+            .await?;
+
+        let progress = server.interface::<_, Progress>(MANAGER_PATH).await?;
+        progress.get_mut().await.current_step = (1, "Probing storage".to_string());
+        progress
+            .get()
+            .await
+            .current_step_changed(progress.signal_context())
+            .await?;
+        Ok(())
+    }
+}
+
+// Serves the mocked manager and returns a client for it.
+async fn manager_client(
+    connection: &Connection,
+    status: u32,
+    probed: Arc<AtomicBool>,
+) -> Result<ManagerClient<'static>, Box<dyn Error>> {
+    let server = connection.object_server();
+    server
+        .at(MANAGER_PATH, ServiceStatus { current: status })
+        .await?;
+    server.at(MANAGER_PATH, Progress::default()).await?;
+    server.at(MANAGER_PATH, Manager { probed }).await?;
+    connection.request_name(MANAGER_SERVICE).await?;
+    Ok(ManagerClient::new(connection.clone()).await?)
+}
+
+#[test]
+async fn test_probe_cancelled_while_busy() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let probed = Arc::new(AtomicBool::new(false));
+    let client = manager_client(&dbus_server.connection(), 1, Arc::clone(&probed)).await?;
+
+    let cancel = CancellationToken::new();
+    let token = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        token.cancel();
+    });
+
+    let result = timeout(
+        Duration::from_secs(5),
+        client.probe_with_cancel(|_| {}, cancel),
+    )
+    .await?;
+    assert!(matches!(
+        result,
+        Err(ServiceError::OperationCancelled("probing"))
+    ));
+    assert!(!probed.load(Ordering::SeqCst));
+    Ok(())
+}
+
+#[test]
+async fn test_probe_cancelled_during_progress() -> Result<(), Box<dyn Error>> {
+    let dbus_server = DBusServer::new().start().await?;
+    let probed = Arc::new(AtomicBool::new(false));
+    let client = manager_client(&dbus_server.connection(), 0, Arc::clone(&probed)).await?;
+
+    let cancel = CancellationToken::new();
+    let token = cancel.clone();
+    let updates = AtomicU32::new(0);
+    let on_progress = |_: &agama_lib::progress::Progress| {
+        updates.fetch_add(1, Ordering::SeqCst);
+        token.cancel();
+    };
+
+    let result = timeout(
+        Duration::from_secs(5),
+        client.probe_with_cancel(on_progress, cancel),
+    )
+    .await?;
+    assert!(matches!(
+        result,
+        Err(ServiceError::OperationCancelled("probing"))
+    ));
+    assert!(probed.load(Ordering::SeqCst));
+    assert_eq!(updates.load(Ordering::SeqCst), 1);
+    Ok(())
+}
@@ -46,6 +46,7 @@ async fn protected() -> String {
 async fn access_protected_route(token: &str, jwt_secret: &str) -> Response {
     let config = ServiceConfig {
         jwt_secret: jwt_secret.to_string(),
+        ..Default::default()
     };
     let (tx, _) = channel(16);
     let web_service = MainServiceBuilder::new(tx, public_dir())